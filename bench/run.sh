@@ -0,0 +1,11 @@
+#!/bin/sh
+# Run every benchmark with an rlox binary, each prints its result and the seconds taken.
+# To measure a change, run once with a build from before and once with a build from after:
+#   bench/run.sh /path/to/old/rlox
+#   bench/run.sh target/release/rlox
+RLOX=${1:-target/release/rlox}
+BENCH=$(dirname "$0")
+for script in "$BENCH"/*.lox; do
+    echo "== $script"
+    "$RLOX" "$script" || exit 1
+done
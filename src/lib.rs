@@ -6,11 +6,15 @@ mod lox;
 mod native;
 
 use lox::{Compiler, VM};
+use lox::common::ByteCode;
 
 pub enum Mode {
     Repl,
     Line,
     File,
+    Compile,
+    ByteCode,
+    Disassemble,
 }
 
 
@@ -18,6 +22,7 @@ pub struct Config {
     pub mode: Mode,
     pub line: Option<String>,
     pub filename: Option<String>,
+    pub output: Option<String>,
 }
 
 
@@ -27,6 +32,7 @@ impl Config {
         let mut mode = Mode::Repl;
         let mut line = None;
         let mut filename = None;
+        let mut output = None;
         
         if args.len() == 2 {
             mode = Mode::File;
@@ -38,7 +44,23 @@ impl Config {
             line = Some(args[2].clone());
         }
 
-        Ok(Config { mode, line, filename })
+        if args.len() == 4 && args[1] == "-c" {
+            mode = Mode::Compile;
+            filename = Some(args[2].clone());
+            output = Some(args[3].clone());
+        }
+
+        if args.len() == 3 && args[1] == "-r" {
+            mode = Mode::ByteCode;
+            filename = Some(args[2].clone());
+        }
+
+        if args.len() == 3 && args[1] == "--disassemble" {
+            mode = Mode::Disassemble;
+            filename = Some(args[2].clone());
+        }
+
+        Ok(Config { mode, line, filename, output })
         
     }
 }
@@ -50,6 +72,39 @@ fn register_natives(vm: &mut VM) {
     vm.native_callables().insert_method("push", native::push, 1);
     vm.native_callables().insert_method("shift", native::shift, 0);
     vm.native_callables().insert_method("unshift", native::unshift, 1);
+    vm.native_callables().insert_method("bytes", native::bytes, 0);
+    vm.native_callables().insert_method("chars", native::chars, 0);
+    vm.native_callables().insert_method("clear", native::clear, 0);
+    vm.native_callables().insert_method("concat", native::concat, 1);
+    vm.native_callables().insert_method("contains", native::contains, 1);
+    vm.native_callables().insert_method("delete", native::delete, 1);
+    vm.native_callables().insert_method("ends_with", native::ends_with, 1);
+    vm.native_callables().insert_method("fill", native::fill, 1);
+    vm.native_callables().insert_method("find", native::index_of, 1);
+    vm.native_callables().insert_method("format", native::format, 1);
+    vm.native_callables().insert_method("has", native::has, 1);
+    vm.native_callables().insert_method("index_of", native::index_of, 1);
+    vm.native_callables().insert_method("insert", native::insert, 2);
+    vm.native_callables().insert_method("join", native::join, 1);
+    vm.native_callables().insert_method("keys", native::keys, 0);
+    vm.native_callables().insert_method("remove", native::remove, 1);
+    vm.native_callables().insert_method("repeat", native::repeat, 1);
+    vm.native_callables().insert_method("replace", native::replace, 2);
+    vm.native_callables().insert_method("reverse", native::reverse, 0);
+    vm.native_callables().insert_method("slice", native::slice, 2);
+    vm.native_callables().insert_method("sort", native::sort, 0);
+    vm.native_callables().insert_method("split", native::split, 1);
+    vm.native_callables().insert_method("starts_with", native::starts_with, 1);
+    vm.native_callables().insert_method("substring", native::substring, 2);
+    vm.native_callables().insert_method("to_lower", native::to_lower, 0);
+    vm.native_callables().insert_method("to_number", native::to_number, 0);
+    vm.native_callables().insert_method("to_upper", native::to_upper, 0);
+    vm.native_callables().insert_method("trim", native::trim, 0);
+    vm.native_callables().insert_method("values", native::values, 0);
+    vm.native_callables().insert_context_method("filter", Box::new(native::filter), 1);
+    vm.native_callables().insert_context_method("map", Box::new(native::map), 1);
+    vm.native_callables().insert_function("clock", native::clock, 0);
+    vm.native_callables().insert_value("math", native::math());
 }
 
 
@@ -60,12 +115,22 @@ pub fn run(config: Config) -> Result<(), std::io::Error> {
 
     match config.mode {
         Mode::Repl => {
-            loop {
-                println!("Interactive mode (Enter 'exit' or hit Ctrl+C when done)");
-                let line = read_stdin()?;
-                if line == "exit" { break; }
-                let reader = std::io::Cursor::new(&line);
-                compile_and_execute("INPUT", reader, &mut vm, |rc| info!("rc={}", rc));
+            println!("Interactive mode (Enter 'exit' or hit Ctrl+C when done)");
+            // One compiler for the whole session so globals carry over between entries
+            let mut compiler = Compiler::with_natives(vm.native_callables());
+            let mut source = String::new();
+            while let Some(line) = read_stdin()? {
+                if source.is_empty() && line == "exit" { break; }
+                source = source + &line + "\n";
+                if !compiler.is_complete(&source) { continue; } // Keep reading until brackets balance
+                let reader = std::io::Cursor::new(std::mem::take(&mut source));
+                match compiler.compile_incremental("INPUT", reader) {
+                    Ok(bytecode) => match vm.execute_incremental(&bytecode) {
+                        Ok(rc) => info!("rc={}", rc),
+                        Err(runtime_error) => eprintln!("{}\n{}", runtime_error, runtime_error.get_stack_trace().join("\n")),
+                    }
+                    Err(compile_errors) => report_compile_errors(&compile_errors),
+                }
             }
         }
         Mode::Line => {
@@ -79,6 +144,45 @@ pub fn run(config: Config) -> Result<(), std::io::Error> {
             let reader = std::io::BufReader::new(file);
             compile_and_execute(&filename, reader, &mut vm, |rc| std::process::exit(rc));
         }
+        Mode::Compile => {
+            let filename = config.filename.unwrap();
+            let file = std::fs::File::open(&filename)?;
+            let reader = std::io::BufReader::new(file);
+            let output = config.output.unwrap();
+            // The errors have been reported, but the exit code must tell a build that nothing was written
+            let bytecode = match compile(&filename, reader, &mut vm) {
+                Some(bytecode) => bytecode,
+                None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Compiling '{}' failed, '{}' not written", filename, output))),
+            };
+            let file = std::fs::File::create(output)?;
+            let mut writer = std::io::BufWriter::new(file);
+            bytecode.write_to(&mut writer)?;
+        }
+        Mode::ByteCode => {
+            let filename = config.filename.unwrap();
+            let file = std::fs::File::open(&filename)?;
+            let mut reader = std::io::BufReader::new(file);
+            let bytecode = ByteCode::read_from(&mut reader)?;
+            // Code that fails verification is a load failure like a corrupt file
+            if let Err(verify_error) = bytecode.verify() {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, verify_error.to_string()));
+            }
+            execute(&bytecode, &mut vm, |rc| std::process::exit(rc));
+        }
+        Mode::Disassemble => {
+            // Precompiled files are listed as they are, anything else is compiled first
+            let filename = config.filename.unwrap();
+            let file = std::fs::File::open(&filename)?;
+            let mut reader = std::io::BufReader::new(file);
+            let bytecode = if ByteCode::is_precompiled(&mut reader)? {
+                Some(ByteCode::read_from(&mut reader)?)
+            } else {
+                compile(&filename, reader, &mut vm)
+            };
+            if let Some(bytecode) = bytecode {
+                print!("{}", bytecode.disassemble());
+            }
+        }
     }
     
     Ok(())
@@ -90,29 +194,57 @@ where
     R: std::io::BufRead + std::io::Read, 
     F: FnOnce(i32),
 {
-    let builder = Compiler::new();
+    if let Some(bytecode) = compile(filename, input, vm) {
+        execute(&bytecode, vm, action);
+    }
+}
+
+
+fn compile<R>(filename: &str, input: R, vm: &mut lox::VM) -> Option<ByteCode>
+where
+    R: std::io::BufRead + std::io::Read, 
+{
+    let builder = Compiler::with_natives(vm.native_callables());
     match builder.compile(filename, input) {
-        Ok(bytecode) => {
-            match vm.execute(&bytecode) {
-                Ok(rc) => action(rc),
-                Err(runtime_error) => { 
-                    eprintln!("{}\n{}", runtime_error, runtime_error.get_stack_trace().join("\n"));
-                }
-            }
+        Ok(bytecode) => return Some(bytecode),
+        Err(compile_errors) => {
+            report_compile_errors(&compile_errors);
+            return None;
         }
-        Err(compile_error) => {
-            eprintln!("{}", compile_error);
+    }
+}
+
+
+fn report_compile_errors(compile_errors: &[lox::CompileError]) {
+    for compile_error in compile_errors {
+        eprintln!("{}", compile_error);
+    }
+    if compile_errors.len() > 1 {
+        eprintln!("{} errors found", compile_errors.len());
+    }
+}
+
+
+fn execute<F>(bytecode: &ByteCode, vm: &mut lox::VM, action: F)
+where
+    F: FnOnce(i32),
+{
+    match vm.execute(bytecode) {
+        Ok(rc) => action(rc),
+        Err(runtime_error) => { 
+            eprintln!("{}\n{}", runtime_error, runtime_error.get_stack_trace().join("\n"));
         }
     }
 }
 
 
 // Called by run() if config.mode == Mode::Repl
-fn read_stdin() -> Result<String, std::io::Error> {
+// Returns None at end of input
+fn read_stdin() -> Result<Option<String>, std::io::Error> {
     let mut line = String::new();
 
-    std::io::stdin().read_line(&mut line)?;
+    if std::io::stdin().read_line(&mut line)? == 0 { return Ok(None); }
         
-    return Ok(line.trim().to_string());
+    return Ok(Some(line.trim().to_string()));
 }
 
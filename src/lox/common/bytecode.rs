@@ -1,8 +1,19 @@
 
 
+#[cfg(test)]
+mod test;
+
+
+mod disassembler;
+mod loxc;
+mod verifier;
+mod verify_error;
+
+
 use super::Function;
 use super::Globals;
 use super::Value;
+pub use verify_error::VerifyError;
 
 
 #[derive(Debug)]
@@ -31,4 +42,42 @@ impl ByteCode {
         return &self.globals_;
     }
 
-}
\ No newline at end of file
+
+    // Serialize to the precompiled .loxc format
+    pub fn write_to(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        return loxc::write(self, writer);
+    }
+
+
+    // Listing of all functions, for inspecting what the compiler emitted
+    pub fn disassemble(&self) -> String {
+        return disassembler::disassemble(self);
+    }
+
+
+    // Check that the code can not crash or corrupt the VM, e.g. after loading it from a file
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        return verifier::verify(self);
+    }
+
+
+    // Check if the input starts like a file written by write_to()
+    pub fn is_precompiled(reader: &mut impl std::io::BufRead) -> std::io::Result<bool> {
+        return loxc::is_precompiled(reader);
+    }
+
+
+    // Load precompiled code written by write_to()
+    pub fn read_from(reader: &mut impl std::io::Read) -> std::io::Result<ByteCode> {
+        return loxc::read(reader);
+    }
+
+}
+
+
+// Big-endian like Chunk::read_bytes(), but returns None instead of panicking past the end
+fn read_operand(code: &[u8], ip: &mut usize, len: usize) -> Option<usize> {
+    let bytes = code.get(*ip..*ip + len)?;
+    *ip = *ip + len;
+    return Some(bytes.iter().fold(0, |operand, byte| (operand << 8) | *byte as usize));
+}
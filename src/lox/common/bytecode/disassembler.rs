@@ -0,0 +1,207 @@
+
+
+use std::collections::HashSet;
+
+
+use crate::lox::common::{Function, Globals, OpCode, Value};
+use super::{ByteCode, read_operand};
+
+
+// Human readable listing of the main function followed by every function
+// and imported module reachable from its constants. Operands are resolved to
+// constant values and variable names where possible.
+pub fn disassemble(bytecode: &ByteCode) -> String {
+    let mut disassembler = Disassembler {
+        globals: bytecode.globals(),
+        modules: HashSet::new(),
+        output: String::new(),
+    };
+    disassembler.function(bytecode.main());
+    return disassembler.output;
+}
+
+
+struct Disassembler<'a> {
+    globals: &'a Globals<Value>,
+    modules: HashSet<String>, // Names of modules already listed
+    output: String,
+}
+
+
+impl<'a> Disassembler<'a> {
+
+    fn function(&mut self, function: &Function) {
+        self.output += &format!("== {} '{}', arity {}, {} upvalue(s) ==\n", function.kind().as_str(), function.name(), function.arity(), function.upvalue_count());
+        if let Some(at) = function.at() {
+            self.output += &format!("   at {}\n", at);
+        }
+
+        let code = function.read_chunk().code();
+        let mut ip = 0;
+        while ip < code.len() {
+            let (line, next) = self.instruction(function, ip);
+            self.output += line.trim_end();
+            self.output += "\n";
+            ip = next;
+        }
+        self.output += "\n";
+
+        for value in function.read_constants().as_slice() {
+            if value.is_function() {
+                self.function(&value.as_function());
+            }
+            if value.is_module() {
+                let module = value.as_module();
+                if self.modules.insert(module.name().to_string()) {
+                    self.function(&module.main().as_function());
+                }
+            }
+        }
+    }
+
+
+    // Return one line of text and the ip of the next instruction
+    fn instruction(&self, function: &Function, ip: usize) -> (String, usize) {
+        let code = function.read_chunk().code();
+        let opcode: OpCode = code[ip].into();
+        let mut next = ip + 1;
+
+        let operand = match read_operand(code, &mut next, opcode.len()) {
+            Some(operand) => operand,
+            None => return (format!("  0x{:04x}  {:<6} <truncated>", ip, opcode.mnemonic()), code.len()),
+        };
+        let operand_text = match opcode.len() {
+            1 => format!("0x{:02x}", operand),
+            2 => format!("0x{:04x}", operand),
+            4 => format!("0x{:08x}", operand),
+            _ => String::new(),
+        };
+        let mut comment = self.comment(function, ip as u32, opcode, operand);
+
+        // Capture is followed by one variable length entry per upvalue of the function
+        if opcode == OpCode::Capture8 || opcode == OpCode::Capture16 || opcode == OpCode::Capture32 {
+            let upvalue_count = match function.read_constants().as_slice().get(operand) {
+                Some(value) if value.is_function() => value.as_function().upvalue_count(),
+                _ => 0,
+            };
+            for _ in 0..upvalue_count {
+                let byte = match read_operand(code, &mut next, 1) {
+                    Some(byte) => byte,
+                    None => break,
+                };
+                let id = match read_operand(code, &mut next, byte & 127) {
+                    Some(id) => id,
+                    None => break,
+                };
+                if (byte & 128) == 128 {
+                    comment += &format!(", local 0x{:02x}", id);
+                    if let Some(name) = local_name(function, id, ip as u32) { comment += &format!(" {}", name); }
+                } else {
+                    comment += &format!(", upvalue 0x{:02x}", id);
+                }
+            }
+        }
+
+        // Invoke is followed by the argument count and an inline cache slot
+        if opcode == OpCode::Invoke8 || opcode == OpCode::Invoke16 || opcode == OpCode::Invoke32 {
+            if let (Some(arg_count), Some(slot)) = (read_operand(code, &mut next, 1), read_operand(code, &mut next, 2)) {
+                comment += &format!(", {} argument(s), cache 0x{:04x}", arg_count, slot);
+            }
+        }
+        // GetProperty only by the inline cache slot
+        if opcode == OpCode::GetProperty8 || opcode == OpCode::GetProperty16 || opcode == OpCode::GetProperty32 {
+            if let Some(slot) = read_operand(code, &mut next, 2) {
+                comment += &format!(", cache 0x{:04x}", slot);
+            }
+        }
+
+        let mut line = format!("  0x{:04x}  {:<6} {:<10}", ip, opcode.mnemonic(), operand_text);
+        if !comment.is_empty() {
+            line += &format!(" ; {}", comment);
+        }
+        return (line, next);
+    }
+
+
+    fn comment(&self, function: &Function, ip: u32, opcode: OpCode, operand: usize) -> String {
+        match opcode {
+            OpCode::GetConst8       |
+            OpCode::GetConst16      |
+            OpCode::GetConst32      |
+            OpCode::GetProperty8    |
+            OpCode::GetProperty16   |
+            OpCode::GetProperty32   |
+            OpCode::GetSuper8       |
+            OpCode::GetSuper16      |
+            OpCode::GetSuper32      |
+            OpCode::SetProperty8    |
+            OpCode::SetProperty16   |
+            OpCode::SetProperty32   |
+            OpCode::Capture8        |
+            OpCode::Capture16       |
+            OpCode::Capture32       |
+            OpCode::Class8          |
+            OpCode::Class16         |
+            OpCode::Class32         |
+            OpCode::Method8         |
+            OpCode::Method16        |
+            OpCode::Method32        |
+            OpCode::Import8         |
+            OpCode::Import16        |
+            OpCode::Import32        |
+            OpCode::Invoke8         |
+            OpCode::Invoke16        |
+            OpCode::Invoke32        => {
+                match function.read_constants().as_slice().get(operand) {
+                    Some(value) if value.is_string() => format!("'{}'", value),
+                    Some(value) if value.is_function() => format!("<fn {}>", value.as_function().name()),
+                    Some(value) if value.is_module() => format!("<module {}>", value),
+                    Some(value) => format!("{}", value),
+                    None => String::from("<bad constant>"),
+                }
+            }
+
+            OpCode::GetGlobal8      |
+            OpCode::GetGlobal16     |
+            OpCode::GetGlobal32     |
+            OpCode::DefGlobal8      |
+            OpCode::DefGlobal16     |
+            OpCode::DefGlobal32     |
+            OpCode::SetGlobal8      |
+            OpCode::SetGlobal16     |
+            OpCode::SetGlobal32     => {
+                if operand < self.globals.count() {
+                    self.globals.name_by_id(operand).to_string()
+                } else {
+                    String::from("<bad global>")
+                }
+            }
+
+            OpCode::GetLocal8       |
+            OpCode::GetLocal16      |
+            OpCode::GetLocal32      |
+            OpCode::SetLocal8       |
+            OpCode::SetLocal16      |
+            OpCode::SetLocal32      => {
+                local_name(function, operand, ip).unwrap_or("").to_string()
+            }
+
+            OpCode::Jmp             |
+            OpCode::JmpFalseP       |
+            OpCode::JmpFalseQ       |
+            OpCode::Try             => format!("-> 0x{:04x}", operand),
+
+            _ => String::new(),
+        }
+    }
+
+}
+
+
+fn local_name(function: &Function, slot: usize, ip: u32) -> Option<&str> {
+    if slot == 0 {
+        // Slot zero holds the receiver of methods or else the function itself
+        return if function.kind().has_receiver() { Some("this") } else { None };
+    }
+    return function.local_name(slot, ip);
+}
@@ -0,0 +1,454 @@
+
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Error, ErrorKind, Read, Write};
+use std::rc::Rc;
+
+
+use crate::lox::common::{Chunk, Function, FunctionKind, Globals, IdentifierKind, Location, Module, Value};
+use super::ByteCode;
+
+
+// Precompiled bytecode file format (.loxc), all integers big-endian:
+//   magic "LOXC", u16 version
+//   location table: u32 count, then (string filename, u32 lineno, u32 charno)
+//   main function
+//   globals: u32 count, then (string name, u8 kind, u8 defined) in id order
+// Global values are not stored; the VM binds native functions by name.
+// Functions refer to source locations by index into the table, 0 meaning none,
+// and keep the names of their local variables for the disassembler.
+
+const MAGIC: &[u8; 4] = b"LOXC";
+const VERSION: u16 = 4;
+
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_NUMBER: u8 = 3;
+const TAG_STRING: u8 = 4;
+const TAG_FUNCTION: u8 = 5;
+const TAG_MODULE: u8 = 6;
+const TAG_NAME: u8 = 7; // String constant naming a field or method
+
+// Limits on what a file may ask us to build, checked before anything is allocated
+const MAX_NESTING: usize = 256; // Functions within functions
+
+
+pub fn write(bytecode: &ByteCode, writer: &mut impl Write) -> std::io::Result<()> {
+    let mut output = Output::new();
+    output.function(bytecode.main())?;
+    let body = output.buffer;
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_be_bytes())?;
+    write_u32(writer, output.locations.len())?;
+    for location in &output.locations {
+        write_string(writer, location.file())?;
+        write_u32(writer, location.lineno() as usize)?;
+        write_u32(writer, location.charno() as usize)?;
+    }
+    writer.write_all(&body)?;
+
+    let globals = bytecode.globals();
+    write_u32(writer, globals.count())?;
+    for id in 0..globals.count() {
+        let global = globals.global_ref_by_id(id);
+        write_string(writer, globals.name_by_id(id))?;
+        write_u8(writer, match global.kind() {
+            IdentifierKind::Variable => 0,
+            IdentifierKind::Constant => 1,
+        })?;
+        write_u8(writer, global.is_defined() as u8)?;
+    }
+    return Ok(());
+}
+
+
+// Check the magic without consuming anything, so other files can be read as source code
+pub fn is_precompiled(reader: &mut impl BufRead) -> std::io::Result<bool> {
+    return Ok(reader.fill_buf()?.starts_with(MAGIC));
+}
+
+
+pub fn read(reader: &mut impl Read) -> std::io::Result<ByteCode> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("Not a precompiled Lox file"));
+    }
+    let version = read_u16(reader)?;
+    if version != VERSION {
+        return Err(invalid(&format!("Unsupported precompiled file version {}, expected {}", version, VERSION)));
+    }
+
+    let count = read_u32(reader)?;
+    let mut locations = vec![];
+    let mut files: HashMap<String, Rc<str>> = HashMap::new();
+    for _ in 0..count {
+        let filename = read_string(reader)?;
+        let file = files.entry(filename).or_insert_with_key(|filename| Rc::from(filename.as_str()));
+        let lineno = read_u32(reader)? as u32;
+        let charno = read_u32(reader)? as u32;
+        locations.push(Location::new(Rc::clone(file), lineno, charno));
+    }
+
+    let mut input = Input::new(reader, locations);
+    let main = input.function()?;
+
+    let mut globals = Globals::new();
+    let count = input.u32()?;
+    for id in 0..count {
+        let name = input.string()?;
+        let kind = match input.u8()? {
+            0 => IdentifierKind::Variable,
+            1 => IdentifierKind::Constant,
+            kind => return Err(invalid(&format!("Invalid identifier kind {}", kind))),
+        };
+        let defined = input.u8()? != 0;
+        match globals.declare(&name, kind) {
+            Ok(declared) if declared == id => {}
+            _ => return Err(invalid(&format!("Invalid global '{}'", name))),
+        }
+        if defined { globals.global_mutref_by_id(id).define(); }
+    }
+
+    return Ok(ByteCode::new(main, globals));
+}
+
+
+// Serializes functions into a buffer while collecting the locations they refer to
+struct Output {
+    buffer: Vec<u8>,
+    locations: Vec<Location>,
+    index: HashMap<Location, usize>, // Location => index+1
+    modules: HashSet<String>, // Modules already written in full
+}
+
+
+impl Output {
+
+    fn new() -> Self {
+        Output {
+            buffer: vec![],
+            locations: vec![],
+            index: HashMap::new(),
+            modules: HashSet::new(),
+        }
+    }
+
+
+    fn location(&mut self, location: Option<&Location>) -> usize {
+        let location = match location {
+            Some(location) => location,
+            None => return 0,
+        };
+        if let Some(index) = self.index.get(location) { return *index; }
+        self.locations.push(location.clone());
+        self.index.insert(location.clone(), self.locations.len());
+        return self.locations.len();
+    }
+
+
+    fn function(&mut self, function: &Function) -> std::io::Result<()> {
+        write_string(&mut self.buffer, function.name())?;
+        write_u8(&mut self.buffer, match function.kind() {
+            FunctionKind::Function => 0,
+            FunctionKind::Initializer => 1,
+            FunctionKind::Method => 2,
+            FunctionKind::Module => 3,
+            FunctionKind::Script => 4,
+        })?;
+        write_u8(&mut self.buffer, function.arity())?;
+        write_u32(&mut self.buffer, function.upvalue_count())?;
+        let location = self.location(function.at().as_ref());
+        write_u32(&mut self.buffer, location)?;
+
+        let chunk = function.read_chunk();
+        write_u32(&mut self.buffer, chunk.code().len())?;
+        self.buffer.write_all(chunk.code())?;
+        write_u32(&mut self.buffer, chunk.source_map().len())?;
+        for (ip, location) in chunk.source_map() {
+            write_u32(&mut self.buffer, *ip as usize)?;
+            let location = self.location(Some(location));
+            write_u32(&mut self.buffer, location)?;
+        }
+
+        let constants = function.read_constants();
+        write_u32(&mut self.buffer, constants.as_slice().len())?;
+        for (id, value) in constants.as_slice().iter().enumerate() {
+            if constants.symbol_by_id(id).is_some() {
+                write_u8(&mut self.buffer, TAG_NAME)?;
+                write_string(&mut self.buffer, &value.as_string())?;
+            } else {
+                self.value(value)?;
+            }
+        }
+
+        // Only used to explain local operands when disassembling
+        write_u32(&mut self.buffer, function.local_names().len())?;
+        for (ip, slot, name) in function.local_names() {
+            write_u32(&mut self.buffer, *ip as usize)?;
+            write_u32(&mut self.buffer, *slot)?;
+            write_string(&mut self.buffer, name)?;
+        }
+        return Ok(());
+    }
+
+
+    fn value(&mut self, value: &Value) -> std::io::Result<()> {
+        match value {
+            Value::Null => write_u8(&mut self.buffer, TAG_NULL),
+            Value::Bool(false) => write_u8(&mut self.buffer, TAG_FALSE),
+            Value::Bool(true) => write_u8(&mut self.buffer, TAG_TRUE),
+            Value::Number(n) => {
+                write_u8(&mut self.buffer, TAG_NUMBER)?;
+                self.buffer.write_all(&n.to_bits().to_be_bytes())
+            }
+            _ if value.is_string() => {
+                write_u8(&mut self.buffer, TAG_STRING)?;
+                write_string(&mut self.buffer, &value.as_string())
+            }
+            _ if value.is_function() => {
+                write_u8(&mut self.buffer, TAG_FUNCTION)?;
+                self.function(&value.as_function())
+            }
+            _ if value.is_module() => {
+                let module = value.as_module();
+                write_u8(&mut self.buffer, TAG_MODULE)?;
+                write_string(&mut self.buffer, module.name())?;
+                // A module imported from several places is written in full only once
+                if self.modules.contains(module.name()) {
+                    return write_u8(&mut self.buffer, 0);
+                }
+                self.modules.insert(module.name().to_string());
+                write_u8(&mut self.buffer, 1)?;
+                self.function(&module.main().as_function())?;
+                let mut exports: Vec<(&String, &usize)> = module.exports().iter().collect();
+                exports.sort();
+                write_u32(&mut self.buffer, exports.len())?;
+                for (name, id) in exports {
+                    write_string(&mut self.buffer, name)?;
+                    write_u32(&mut self.buffer, *id)?;
+                }
+                Ok(())
+            }
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Can not serialize constant {}", value))),
+        }
+    }
+
+}
+
+
+// Deserializes functions, resolving location indexes and shared modules
+struct Input<'a, R: Read> {
+    reader: &'a mut R,
+    locations: Vec<Location>,
+    modules: HashMap<String, Value>,
+    depth: usize, // Functions currently being read
+}
+
+
+impl<'a, R: Read> Input<'a, R> {
+
+    fn new(reader: &'a mut R, locations: Vec<Location>) -> Self {
+        Input {
+            reader,
+            locations,
+            modules: HashMap::new(),
+            depth: 0,
+        }
+    }
+
+
+    fn u8(&mut self) -> std::io::Result<u8> {
+        return read_u8(self.reader);
+    }
+
+
+    fn u32(&mut self) -> std::io::Result<usize> {
+        return read_u32(self.reader);
+    }
+
+
+    fn string(&mut self) -> std::io::Result<String> {
+        return read_string(self.reader);
+    }
+
+
+    fn location(&mut self) -> std::io::Result<Option<Location>> {
+        let index = self.u32()?;
+        if index == 0 { return Ok(None); }
+        match self.locations.get(index - 1) {
+            Some(location) => return Ok(Some(location.clone())),
+            None => return Err(invalid(&format!("Invalid location index {}", index))),
+        }
+    }
+
+
+    fn function(&mut self) -> std::io::Result<Function> {
+        if self.depth == MAX_NESTING {
+            return Err(invalid(&format!("Functions nested more than {} deep", MAX_NESTING)));
+        }
+        self.depth = self.depth + 1;
+        let function = self.function_body();
+        self.depth = self.depth - 1;
+        return function;
+    }
+
+
+    fn function_body(&mut self) -> std::io::Result<Function> {
+        let name = self.string()?;
+        let kind = match self.u8()? {
+            0 => FunctionKind::Function,
+            1 => FunctionKind::Initializer,
+            2 => FunctionKind::Method,
+            3 => FunctionKind::Module,
+            4 => FunctionKind::Script,
+            kind => return Err(invalid(&format!("Invalid function kind {}", kind))),
+        };
+        let arity = self.u8()?;
+        let upvalue_count = self.u32()?;
+        let at = self.location()?;
+
+        let mut function = Function::new(&name, kind, at);
+        function.set_arity(arity);
+        function.set_upvalue_count(upvalue_count);
+
+        let length = self.u32()?;
+        let code = read_bytes(self.reader, length)?;
+        let count = self.u32()?;
+        let mut source_map = vec![];
+        for _ in 0..count {
+            let ip = self.u32()? as u32;
+            match self.location()? {
+                Some(location) => source_map.push((ip, location)),
+                None => return Err(invalid("Source map entry without a location")),
+            }
+        }
+        *function.chunk() = Chunk::with_source_map(code, source_map);
+
+        let count = self.u32()?;
+        for _ in 0..count {
+            match self.u8()? {
+                TAG_NAME => { function.constants().push_name(&self.string()?); }
+                tag => { function.constants().push(self.value(tag)?); }
+            }
+        }
+
+        let count = self.u32()?;
+        for _ in 0..count {
+            let ip = self.u32()? as u32;
+            let slot = self.u32()?;
+            function.name_local(ip, slot, &self.string()?);
+        }
+        return Ok(function);
+    }
+
+
+    fn value(&mut self, tag: u8) -> std::io::Result<Value> {
+        match tag {
+            TAG_NULL => return Ok(Value::null()),
+            TAG_FALSE => return Ok(Value::boolean(false)),
+            TAG_TRUE => return Ok(Value::boolean(true)),
+            TAG_NUMBER => {
+                let mut bytes = [0u8; 8];
+                self.reader.read_exact(&mut bytes)?;
+                return Ok(Value::number(f64::from_bits(u64::from_be_bytes(bytes))));
+            }
+            TAG_STRING => return Ok(Value::string(&self.string()?)),
+            TAG_FUNCTION => return Ok(Value::function(self.function()?)),
+            TAG_MODULE => {
+                let name = self.string()?;
+                if self.u8()? == 0 {
+                    match self.modules.get(&name) {
+                        Some(module) => return Ok(module.clone()),
+                        None => return Err(invalid(&format!("Module '{}' used before it was defined", name))),
+                    }
+                }
+                let main = Value::function(self.function()?);
+                let mut exports = HashMap::new();
+                let count = self.u32()?;
+                for _ in 0..count {
+                    let export = self.string()?;
+                    let id = self.u32()?;
+                    exports.insert(export, id);
+                }
+                let module = Value::module(Module::new(&name, main, exports));
+                // The VM imports modules by name, so a second body would run in place of the first
+                if self.modules.insert(name.clone(), module.clone()).is_some() {
+                    return Err(invalid(&format!("Module '{}' defined more than once", name)));
+                }
+                return Ok(module);
+            }
+            tag => return Err(invalid(&format!("Invalid constant tag {}", tag))),
+        }
+    }
+
+}
+
+
+fn invalid(message: &str) -> Error {
+    return Error::new(ErrorKind::InvalidData, message);
+}
+
+
+fn write_u8(writer: &mut impl Write, value: u8) -> std::io::Result<()> {
+    return writer.write_all(&[value]);
+}
+
+
+fn write_u32(writer: &mut impl Write, value: usize) -> std::io::Result<()> {
+    if value > u32::MAX as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Value {} does not fit in 32 bits", value)));
+    }
+    return writer.write_all(&(value as u32).to_be_bytes());
+}
+
+
+fn write_string(writer: &mut impl Write, value: &str) -> std::io::Result<()> {
+    write_u32(writer, value.len())?;
+    return writer.write_all(value.as_bytes());
+}
+
+
+fn read_u8(reader: &mut impl Read) -> std::io::Result<u8> {
+    let mut bytes = [0u8; 1];
+    reader.read_exact(&mut bytes)?;
+    return Ok(bytes[0]);
+}
+
+
+fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    return Ok(u16::from_be_bytes(bytes));
+}
+
+
+fn read_u32(reader: &mut impl Read) -> std::io::Result<usize> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    return Ok(u32::from_be_bytes(bytes) as usize);
+}
+
+
+// Read a length taken from the file, growing the buffer only as data
+// arrives so a corrupt length can not make us allocate more than the file holds
+fn read_bytes(reader: &mut impl Read, length: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    reader.by_ref().take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < length {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Unexpected end of file"));
+    }
+    return Ok(bytes);
+}
+
+
+fn read_string(reader: &mut impl Read) -> std::io::Result<String> {
+    let length = read_u32(reader)?;
+    let bytes = read_bytes(reader, length)?;
+    match String::from_utf8(bytes) {
+        Ok(string) => return Ok(string),
+        Err(_) => return Err(invalid("Invalid UTF-8 in string")),
+    }
+}
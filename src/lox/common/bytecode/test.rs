@@ -0,0 +1,475 @@
+
+
+use std::collections::HashMap;
+
+
+use crate::lox::{Compiler, RuntimeError, VM};
+use crate::lox::common::{Function, FunctionKind, Globals, IdentifierKind, Module, OpCode, Value};
+use super::{ByteCode, VerifyError};
+
+
+fn compile(code: &str) -> ByteCode {
+    let builder = Compiler::new();
+    let reader = std::io::Cursor::new(code);
+    match builder.compile("test", reader) {
+        Ok(bytecode) => return bytecode,
+        Err(errors) => panic!("Compile failed: {:?}", errors),
+    }
+}
+
+
+fn round_trip(bytecode: &ByteCode) -> ByteCode {
+    let mut buffer = vec![];
+    bytecode.write_to(&mut buffer).expect("write_to() failed");
+    return ByteCode::read_from(&mut buffer.as_slice()).expect("read_from() failed");
+}
+
+
+// Build a script from raw bytes, as if loaded from an untrusted file
+fn hand_written(code: &[u8], constants: Vec<Value>) -> ByteCode {
+    let mut function = Function::new("test", FunctionKind::Script, None);
+    for byte in code { function.chunk().append_bytes(*byte as u32, 1); }
+    for value in constants { function.constants().push(value); }
+    return ByteCode::new(function, Globals::new());
+}
+
+
+fn verify_error(bytecode: &ByteCode) -> VerifyError {
+    match bytecode.verify() {
+        Ok(()) => panic!("Unexpectedly passed verification"),
+        Err(error) => return error,
+    }
+}
+
+
+fn execute(bytecode: &ByteCode) -> Result<i32, RuntimeError> {
+    return VM::new().execute(bytecode);
+}
+
+
+#[test]
+fn round_trip_exit() {
+    let bytecode = round_trip(&compile("exit 3;"));
+    assert_eq!(execute(&bytecode).unwrap(), 3);
+}
+
+#[test]
+fn round_trip_constants() {
+    let code = "var s = 'abc'; var n = 1.5; var t = true; var f = false; var x = null; exit s == 'abc' && n == 1.5 && t && !f && x == null;";
+    let bytecode = round_trip(&compile(code));
+    assert_eq!(execute(&bytecode).unwrap(), 1);
+}
+
+#[test]
+fn round_trip_functions() {
+    let code = "fun add(a, b) { return a + b; } fun make() { var n = 40; fun f() { return n + 2; } return f; } exit add(make()(), 0);";
+    let bytecode = round_trip(&compile(code));
+    assert_eq!(execute(&bytecode).unwrap(), 42);
+}
+
+#[test]
+fn round_trip_classes() {
+    let code = "class A { init(x) { this.x = x; } get() { return this.x; } } class B of A { get() { return super.get() * 2; } } exit B(21).get();";
+    let bytecode = round_trip(&compile(code));
+    assert_eq!(execute(&bytecode).unwrap(), 42);
+}
+
+#[test]
+fn round_trip_modules() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; import 'src/lox/vm/test/testlib/nested.lox' as n; g.bump(); exit g.greet('world') == 'Hello, world' && g.counter == 1;";
+    let bytecode = round_trip(&compile(code));
+    assert_eq!(execute(&bytecode).unwrap(), 1);
+}
+
+#[test]
+fn round_trip_globals() {
+    let bytecode = compile("var a = 1; const b = 2;");
+    let loaded = round_trip(&bytecode);
+    assert_eq!(loaded.globals().count(), bytecode.globals().count());
+    for name in &["a", "b"] {
+        assert_eq!(loaded.globals().id_by_name(name), bytecode.globals().id_by_name(name));
+    }
+}
+
+#[test]
+fn round_trip_stack_trace() {
+    let code = "fun f() {\n  return 0.invalid;\n}\nf();";
+    let bytecode = compile(code);
+    let original = execute(&bytecode).unwrap_err();
+    let loaded = execute(&round_trip(&bytecode)).unwrap_err();
+    assert_eq!(loaded.get_message(), original.get_message());
+    assert_eq!(loaded.get_stack_trace(), original.get_stack_trace());
+}
+
+#[test]
+fn is_precompiled() {
+    let mut buffer = vec![];
+    compile("exit 3;").write_to(&mut buffer).unwrap();
+    assert_eq!(ByteCode::is_precompiled(&mut buffer.as_slice()).unwrap(), true);
+    assert_eq!(ByteCode::is_precompiled(&mut &b"exit 3;"[..]).unwrap(), false);
+    assert_eq!(ByteCode::is_precompiled(&mut &b""[..]).unwrap(), false);
+}
+
+#[test]
+fn bad_magic() {
+    let data = b"LOXX\x00\x01";
+    let res = ByteCode::read_from(&mut &data[..]);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().to_string(), "Not a precompiled Lox file");
+}
+
+#[test]
+fn bad_version() {
+    let data = b"LOXC\x00\x63";
+    let res = ByteCode::read_from(&mut &data[..]);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().to_string(), "Unsupported precompiled file version 99, expected 4");
+}
+
+#[test]
+fn truncated() {
+    let mut buffer = vec![];
+    compile("exit 3;").write_to(&mut buffer).unwrap();
+    buffer.truncate(buffer.len() - 1);
+    assert_eq!(ByteCode::read_from(&mut buffer.as_slice()).is_err(), true);
+}
+
+#[test]
+fn huge_string_length() {
+    // Function name claims to be 4 GB long
+    let data = b"LOXC\x00\x01\x00\x00\x00\x00\xff\xff\xff\xff";
+    let res = ByteCode::read_from(&mut &data[..]);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn huge_code_length() {
+    // Empty name, kind, arity, upvalues, location, then 4 GB of code
+    let data = b"LOXC\x00\x01\x00\x00\x00\x00\x00\x00\x00\x00\x04\x00\x00\x00\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff";
+    let res = ByteCode::read_from(&mut &data[..]);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn huge_location() {
+    // One location at line 4294967295 of file "f"
+    let data = b"LOXC\x00\x01\x00\x00\x00\x01\x00\x00\x00\x01f\xff\xff\xff\xff\x00\x00\x00\x01";
+    let res = ByteCode::read_from(&mut &data[..]);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn deeply_nested_functions() {
+    let mut data = b"LOXC\x00\x01\x00\x00\x00\x00".to_vec();
+    for _ in 0..10000 {
+        // Empty name, kind, arity, upvalues, location, code, source map, then one function constant
+        data.extend_from_slice(b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x05");
+    }
+    let res = ByteCode::read_from(&mut data.as_slice());
+    assert_eq!(res.is_err(), true);
+}
+
+
+// Verifier
+
+#[test]
+fn verify_compiled() {
+    let code = "
+        var g = 1;
+        fun outer(a, b) { var c = a; fun inner() { return c + b; } return inner; }
+        class A { init(x) { this.x = x; } get() { return this.x; } }
+        class B of A { get() { return super.get() * 2; } }
+        var m = {'k': [1, 2, 3]};
+        m['k'][0] = g > 0 ? 2 : 3;
+        for var v in m['k'] { if (v == 2 && g) continue; g = g + v; }
+        var i = 0;
+        while (i < 3) { var j = i; fun f() { return j; } i = i + 1; if (f() == 1) break; }
+        try { throw B(1); } catch (e) { g = e.get(); } finally { g = g + 1; }
+        exit outer(g, 1)();
+    ";
+    let bytecode = compile(code);
+    assert_eq!(bytecode.verify().is_ok(), true);
+    assert_eq!(round_trip(&bytecode).verify().is_ok(), true);
+}
+
+#[test]
+fn verify_compiled_modules() {
+    let bytecode = compile("import 'src/lox/vm/test/testlib/nested.lox' as n; exit n.hello() == 'Hello, nested';");
+    assert_eq!(bytecode.verify().is_ok(), true);
+}
+
+#[test]
+fn verify_hand_written() {
+    let bytecode = hand_written(&[OpCode::Null.as_byte(), OpCode::Exit.as_byte()], vec![]);
+    assert_eq!(bytecode.verify().is_ok(), true);
+}
+
+#[test]
+fn verify_invalid_opcode() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte(), 0xfe], vec![]));
+    assert_eq!(error.get_message(), "Invalid opcode 0xfe");
+    assert_eq!(error.get_function(), "test");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_truncated_operand() {
+    let error = verify_error(&hand_written(&[OpCode::GetConst16.as_byte(), 0x00], vec![]));
+    assert_eq!(error.get_message(), "Truncated GETC instruction");
+    assert_eq!(error.get_ip(), Some(0));
+}
+
+#[test]
+fn verify_truncated_invoke() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte(), OpCode::Invoke8.as_byte(), 0x00, 0x00], vec![Value::string("m")]));
+    assert_eq!(error.get_message(), "Truncated INVK instruction");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_truncated_getproperty() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte(), OpCode::GetProperty8.as_byte(), 0, 0], vec![Value::string("x")]));
+    assert_eq!(error.get_message(), "Truncated GETP instruction");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_constant_out_of_range() {
+    let error = verify_error(&hand_written(&[OpCode::GetConst8.as_byte(), 1, OpCode::Exit.as_byte()], vec![Value::number(1.0)]));
+    assert_eq!(error.get_message(), "Constant 0x0001 out of range, 1 defined");
+}
+
+#[test]
+fn verify_constant_wrong_type() {
+    let error = verify_error(&hand_written(&[OpCode::Class8.as_byte(), 0, OpCode::Exit.as_byte()], vec![Value::number(1.0)]));
+    assert_eq!(error.get_message(), "Constant 0x0000 is not a string");
+}
+
+#[test]
+fn verify_constant_not_a_name() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte(), OpCode::GetProperty8.as_byte(), 0, 0, 0, OpCode::Exit.as_byte()], vec![Value::string("x")]));
+    assert_eq!(error.get_message(), "Constant 0x0000 is not a name");
+}
+
+#[test]
+fn verify_global_out_of_range() {
+    let error = verify_error(&hand_written(&[OpCode::GetGlobal8.as_byte(), 3, OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Global 0x0003 out of range, 0 declared");
+}
+
+#[test]
+fn verify_tuck_zero() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte(), OpCode::Tuck.as_byte(), 0, OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Invalid TUCK count 0");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_upvalue_out_of_range() {
+    let error = verify_error(&hand_written(&[OpCode::GetUpvalue8.as_byte(), 0, OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Upvalue 0x0000 out of range, 0 captured");
+}
+
+#[test]
+fn verify_local_out_of_range() {
+    let error = verify_error(&hand_written(&[OpCode::GetLocal8.as_byte(), 1, OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Local 0x0001 out of range, stack depth is 1");
+}
+
+#[test]
+fn verify_jump_into_operand() {
+    let error = verify_error(&hand_written(&[OpCode::Jmp.as_byte(), 0, 0, 0, 2, OpCode::Null.as_byte(), OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Jump to 0x00000002 is not an instruction");
+    assert_eq!(error.get_ip(), Some(0));
+}
+
+#[test]
+fn verify_stack_underflow() {
+    let error = verify_error(&hand_written(&[OpCode::Pop.as_byte(), OpCode::Pop.as_byte(), OpCode::Null.as_byte(), OpCode::Exit.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Stack underflow, POP needs 1 value(s) but depth is 0");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_inconsistent_depth() {
+    // The branch that falls through pushes one more value than the one that jumps
+    let code = [OpCode::True.as_byte(), OpCode::JmpFalseP.as_byte(), 0, 0, 0, 7, OpCode::Null.as_byte(), OpCode::Exit.as_byte()];
+    let error = verify_error(&hand_written(&code, vec![]));
+    assert_eq!(error.get_message().starts_with("Inconsistent stack depth"), true);
+    assert_eq!(error.get_ip(), Some(7));
+}
+
+#[test]
+fn verify_runs_past_end() {
+    let error = verify_error(&hand_written(&[OpCode::Null.as_byte()], vec![]));
+    assert_eq!(error.get_message(), "Execution runs past the end of the code");
+}
+
+#[test]
+fn verify_nested_function() {
+    let mut inner = Function::new("inner", FunctionKind::Function, None);
+    inner.chunk().append_bytes(OpCode::Pop.as_byte() as u32, 1);
+    inner.chunk().append_bytes(OpCode::Return.as_byte() as u32, 1);
+    let code = [OpCode::Capture8.as_byte(), 0, OpCode::Exit.as_byte()];
+    let error = verify_error(&hand_written(&code, vec![Value::function(inner)]));
+    assert_eq!(error.get_message(), "Stack underflow, RET needs 1 value(s) but depth is 0");
+    assert_eq!(error.get_function(), "inner");
+    assert_eq!(error.get_ip(), Some(1));
+}
+
+#[test]
+fn verify_export_out_of_range() {
+    let mut main = Function::new("lib", FunctionKind::Script, None);
+    main.chunk().append_bytes(OpCode::Null.as_byte() as u32, 1);
+    main.chunk().append_bytes(OpCode::Return.as_byte() as u32, 1);
+    let exports = HashMap::from([(String::from("f"), 5)]);
+    let module = Value::module(Module::new("lib", Value::function(main), exports));
+    let code = [OpCode::Import8.as_byte(), 0, OpCode::Null.as_byte(), OpCode::Exit.as_byte()];
+    let error = verify_error(&hand_written(&code, vec![module]));
+    assert_eq!(error.get_message(), "Export 'f' of module 'lib' is global 0x0005, out of range, 0 declared");
+}
+
+#[test]
+fn verify_same_named_modules() {
+    // A valid module reached first must not let a different one by the same name through
+    let module = |code: &[OpCode]| {
+        let mut main = Function::new("m", FunctionKind::Module, None);
+        for opcode in code { main.chunk().append_bytes(opcode.as_byte() as u32, 1); }
+        Value::module(Module::new("m", Value::function(main), HashMap::new()))
+    };
+    let mut inner = Function::new("inner", FunctionKind::Function, None);
+    inner.constants().push(module(&[OpCode::Null, OpCode::Return]));
+    inner.chunk().append_bytes(OpCode::Null.as_byte() as u32, 1);
+    inner.chunk().append_bytes(OpCode::Return.as_byte() as u32, 1);
+    let code = [OpCode::Import8.as_byte(), 1, OpCode::Null.as_byte(), OpCode::Exit.as_byte()];
+    let error = verify_error(&hand_written(&code, vec![Value::function(inner), module(&[OpCode::Pop, OpCode::Return])]));
+    assert_eq!(error.get_message(), "Stack underflow, POP needs 1 value(s) but depth is 0");
+    assert_eq!(error.get_function(), "m");
+}
+
+#[test]
+fn same_named_modules() {
+    let mut lib1 = Function::new("lib1", FunctionKind::Module, None);
+    lib1.chunk().append_bytes(OpCode::Null.as_byte() as u32, 1);
+    lib1.chunk().append_bytes(OpCode::Return.as_byte() as u32, 1);
+    let mut lib2 = Function::new("lib2", FunctionKind::Module, None);
+    lib2.chunk().append_bytes(OpCode::Null.as_byte() as u32, 1);
+    lib2.chunk().append_bytes(OpCode::Return.as_byte() as u32, 1);
+    let constants = vec![
+        Value::module(Module::new("lib1", Value::function(lib1), HashMap::new())),
+        Value::module(Module::new("lib2", Value::function(lib2), HashMap::new())),
+    ];
+    let mut buffer = vec![];
+    hand_written(&[OpCode::Null.as_byte(), OpCode::Exit.as_byte()], constants).write_to(&mut buffer).unwrap();
+    // Rename the second module so both are written in full under one name
+    for i in 0..buffer.len() - 3 {
+        if &buffer[i..i + 4] == b"lib2" { buffer[i + 3] = b'1'; }
+    }
+    let res = ByteCode::read_from(&mut buffer.as_slice());
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().to_string(), "Module 'lib1' defined more than once");
+}
+
+#[test]
+fn undefined_global() {
+    // Passes verification since the id is declared, but nothing defines it
+    let mut function = Function::new("test", FunctionKind::Script, None);
+    for byte in [OpCode::GetGlobal8.as_byte(), 0, OpCode::Exit.as_byte()] { function.chunk().append_bytes(byte as u32, 1); }
+    let mut globals = Globals::new();
+    globals.declare("g", IdentifierKind::Variable).unwrap();
+    let bytecode = ByteCode::new(function, globals);
+    assert_eq!(bytecode.verify().is_ok(), true);
+    let error = execute(&bytecode).expect_err("Expected an error");
+    assert_eq!(error.get_message(), "Global 'g' has not been defined yet");
+}
+
+// Disassembler
+
+fn has_line(listing: &str, parts: &[&str]) -> bool {
+    return listing.lines().any(|line| parts.iter().all(|part| line.contains(part)));
+}
+
+#[test]
+fn disassemble_globals() {
+    let listing = compile("var greeting = 'hi'; exit greeting == 'hi';").disassemble();
+    println!("{}", listing);
+    assert_eq!(listing.lines().next(), Some("== Script '__main__', arity 0, 0 upvalue(s) =="));
+    assert_eq!(has_line(&listing, &["GETC", "; 'hi'"]), true);
+    assert_eq!(has_line(&listing, &["DEFG", "; greeting"]), true);
+    assert_eq!(has_line(&listing, &["GETG", "; greeting"]), true);
+}
+
+#[test]
+fn disassemble_functions_and_locals() {
+    let listing = compile("fun add(a, b) { var c = a + b; return c; } exit add(1, 2);").disassemble();
+    println!("{}", listing);
+    assert_eq!(listing.contains("== Function 'add', arity 2, 0 upvalue(s) =="), true);
+    assert_eq!(has_line(&listing, &["GETL", "0x01", "; a"]), true);
+    assert_eq!(has_line(&listing, &["GETL", "0x02", "; b"]), true);
+    assert_eq!(has_line(&listing, &["GETL", "0x03", "; c"]), true);
+}
+
+#[test]
+fn disassemble_jump_targets() {
+    let listing = compile("var a = true; if (a) exit 1; exit 2;").disassemble();
+    println!("{}", listing);
+    let line = listing.lines().find(|line| line.contains("JFP")).expect("No JFP instruction");
+    let target = line.split("-> ").nth(1).expect("No jump target");
+    assert_eq!(listing.lines().any(|line| line.starts_with(&format!("  {}  ", target))), true);
+}
+
+#[test]
+fn disassemble_closures() {
+    let listing = compile("fun mk() { var x = 1; fun get() { return x; } return get; } exit mk()();").disassemble();
+    println!("{}", listing);
+    assert_eq!(listing.contains("== Function 'get', arity 0, 1 upvalue(s) =="), true);
+    assert_eq!(has_line(&listing, &["CAP", "; <fn get>, local 0x01 x"]), true);
+}
+
+#[test]
+fn disassemble_methods() {
+    let listing = compile("class A { m() { return this; } } exit 0;").disassemble();
+    println!("{}", listing);
+    assert_eq!(listing.contains("== Method 'm', arity 0, 0 upvalue(s) =="), true);
+    assert_eq!(has_line(&listing, &["GETL", "0x00", "; this"]), true);
+    assert_eq!(has_line(&listing, &["MTHD", "; 'm'"]), true);
+}
+
+#[test]
+fn disassemble_modules_once() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; import 'src/lox/vm/test/testlib/nested.lox' as n; exit 0;";
+    let listing = compile(code).disassemble();
+    println!("{}", listing);
+    assert_eq!(listing.matches("== Module 'src/lox/vm/test/testlib/greeter.lox'").count(), 1);
+    assert_eq!(has_line(&listing, &["IMPORT", "; <module src/lox/vm/test/testlib/nested.lox>"]), true);
+}
+
+#[test]
+fn disassemble_bad_code() {
+    let listing = hand_written(&[OpCode::Null.as_byte(), OpCode::GetConst16.as_byte(), 0x00], vec![]).disassemble();
+    println!("{}", listing);
+    assert_eq!(has_line(&listing, &["0x0001", "GETC", "<truncated>"]), true);
+}
+
+#[test]
+fn disassemble_getproperty() {
+    let listing = compile("class A { m() { return 1; } } var a = A(); var f = a.m; exit f();").disassemble();
+    println!("{}", listing);
+    assert_eq!(has_line(&listing, &["GETP", "; 'm', cache 0x0000"]), true);
+}
+
+#[test]
+fn disassemble_invoke() {
+    let listing = compile("class A { m(x) { return x; } } var a = A(); exit a.m(1);").disassemble();
+    println!("{}", listing);
+    assert_eq!(has_line(&listing, &["INVK", "; 'm', 1 argument(s), cache 0x0000"]), true);
+}
+
+#[test]
+fn disassemble_round_trip() {
+    let bytecode = compile("fun add(a, b) { var c = a + b; return c; } exit add(1, 2);");
+    let listing = round_trip(&bytecode).disassemble();
+    println!("{}", listing);
+    assert_eq!(has_line(&listing, &["GETL", "0x03", "; c"]), true);
+    assert_eq!(listing, bytecode.disassemble());
+}
@@ -0,0 +1,426 @@
+
+
+use std::collections::HashMap;
+
+
+use crate::lox::common::{Function, OpCode, Value};
+use super::{ByteCode, VerifyError, read_operand};
+
+
+// Checks bytecode that did not come straight from the compiler before the VM runs it.
+// The VM trusts its input completely and would panic on bad operands or jump targets,
+// or silently corrupt its stack if the stack depth does not add up.
+pub fn verify(bytecode: &ByteCode) -> Result<(), VerifyError> {
+    let mut verifier = Verifier {
+        globals: bytecode.globals().count(),
+        modules: vec![],
+    };
+    return verifier.function(bytecode.main());
+}
+
+
+struct Verifier {
+    globals: usize,
+    modules: Vec<Value>, // Modules already verified, compared by identity since names need not be unique
+}
+
+
+// A decoded instruction
+struct Instruction {
+    ip: u32,
+    opcode: OpCode,
+    operand: usize,
+    captures: Vec<(bool, usize)>, // (is_local, id) for each upvalue, Capture only
+    arg_count: usize, // Invoke only
+    next: u32,
+}
+
+
+impl Verifier {
+
+    fn function(&mut self, function: &Function) -> Result<(), VerifyError> {
+        let instructions = decode(function)?;
+        for instruction in &instructions {
+            self.check_operands(function, instruction)?;
+        }
+        check_stack(function, &instructions)?;
+
+        // Nested functions and imported modules are verified as well
+        for value in function.read_constants().as_slice() {
+            if value.is_function() {
+                self.function(&value.as_function())?;
+            }
+            if value.is_module() {
+                if !self.modules.iter().any(|verified| verified.is(value)) {
+                    self.modules.push(value.clone());
+                    let module = value.as_module();
+                    // Exported names are looked up by global id when the importer reads them
+                    for (name, id) in module.exports() {
+                        if *id >= self.globals {
+                            let message = format!("Export '{}' of module '{}' is global 0x{:04x}, out of range, {} declared", name, module.name(), id, self.globals);
+                            return Err(VerifyError::new(message, function.name()));
+                        }
+                    }
+                    self.function(&module.main().as_function())?;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+
+    fn check_operands(&self, function: &Function, instruction: &Instruction) -> Result<(), VerifyError> {
+        let id = instruction.operand;
+        match instruction.opcode {
+            OpCode::GetConst8       |
+            OpCode::GetConst16      |
+            OpCode::GetConst32      => { constant(function, instruction, id)?; }
+
+            OpCode::GetProperty8    |
+            OpCode::GetProperty16   |
+            OpCode::GetProperty32   |
+            OpCode::GetSuper8       |
+            OpCode::GetSuper16      |
+            OpCode::GetSuper32      |
+            OpCode::SetProperty8    |
+            OpCode::SetProperty16   |
+            OpCode::SetProperty32   |
+            OpCode::Method8         |
+            OpCode::Method16        |
+            OpCode::Method32        |
+            OpCode::Invoke8         |
+            OpCode::Invoke16        |
+            OpCode::Invoke32        => {
+                constant(function, instruction, id)?;
+                if function.read_constants().symbol_by_id(id).is_none() {
+                    return Err(error(function, instruction, format!("Constant 0x{:04x} is not a name", id)));
+                }
+            }
+
+            OpCode::Class8          |
+            OpCode::Class16         |
+            OpCode::Class32         => {
+                if !constant(function, instruction, id)?.is_string() {
+                    return Err(error(function, instruction, format!("Constant 0x{:04x} is not a string", id)));
+                }
+            }
+
+            OpCode::Import8         |
+            OpCode::Import16        |
+            OpCode::Import32        => {
+                if !constant(function, instruction, id)?.is_module() {
+                    return Err(error(function, instruction, format!("Constant 0x{:04x} is not a module", id)));
+                }
+            }
+
+            OpCode::GetUpvalue8     |
+            OpCode::GetUpvalue16    |
+            OpCode::GetUpvalue32    |
+            OpCode::SetUpvalue8     |
+            OpCode::SetUpvalue16    |
+            OpCode::SetUpvalue32    => { upvalue(function, instruction, id)?; }
+
+            OpCode::GetGlobal8      |
+            OpCode::GetGlobal16     |
+            OpCode::GetGlobal32     |
+            OpCode::DefGlobal8      |
+            OpCode::DefGlobal16     |
+            OpCode::DefGlobal32     |
+            OpCode::SetGlobal8      |
+            OpCode::SetGlobal16     |
+            OpCode::SetGlobal32     => {
+                if id >= self.globals {
+                    return Err(error(function, instruction, format!("Global 0x{:04x} out of range, {} declared", id, self.globals)));
+                }
+            }
+
+            OpCode::Tuck            => {
+                // The copy of the top value goes below it, so at least that value must be counted
+                if id == 0 {
+                    return Err(error(function, instruction, format!("Invalid {} count 0", instruction.opcode.mnemonic())));
+                }
+            }
+
+            OpCode::Capture8        |
+            OpCode::Capture16       |
+            OpCode::Capture32       => {
+                // Local ids depend on the stack depth and are checked by check_stack()
+                for (is_local, id) in &instruction.captures {
+                    if !is_local { upvalue(function, instruction, *id)?; }
+                }
+            }
+
+            _ => {}
+        }
+        return Ok(());
+    }
+
+}
+
+
+// Split the code into instructions, checking that each one decodes
+fn decode(function: &Function) -> Result<Vec<Instruction>, VerifyError> {
+    let code = function.read_chunk().code();
+    let mut instructions = vec![];
+    let mut ip = 0;
+    while ip < code.len() {
+        let opcode: OpCode = code[ip].into();
+        if opcode == OpCode::BAD {
+            return Err(VerifyError::new_at(format!("Invalid opcode 0x{:02x}", code[ip]), function.name(), ip as u32));
+        }
+        let truncated = || VerifyError::new_at(format!("Truncated {} instruction", opcode.mnemonic()), function.name(), ip as u32);
+        let mut next = ip + 1;
+        let operand = read_operand(code, &mut next, opcode.len()).ok_or_else(truncated)?;
+
+        // Capture is followed by one variable length entry per upvalue of the function
+        let mut captures = vec![];
+        if opcode == OpCode::Capture8 || opcode == OpCode::Capture16 || opcode == OpCode::Capture32 {
+            let value = match function.read_constants().as_slice().get(operand) {
+                Some(value) if value.is_function() => value.clone(),
+                _ => return Err(VerifyError::new_at(format!("Constant 0x{:04x} is not a function", operand), function.name(), ip as u32)),
+            };
+            for _ in 0..value.as_function().upvalue_count() {
+                let byte = read_operand(code, &mut next, 1).ok_or_else(truncated)?;
+                let len = byte & 127;
+                if len != 1 && len != 2 && len != 4 {
+                    return Err(VerifyError::new_at(format!("Invalid upvalue id length {}", len), function.name(), ip as u32));
+                }
+                let id = read_operand(code, &mut next, len).ok_or_else(truncated)?;
+                captures.push(((byte & 128) == 128, id));
+            }
+        }
+
+        // Invoke is followed by the argument count and an inline cache slot
+        let mut arg_count = 0;
+        if opcode == OpCode::Invoke8 || opcode == OpCode::Invoke16 || opcode == OpCode::Invoke32 {
+            arg_count = read_operand(code, &mut next, 1).ok_or_else(truncated)?;
+            read_operand(code, &mut next, 2).ok_or_else(truncated)?;
+        }
+        // GetProperty only by the inline cache slot
+        if opcode == OpCode::GetProperty8 || opcode == OpCode::GetProperty16 || opcode == OpCode::GetProperty32 {
+            read_operand(code, &mut next, 2).ok_or_else(truncated)?;
+        }
+
+        instructions.push(Instruction { ip: ip as u32, opcode, operand, captures, arg_count, next: next as u32 });
+        ip = next;
+    }
+    return Ok(instructions);
+}
+
+
+// Follow every path through the code, checking that the stack depth is the same
+// whichever way an instruction is reached, and that nothing reads below the callframe
+fn check_stack(function: &Function, instructions: &[Instruction]) -> Result<(), VerifyError> {
+    let index: HashMap<u32, usize> = instructions.iter().enumerate().map(|(i, instruction)| (instruction.ip, i)).collect();
+    let mut depths: Vec<Option<usize>> = vec![None; instructions.len()];
+    let mut pending: Vec<(usize, usize)> = vec![];
+    if !instructions.is_empty() {
+        // Slot zero holds the callee or receiver, followed by the arguments
+        pending.push((0, 1 + function.arity() as usize));
+    }
+
+    while let Some((i, depth)) = pending.pop() {
+        let instruction = &instructions[i];
+        match depths[i] {
+            Some(known) if known == depth => continue,
+            Some(known) => return Err(error(function, instruction, format!("Inconsistent stack depth, {} or {}", known, depth))),
+            None => depths[i] = Some(depth),
+        }
+
+        match instruction.opcode {
+            OpCode::GetLocal8       |
+            OpCode::GetLocal16      |
+            OpCode::GetLocal32      |
+            OpCode::SetLocal8       |
+            OpCode::SetLocal16      |
+            OpCode::SetLocal32      => { local(function, instruction, instruction.operand, depth)?; }
+            _ => {}
+        }
+        for (is_local, id) in &instruction.captures {
+            if *is_local { local(function, instruction, *id, depth)?; }
+        }
+
+        let (pops, pushes) = stack_effect(instruction);
+        if depth < pops {
+            return Err(error(function, instruction, format!("Stack underflow, {} needs {} value(s) but depth is {}", instruction.opcode.mnemonic(), pops, depth)));
+        }
+        let after = depth - pops + pushes;
+
+        let mut targets = vec![];
+        match instruction.opcode {
+            OpCode::Exit | OpCode::Return | OpCode::Throw => {}
+            OpCode::Jmp => targets.push((instruction.operand as u32, after)),
+            OpCode::JmpFalseP | OpCode::JmpFalseQ => {
+                targets.push((instruction.operand as u32, after));
+                targets.push((instruction.next, after));
+            }
+            // The handler runs with the exception on top of the stack as it was here
+            OpCode::Try => {
+                targets.push((instruction.operand as u32, depth + 1));
+                targets.push((instruction.next, after));
+            }
+            _ => targets.push((instruction.next, after)),
+        }
+        for (ip, depth) in targets {
+            match index.get(&ip) {
+                Some(target) => pending.push((*target, depth)),
+                None if ip == instruction.next && instruction.next as usize == function.read_chunk().code().len() => {
+                    return Err(error(function, instruction, String::from("Execution runs past the end of the code")));
+                }
+                None => return Err(error(function, instruction, format!("Jump to 0x{:08x} is not an instruction", ip))),
+            }
+        }
+    }
+    return Ok(());
+}
+
+
+// Number of values an instruction pops off the stack and pushes back on,
+// as seen by the instruction that follows it
+fn stack_effect(instruction: &Instruction) -> (usize, usize) {
+    let n = instruction.operand;
+    match instruction.opcode {
+        OpCode::Debug           |
+        OpCode::Exit            |
+        OpCode::Print           |
+        OpCode::Return          |
+        OpCode::Throw           |
+        OpCode::Pop             |
+        OpCode::CloseUpvalue    |
+        OpCode::JmpFalseP       |
+        OpCode::DefGlobal8      |
+        OpCode::DefGlobal16     |
+        OpCode::DefGlobal32     => (1, 0),
+
+        OpCode::Dup             => (1, 2),
+        OpCode::Dup2            => (2, 4),
+        OpCode::Tuck            => (n, n + 1),
+        // The iterator, continue flag and last value live in three reserved slots below
+        OpCode::MakeIter        => (4, 3),
+        // Calling an instance iterator leaves two extra values that NextIter cleans up
+        OpCode::ReadIter        |
+        OpCode::NextIter        => (3, 3),
+
+        OpCode::GetConst8       |
+        OpCode::GetConst16      |
+        OpCode::GetConst32      |
+        OpCode::False           |
+        OpCode::Null            |
+        OpCode::True            |
+        OpCode::NaN             |
+        OpCode::Inf             |
+        OpCode::GetLocal8       |
+        OpCode::GetLocal16      |
+        OpCode::GetLocal32      |
+        OpCode::GetUpvalue8     |
+        OpCode::GetUpvalue16    |
+        OpCode::GetUpvalue32    |
+        OpCode::GetGlobal8      |
+        OpCode::GetGlobal16     |
+        OpCode::GetGlobal32     |
+        OpCode::Capture8        |
+        OpCode::Capture16       |
+        OpCode::Capture32       |
+        OpCode::Class8          |
+        OpCode::Class16         |
+        OpCode::Class32         |
+        OpCode::Import8         |
+        OpCode::Import16        |
+        OpCode::Import32        => (0, 1),
+
+        OpCode::GetProperty8    |
+        OpCode::GetProperty16   |
+        OpCode::GetProperty32   |
+        OpCode::SetLocal8       |
+        OpCode::SetLocal16      |
+        OpCode::SetLocal32      |
+        OpCode::SetUpvalue8     |
+        OpCode::SetUpvalue16    |
+        OpCode::SetUpvalue32    |
+        OpCode::SetGlobal8      |
+        OpCode::SetGlobal16     |
+        OpCode::SetGlobal32     |
+        OpCode::Not             |
+        OpCode::Negate          |
+        OpCode::BitNot          |
+        OpCode::JmpFalseQ       => (1, 1),
+
+        OpCode::GetSuper8       |
+        OpCode::GetSuper16      |
+        OpCode::GetSuper32      |
+        OpCode::SetProperty8    |
+        OpCode::SetProperty16   |
+        OpCode::SetProperty32   |
+        OpCode::Method8         |
+        OpCode::Method16        |
+        OpCode::Method32        |
+        OpCode::Add             |
+        OpCode::Sub             |
+        OpCode::Mul             |
+        OpCode::Div             |
+        OpCode::Mod             |
+        OpCode::Equal           |
+        OpCode::NotEqual        |
+        OpCode::Less            |
+        OpCode::Greater         |
+        OpCode::LessEqual       |
+        OpCode::GreaterEqual    |
+        OpCode::Same            |
+        OpCode::BitAnd          |
+        OpCode::BitOr           |
+        OpCode::BitXor          |
+        OpCode::ShiftLeft       |
+        OpCode::ShiftRight      |
+        OpCode::Inherit         |
+        OpCode::GetSubscript    => (2, 1),
+
+        OpCode::SetSubscript    => (3, 1),
+
+        OpCode::DefArray8       |
+        OpCode::DefArray16      |
+        OpCode::DefArray32      => (n, 1),
+        OpCode::DefMap8         |
+        OpCode::DefMap16        |
+        OpCode::DefMap32        => (n * 2, 1),
+        OpCode::Call            => (n + 1, 1), // Callee and arguments are replaced by the return value
+        OpCode::Invoke8         |
+        OpCode::Invoke16        |
+        OpCode::Invoke32        => (instruction.arg_count + 1, 1), // Same as Call, with the receiver as callee
+        OpCode::PopN            => (n, 0),
+
+        OpCode::Jmp             |
+        OpCode::Try             |
+        OpCode::EndTry          |
+        OpCode::BAD             => (0, 0),
+    }
+}
+
+
+fn constant<'a>(function: &'a Function, instruction: &Instruction, id: usize) -> Result<&'a Value, VerifyError> {
+    let constants = function.read_constants().as_slice();
+    match constants.get(id) {
+        Some(value) => return Ok(value),
+        None => return Err(error(function, instruction, format!("Constant 0x{:04x} out of range, {} defined", id, constants.len()))),
+    }
+}
+
+
+fn upvalue(function: &Function, instruction: &Instruction, id: usize) -> Result<(), VerifyError> {
+    if id >= function.upvalue_count() {
+        return Err(error(function, instruction, format!("Upvalue 0x{:04x} out of range, {} captured", id, function.upvalue_count())));
+    }
+    return Ok(());
+}
+
+
+fn local(function: &Function, instruction: &Instruction, id: usize, depth: usize) -> Result<(), VerifyError> {
+    if id >= depth {
+        return Err(error(function, instruction, format!("Local 0x{:04x} out of range, stack depth is {}", id, depth)));
+    }
+    return Ok(());
+}
+
+
+fn error(function: &Function, instruction: &Instruction, message: String) -> VerifyError {
+    return VerifyError::new_at(message, function.name(), instruction.ip);
+}
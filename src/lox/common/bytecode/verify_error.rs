@@ -0,0 +1,58 @@
+
+
+#[derive(Debug)]
+pub struct VerifyError {
+    message: String,
+    function: String,
+    ip: Option<u32>,
+}
+
+
+#[allow(dead_code)]
+impl VerifyError {
+
+    pub fn new(message: String, function: &str) -> Self {
+        VerifyError {
+            message,
+            function: function.to_string(),
+            ip: None,
+        }
+    }
+
+
+    pub fn new_at(message: String, function: &str, ip: u32) -> Self {
+        VerifyError {
+            message,
+            function: function.to_string(),
+            ip: Some(ip),
+        }
+    }
+
+
+    pub fn get_message(&self) -> &String {
+        return &self.message;
+    }
+
+
+    // Name of the function containing the bad code
+    pub fn get_function(&self) -> &String {
+        return &self.function;
+    }
+
+
+    // Offset of the bad instruction, if any
+    pub fn get_ip(&self) -> Option<u32> {
+        return self.ip;
+    }
+
+}
+
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.ip {
+            None => writeln!(f, "ERROR> {} in {}", self.message, self.function),
+            Some(ip) => writeln!(f, "ERROR> {} in {}:0x{:08x}", self.message, self.function, ip),
+        }
+    }
+}
@@ -4,12 +4,18 @@
 mod test;
 
 
-use super::OpCode;
+use std::rc::Rc;
+
+
+use super::{At, Location, OpCode};
 
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct Chunk {
-    code: Vec<u8>,    
+    code: Rc<Vec<u8>>, // Shared with the call frames executing it
+    // Run-length encoded source map; each entry covers the code
+    // from its ip up to the ip of the next entry
+    source_map: Vec<(u32, Location)>,
 }
 
 
@@ -17,20 +23,31 @@ impl Chunk {
 
     pub fn new() -> Chunk {
         Chunk {
-            code:	vec![],
+            code:	Rc::new(vec![]),
+            source_map:	vec![],
+        }
+    }
+
+
+    // Used when loading precompiled code
+    pub fn with_source_map(code: Vec<u8>, source_map: Vec<(u32, Location)>) -> Chunk {
+        Chunk {
+            code:	Rc::new(code),
+            source_map,
         }
     }
 
     pub fn append_bytes(&mut self, dword: u32, len: usize) {
+        let code = Rc::make_mut(&mut self.code);
         if len == 4 {
-            self.code.push(((dword >> 24) & 0xff) as u8);
-            self.code.push(((dword >> 16) & 0xff) as u8);
+            code.push(((dword >> 24) & 0xff) as u8);
+            code.push(((dword >> 16) & 0xff) as u8);
         }
         if len == 4 || len == 2 {
-            self.code.push(((dword >> 8) & 0xff) as u8);
+            code.push(((dword >> 8) & 0xff) as u8);
         }
         if len == 4 || len == 2 || len == 1 {
-            self.code.push((dword & 0xff) as u8);
+            code.push((dword & 0xff) as u8);
             return;
         }
         panic!("Length must be 1, 2 or 4 bytes; got {}", len);
@@ -40,6 +57,22 @@ impl Chunk {
     pub fn length(&self) -> u32 {
         return self.code.len() as u32;
     }
+
+
+    pub fn code(&self) -> &[u8] {
+        return &self.code;
+    }
+
+
+    // Used by the VM to read the code without borrowing the function
+    pub fn shared_code(&self) -> Rc<Vec<u8>> {
+        return Rc::clone(&self.code);
+    }
+
+
+    pub fn source_map(&self) -> &[(u32, Location)] {
+        return &self.source_map;
+    }
     
     pub fn read_bytes(&self, index: u32, len: usize) -> u32 {
         //println!("read_bytes(index={}, len={}) from code={:?}", index, len, self.code);
@@ -54,19 +87,43 @@ impl Chunk {
     }
 
 
+// Called by ChunkWriter before appending the bytes of an instruction
+pub fn mark_at(&mut self, file: &Rc<str>, at: &At) {
+    let ip = self.length();
+    if let Some((last_ip, last_location)) = self.source_map.last_mut() {
+        if last_location.is_at(file, at) { return; }
+        if *last_ip == ip {
+            // Nothing was emitted at the previous location
+            *last_location = Location::from_at(file, at);
+            return;
+        }
+    }
+    self.source_map.push((ip, Location::from_at(file, at)));
+}
+
+
+// Source location of the instruction covering ip, if known
+pub fn at(&self, ip: u32) -> Option<&Location> {
+    let index = self.source_map.partition_point(|(start, _)| *start <= ip);
+    if index == 0 { return None; }
+    return Some(&self.source_map[index - 1].1);
+}
+
+
 pub fn write_bytes(&mut self, dword: u32, mut index: u32, len: usize) {
+    let code = Rc::make_mut(&mut self.code);
     if len == 4 {
-        self.code[index as usize] = ((dword >> 24) & 0xff) as u8;
+        code[index as usize] = ((dword >> 24) & 0xff) as u8;
         index = index + 1;
-        self.code[index as usize] = ((dword >> 16) & 0xff) as u8;
+        code[index as usize] = ((dword >> 16) & 0xff) as u8;
         index = index + 1;
     }
     if len == 4 || len == 2 {
-        self.code[index as usize] = ((dword >>  8) & 0xff) as u8;
+        code[index as usize] = ((dword >>  8) & 0xff) as u8;
         index = index + 1;
     }
     if len == 4 || len == 2 || len == 1 {
-        self.code[index as usize] = ((dword >>  0) & 0xff) as u8;
+        code[index as usize] = ((dword >>  0) & 0xff) as u8;
         //index = index + 1; // Pointless here
         return;
     }
@@ -100,6 +157,8 @@ impl Chunk {
             OpCode::MakeIter        => self.opcode_immediate(ip),
             OpCode::ReadIter        => self.opcode_immediate(ip),
             OpCode::NextIter        => self.opcode_immediate(ip),
+            OpCode::Dup2            => self.opcode_immediate(ip),
+            OpCode::Tuck            => self.opcode_variant(ip),
 
             OpCode::GetConst8 		|
             OpCode::GetConst16 		|
@@ -122,7 +181,7 @@ impl Chunk {
             OpCode::GetGlobal32 	=> self.opcode_variant(ip),
             OpCode::GetProperty8 	|
             OpCode::GetProperty16 	|
-            OpCode::GetProperty32 	=> self.opcode_variant(ip),
+            OpCode::GetProperty32 	=> self.opcode_getproperty(ip),
             OpCode::GetSuper8 		|
             OpCode::GetSuper16 	    |
             OpCode::GetSuper32 	    => self.opcode_variant(ip),
@@ -132,7 +191,10 @@ impl Chunk {
             OpCode::DefGlobal32 	=> self.opcode_variant(ip),
             OpCode::DefArray8 		|
             OpCode::DefArray16 	    |
-            OpCode::DefArray32 	    => self.opcode_variant(ip),
+            OpCode::DefArray32 	    |
+            OpCode::DefMap8 		|
+            OpCode::DefMap16 	    |
+            OpCode::DefMap32 	    => self.opcode_variant(ip),
 
             OpCode::SetLocal8 		|
             OpCode::SetLocal16 		|
@@ -157,9 +219,13 @@ impl Chunk {
             OpCode::Method8 		|
             OpCode::Method16 		|
             OpCode::Method32 		=> self.opcode_variant(ip),
+            OpCode::Import8 		|
+            OpCode::Import16 		|
+            OpCode::Import32 		=> self.opcode_variant(ip),
 
             OpCode::Not 		    => self.opcode_immediate(ip),
             OpCode::Negate 		    => self.opcode_immediate(ip),
+            OpCode::BitNot 		    => self.opcode_immediate(ip),
 
             OpCode::Add 		    => self.opcode_immediate(ip),
             OpCode::Sub 		    => self.opcode_immediate(ip),
@@ -173,11 +239,19 @@ impl Chunk {
             OpCode::LessEqual		=> self.opcode_immediate(ip),
             OpCode::GreaterEqual	=> self.opcode_immediate(ip),
             OpCode::Same		    => self.opcode_immediate(ip),
+            OpCode::BitAnd		    => self.opcode_immediate(ip),
+            OpCode::BitOr		    => self.opcode_immediate(ip),
+            OpCode::BitXor		    => self.opcode_immediate(ip),
+            OpCode::ShiftLeft	    => self.opcode_immediate(ip),
+            OpCode::ShiftRight	    => self.opcode_immediate(ip),
 
             OpCode::Jmp			    => self.opcode_variant(ip),
             OpCode::JmpFalseP		=> self.opcode_variant(ip),
             OpCode::JmpFalseQ		=> self.opcode_variant(ip),
             OpCode::Call		    => self.opcode_variant(ip),
+            OpCode::Try		        => self.opcode_variant(ip),
+            OpCode::EndTry		    => self.opcode_immediate(ip),
+            OpCode::Throw		    => self.opcode_immediate(ip),
 
             OpCode::Pop 		    => self.opcode_immediate(ip),
             OpCode::PopN 		    => self.opcode_variant(ip),
@@ -186,6 +260,10 @@ impl Chunk {
             OpCode::GetSubscript    => self.opcode_immediate(ip),
             OpCode::SetSubscript    => self.opcode_immediate(ip),
 
+            OpCode::Invoke8         |
+            OpCode::Invoke16        |
+            OpCode::Invoke32        => self.opcode_invoke(ip),
+
             OpCode::BAD 		    => self.opcode_immediate(ip),
         };
         result += &instruction;
@@ -218,6 +296,27 @@ impl Chunk {
     }
 
 
+    // Followed by the argument count and inline cache slot
+    fn opcode_invoke(&self, ip: &mut u32) -> String {
+        let mut result = self.opcode_variant(ip);
+        let arg_count = self.read_bytes(*ip, 1);
+        let slot = self.read_bytes(*ip + 1, 2);
+        *ip = *ip + 3;
+        result = result + &format!(" 0x{:02x} 0x{:04x}", arg_count, slot);
+        return result;
+    }
+
+
+    // Followed by the inline cache slot
+    fn opcode_getproperty(&self, ip: &mut u32) -> String {
+        let mut result = self.opcode_variant(ip);
+        let slot = self.read_bytes(*ip, 2);
+        *ip = *ip + 2;
+        result = result + &format!(" 0x{:04x}", slot);
+        return result;
+    }
+
+
     // I have no idea how to decode these from the viewpoint
     // of a chunk because they require insight into the function
     // that the opcode will operate on.
@@ -1,5 +1,8 @@
 
 
+use std::rc::Rc;
+
+
 use super::Chunk;
 
 
@@ -11,25 +14,25 @@ fn new() {
 #[test]
 fn write_1_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00];
+    chunk.code = Rc::new(vec![0x00]);
     chunk.write_bytes(0x12, 0, 1);
-    assert_eq!(chunk.code, vec![0x12])
+    assert_eq!(*chunk.code, vec![0x12])
 }
 
 #[test]
 fn write_2_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00, 0x00];
+    chunk.code = Rc::new(vec![0x00, 0x00]);
     chunk.write_bytes(0x1234, 0, 2);
-    assert_eq!(chunk.code, vec![0x12, 0x34])
+    assert_eq!(*chunk.code, vec![0x12, 0x34])
 }
 
 #[test]
 fn write_4_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00, 0x00, 0x00, 0x00];
+    chunk.code = Rc::new(vec![0x00, 0x00, 0x00, 0x00]);
     chunk.write_bytes(0x12345678, 0, 4);
-    assert_eq!(chunk.code, vec![0x12, 0x34, 0x56, 0x78])
+    assert_eq!(*chunk.code, vec![0x12, 0x34, 0x56, 0x78])
 }
 
 #[test]
@@ -43,7 +46,7 @@ fn write_1_bytes_outside_bounds() {
 #[should_panic]
 fn write_2_bytes_outside_bounds() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00]; // 1 byte short
+    chunk.code = Rc::new(vec![0x00]); // 1 byte short
     chunk.write_bytes(0x1234, 0, 2);
 }
 
@@ -51,14 +54,14 @@ fn write_2_bytes_outside_bounds() {
 #[should_panic]
 fn write_4_bytes_outside_bounds() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00, 0x00, 0x00]; // 1 byte short
+    chunk.code = Rc::new(vec![0x00, 0x00, 0x00]); // 1 byte short
     chunk.write_bytes(0x12345678, 0, 4);
 }
 
 #[test]
 fn read_1_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x12];
+    chunk.code = Rc::new(vec![0x12]);
     let dword = chunk.read_bytes(0, 1);
     assert_eq!(dword, 0x12)
 }
@@ -66,7 +69,7 @@ fn read_1_bytes() {
 #[test]
 fn read_2_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x12, 0x34];
+    chunk.code = Rc::new(vec![0x12, 0x34]);
     let dword = chunk.read_bytes(0, 2);
     assert_eq!(dword, 0x1234)
 }
@@ -74,7 +77,7 @@ fn read_2_bytes() {
 #[test]
 fn read_4_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x12, 0x34, 0x56, 0x78];
+    chunk.code = Rc::new(vec![0x12, 0x34, 0x56, 0x78]);
     let dword = chunk.read_bytes(0, 4);
     assert_eq!(dword, 0x12345678)
 }
@@ -90,7 +93,7 @@ fn read_1_bytes_outside_bounds() {
 #[should_panic]
 fn read_2_bytes_outside_bounds() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00]; // 1 byte short
+    chunk.code = Rc::new(vec![0x00]); // 1 byte short
     let _dword = chunk.read_bytes(0, 2);
 }
 
@@ -98,31 +101,31 @@ fn read_2_bytes_outside_bounds() {
 #[should_panic]
 fn read_4_bytes_outside_bounds() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0x00, 0x00, 0x00]; // 1 byte short
+    chunk.code = Rc::new(vec![0x00, 0x00, 0x00]); // 1 byte short
     let _dword = chunk.read_bytes(0, 4);
 }
 
 #[test]
 fn append_1_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0xff];
+    chunk.code = Rc::new(vec![0xff]);
     chunk.append_bytes(0x12, 1);
-    assert_eq!(chunk.code, vec![0xff, 0x12])
+    assert_eq!(*chunk.code, vec![0xff, 0x12])
 }
 
 #[test]
 fn append_2_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0xff];
+    chunk.code = Rc::new(vec![0xff]);
     chunk.append_bytes(0x1234, 2);
-    assert_eq!(chunk.code, vec![0xff, 0x12, 0x34])
+    assert_eq!(*chunk.code, vec![0xff, 0x12, 0x34])
 }
 
 #[test]
 fn append_4_bytes() {
     let mut chunk = Chunk::new();
-    chunk.code = vec![0xff];
+    chunk.code = Rc::new(vec![0xff]);
     chunk.append_bytes(0x12345678, 4);
-    assert_eq!(chunk.code, vec![0xff, 0x12, 0x34, 0x56, 0x78])
+    assert_eq!(*chunk.code, vec![0xff, 0x12, 0x34, 0x56, 0x78])
 }
 
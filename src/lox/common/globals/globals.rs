@@ -107,9 +107,14 @@ impl<T> Globals<T> {
     }
 
 
+    // Iterate over all defined values
+    // Note: Used by the garbage collector
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        return self.values.iter().filter_map(|value| value.as_ref());
+    }
+
+
     // Return the number of variables
-    // Only used in testing
-    #[cfg(test)]
     pub fn count(&self) -> usize {
         return self.values.len();
     }    
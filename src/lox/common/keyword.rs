@@ -1,16 +1,21 @@
 
 
+pub const KEYWORD_AS:       &str = "as";
 pub const KEYWORD_BREAK:    &str = "break";
+pub const KEYWORD_CATCH:    &str = "catch";
 pub const KEYWORD_CLASS:    &str = "class";
 pub const KEYWORD_CONST:    &str = "const";
 pub const KEYWORD_CONTINUE: &str = "continue";
 pub const KEYWORD_DEBUG:    &str = "debug";
 pub const KEYWORD_ELSE:     &str = "else";
 pub const KEYWORD_EXIT:     &str = "exit";
+pub const KEYWORD_EXPORT:   &str = "export";
 pub const KEYWORD_FOR:      &str = "for";
 pub const KEYWORD_FUN:      &str = "fun";
 pub const KEYWORD_FALSE:    &str = "false";
+pub const KEYWORD_FINALLY:  &str = "finally";
 pub const KEYWORD_IF:       &str = "if";
+pub const KEYWORD_IMPORT:   &str = "import";
 pub const KEYWORD_IN:       &str = "in";
 pub const KEYWORD_INF:      &str = "inf";
 pub const KEYWORD_IS:       &str = "is";
@@ -24,6 +29,8 @@ pub const KEYWORD_PRINT:    &str = "print";
 pub const KEYWORD_RETURN:   &str = "return";
 pub const KEYWORD_SUPER:    &str = "super";
 pub const KEYWORD_THIS:     &str = "this";
+pub const KEYWORD_THROW:    &str = "throw";
 pub const KEYWORD_TRUE:     &str = "true";
+pub const KEYWORD_TRY:      &str = "try";
 pub const KEYWORD_VAR:      &str = "var";
 pub const KEYWORD_WHILE:    &str = "while";
@@ -0,0 +1,69 @@
+
+
+use std::rc::Rc;
+
+
+use super::At;
+
+
+// Source location of compiled code, kept by Chunk and Function for runtime errors.
+// The compiler works with At from the scanner, which can only be created by
+// scanning; a Location can also be rebuilt from its parts, e.g. when loading
+// precompiled code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    file: Rc<str>, // Shared by every location in the same file
+    lineno: u32,
+    charno: u32,
+}
+
+
+impl Location {
+
+    pub fn new(file: Rc<str>, lineno: u32, charno: u32) -> Self {
+        Location {
+            file,
+            lineno,
+            charno,
+        }
+    }
+
+
+    // The file name is given separately, At only shows it through Display
+    pub fn from_at(file: &Rc<str>, at: &At) -> Self {
+        return Location::new(Rc::clone(file), at.lineno() as u32, at.charno() as u32);
+    }
+
+
+    // Checked for every emitted instruction, so compare the numbers first
+    // and the file name by pointer before falling back to its text
+    pub fn is_at(&self, file: &Rc<str>, at: &At) -> bool {
+        return self.lineno == at.lineno() as u32
+            && self.charno == at.charno() as u32
+            && (Rc::ptr_eq(&self.file, file) || self.file == *file);
+    }
+
+
+    pub fn file(&self) -> &Rc<str> {
+        return &self.file;
+    }
+
+
+    pub fn lineno(&self) -> u32 {
+        return self.lineno;
+    }
+
+
+    pub fn charno(&self) -> u32 {
+        return self.charno;
+    }
+
+}
+
+
+// Same format as At
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {} char {} of {}", self.lineno, self.charno, self.file)
+    }
+}
@@ -7,15 +7,20 @@ mod bytecode;
 mod chunk;
 mod globals;
 pub mod keyword;
+mod location;
 mod opcode;
 mod identifier_kind;
+mod symbol;
 mod value;
 
 
 pub use at::At;
-pub use bytecode::ByteCode;
+pub use bytecode::{ByteCode, VerifyError};
 pub use chunk::Chunk;
 pub use globals::Globals;
 pub use opcode::{OpCode, OpCodeSet};
 pub use identifier_kind::IdentifierKind;
-pub use value::{Array, Closure, Function, FunctionKind, NativeCallable, NativeCallables, Value, ValueIterator, Obj};
+pub use location::Location;
+pub use symbol::{Symbol, SymbolMap};
+pub use value::allocations;
+pub use value::{Array, Closure, Function, FunctionKind, InlineCaches, Map, Module, NativeCallable, NativeCallables, NativeContextFn, NativeFn, NativeFunction, Value, ValueIterator, Obj};
@@ -15,6 +15,8 @@ pub enum OpCode {
     MakeIter,               // pop, define iterator, push
     ReadIter,               // peek(0), push true/false for continue, push next value, finally call method if instance iterator
     NextIter,               // peek(2), update last value
+    Dup2,                   // push(peek(1)) twice to duplicate two values
+    Tuck,                   // Insert a copy of peek(0) below the top N values, followed by BYTE N (at least 1)
     
     // Push constant value onto stack
     GetConst8	    = 0x10,	// Followed by BYTE indexing table of constants
@@ -36,7 +38,7 @@ pub enum OpCode {
     GetGlobal8	    = 0x26,
     GetGlobal16	    = 0x27,
     GetGlobal32	    = 0x28,
-    GetProperty8	= 0x29,
+    GetProperty8	= 0x29,	// Followed by WORD inline cache slot after the name
     GetProperty16	= 0x2a,
     GetProperty32	= 0x2b,
     GetSuper8	    = 0x2c,
@@ -51,6 +53,9 @@ pub enum OpCode {
     DefArray8,              // Followed by BYTE with element count
     DefArray16,             // Followed by WORD with element count
     DefArray32,	            // Followed by DWORD with element count
+    DefMap8,                // Followed by BYTE with key/value pair count
+    DefMap16,               // Followed by WORD with key/value pair count
+    DefMap32,               // Followed by DWORD with key/value pair count
 
     // Pop value and put in existing variable
     SetLocal8	    = 0x40,
@@ -80,10 +85,15 @@ pub enum OpCode {
     Method8,	// Followed by BYTE indexing table of constants
     Method16,	// Followed by WORD indexing table of constants
     Method32,	// Followed by DWORD indexing table of constants
+    // Get constant value (a module) and push it, running its top level code on first import
+    Import8,	// Followed by BYTE indexing table of constants
+    Import16,	// Followed by WORD indexing table of constants
+    Import32,	// Followed by DWORD indexing table of constants
     
     // Pop one value, perform operation, push result
     Not,
     Negate,
+    BitNot,
     
     // Pop two values, perform operation, push result
     Add,
@@ -98,12 +108,20 @@ pub enum OpCode {
     LessEqual,
     GreaterEqual,
     Same,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
 
     // Change instruction pointer
     Jmp,
     JmpFalseP,	// POP, then if false JUMP
     JmpFalseQ,  // PEEK, then if false JUMP
     Call,
+    Try,        // Followed by DWORD address of the handler, push exception handler
+    EndTry,     // Pop exception handler
+    Throw,      // POP, then raise as exception
         
     // Pop (and discard) one or more values from the stack
     Pop,
@@ -112,6 +130,12 @@ pub enum OpCode {
     Inherit,
     GetSubscript,
     SetSubscript,
+
+    // Get constant value (should be a name), look up the method of that name on the receiver
+    // below the arguments and call it; followed by BYTE argument count and WORD inline cache slot
+    Invoke8,
+    Invoke16,
+    Invoke32,
     
     #[default]
     BAD 	        = 0xff,	// Unknown/bad opcodes resolve to this
@@ -130,6 +154,8 @@ impl OpCode {
             OpCode::MakeIter        => "MKIT",
             OpCode::ReadIter        => "RDIT",
             OpCode::NextIter        => "NXIT",
+            OpCode::Dup2            => "DUP2",
+            OpCode::Tuck            => "TUCK",
 
             OpCode::GetConst8       => "GETC",
             OpCode::GetConst16      => "GETC",
@@ -162,6 +188,9 @@ impl OpCode {
             OpCode::DefArray8 		=> "DEFA",
             OpCode::DefArray16 	    => "DEFA",
             OpCode::DefArray32 	    => "DEFA",
+            OpCode::DefMap8 		=> "DEFM",
+            OpCode::DefMap16 	    => "DEFM",
+            OpCode::DefMap32 	    => "DEFM",
 
             OpCode::SetLocal8 		=> "SETL",
             OpCode::SetLocal16 		=> "SETL",
@@ -186,9 +215,13 @@ impl OpCode {
             OpCode::Method8	 	    => "MTHD",
             OpCode::Method16		=> "MTHD",
             OpCode::Method32		=> "MTHD",
+            OpCode::Import8	 	    => "IMPORT",
+            OpCode::Import16		=> "IMPORT",
+            OpCode::Import32		=> "IMPORT",
 
             OpCode::Not			    => "NOT",
             OpCode::Negate		    => "NEG",
+            OpCode::BitNot		    => "BNOT",
 
             OpCode::Add 		    => "ADD",
             OpCode::Sub 		    => "SUB",
@@ -202,11 +235,19 @@ impl OpCode {
             OpCode::LessEqual 		=> "LEQ",
             OpCode::GreaterEqual 	=> "GEQ",
             OpCode::Same 		    => "SAME",
+            OpCode::BitAnd 		    => "BAND",
+            OpCode::BitOr 		    => "BOR",
+            OpCode::BitXor 		    => "BXOR",
+            OpCode::ShiftLeft 		=> "SHL",
+            OpCode::ShiftRight 		=> "SHR",
         
             OpCode::Jmp 		    => "JMP",
             OpCode::JmpFalseP 		=> "JFP",
             OpCode::JmpFalseQ 		=> "JFQ",
             OpCode::Call 		    => "CALL",
+            OpCode::Try 		    => "TRY",
+            OpCode::EndTry 		    => "ENDTRY",
+            OpCode::Throw 		    => "THROW",
 
             OpCode::Pop 		    => "POP",
             OpCode::PopN 		    => "POP",
@@ -214,6 +255,10 @@ impl OpCode {
             OpCode::Inherit         => "INHRT",
             OpCode::GetSubscript    => "GSUB",
             OpCode::SetSubscript    => "SSUB",
+
+            OpCode::Invoke8         => "INVK",
+            OpCode::Invoke16        => "INVK",
+            OpCode::Invoke32        => "INVK",
             
             OpCode::BAD 		    => "???",
         }
@@ -251,6 +296,9 @@ impl OpCode {
             OpCode::DefArray8 		=> 1,
             OpCode::DefArray16 	    => 2,
             OpCode::DefArray32 	    => 4,
+            OpCode::DefMap8 		=> 1,
+            OpCode::DefMap16 	    => 2,
+            OpCode::DefMap32 	    => 4,
 
             OpCode::SetLocal8 		=> 1,
             OpCode::SetLocal16 		=> 2,
@@ -275,13 +323,22 @@ impl OpCode {
             OpCode::Method8	 	    => 1,
             OpCode::Method16		=> 2,
             OpCode::Method32		=> 4,
+            OpCode::Import8	 	    => 1,
+            OpCode::Import16		=> 2,
+            OpCode::Import32		=> 4,
 
             OpCode::Jmp 		    => 4,
             OpCode::JmpFalseP 		=> 4,
             OpCode::JmpFalseQ 		=> 4,
             OpCode::Call 		    => 1, // Number of arguments on the stack
+            OpCode::Try 		    => 4,
 
             OpCode::PopN 		    => 1, // Number of values to pop
+            OpCode::Tuck            => 1, // Number of values to tuck the copy under
+
+            OpCode::Invoke8         => 1,
+            OpCode::Invoke16        => 2,
+            OpCode::Invoke32        => 4,
 
             _ => 0,
         }
@@ -31,6 +31,15 @@ impl OpCodeSet {
     }
 
 
+    pub fn defmap() -> OpCodeSet {
+        OpCodeSet {
+            byte: 	OpCode::DefMap8,
+            word:	OpCode::DefMap16,
+            dword:	OpCode::DefMap32,
+        }
+    }
+
+
     pub fn capture() -> OpCodeSet {
         OpCodeSet {
             byte: 	OpCode::Capture8,
@@ -58,6 +67,15 @@ impl OpCodeSet {
     }
 
 
+    pub fn import() -> OpCodeSet {
+        OpCodeSet {
+            byte: 	OpCode::Import8,
+            word:	OpCode::Import16,
+            dword:	OpCode::Import32,
+        }
+    }
+
+
     pub fn getconst() -> OpCodeSet {
         OpCodeSet {
             byte: 	OpCode::GetConst8,
@@ -147,4 +165,13 @@ impl OpCodeSet {
         }
     }
 
+
+    pub fn invoke() -> OpCodeSet {
+        OpCodeSet {
+            byte: 	OpCode::Invoke8,
+            word:	OpCode::Invoke16,
+            dword:	OpCode::Invoke32,
+        }
+    }
+
 }
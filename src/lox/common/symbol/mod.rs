@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod test;
+
+
+mod symbol;
+
+
+pub use symbol::{Symbol, SymbolMap};
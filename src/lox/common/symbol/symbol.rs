@@ -0,0 +1,153 @@
+
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::rc::Rc;
+
+
+use crate::lox::common::Value;
+
+
+// Names of fields, methods and native callables are interned so they can be
+// looked up and compared as integers instead of hashing the whole string.
+// Like allocations, the interner is kept per thread and is therefore shared
+// by the compiler and every VM on that thread. Interned names are never freed,
+// so only names from the source code and native libraries are interned, never
+// strings made up at runtime. Each name also has a single string Value that
+// every constant naming it shares, so comparing two of them is a pointer comparison.
+
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner::new());
+}
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+
+impl Symbol {
+
+    // Return the symbol of a name, interning it if necessary
+    pub fn intern(name: &str) -> Symbol {
+        INTERNER.with(|interner| interner.borrow_mut().intern(name))
+    }
+
+
+    // Return the symbol of a name only if it has already been interned;
+    // a name nobody has interned can not be the key of anything
+    pub fn find(name: &str) -> Option<Symbol> {
+        INTERNER.with(|interner| interner.borrow().find(name))
+    }
+
+
+    pub fn name(&self) -> Rc<str> {
+        INTERNER.with(|interner| interner.borrow().name(*self))
+    }
+
+
+    // The one string Value of this name
+    pub fn value(&self) -> Value {
+        INTERNER.with(|interner| interner.borrow().value(*self))
+    }
+
+
+    pub fn id(&self) -> u32 {
+        return self.0;
+    }
+
+}
+
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+
+impl std::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.name())
+    }
+}
+
+
+struct Interner {
+    ids: HashMap<Rc<str>, Symbol>,
+    names: Vec<Rc<str>>,
+    values: Vec<Value>,
+}
+
+
+impl Interner {
+
+    fn new() -> Self {
+        Interner {
+            ids:	HashMap::new(),
+            names:	vec![],
+            values:	vec![],
+        }
+    }
+
+
+    fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.ids.get(name) { return *symbol; }
+        let symbol = Symbol(self.names.len() as u32);
+        let name: Rc<str> = Rc::from(name);
+        self.names.push(Rc::clone(&name));
+        self.values.push(Value::string(&name));
+        self.ids.insert(name, symbol);
+        return symbol;
+    }
+
+
+    fn find(&self, name: &str) -> Option<Symbol> {
+        return self.ids.get(name).copied();
+    }
+
+
+    fn name(&self, symbol: Symbol) -> Rc<str> {
+        return Rc::clone(&self.names[symbol.0 as usize]);
+    }
+
+
+    fn value(&self, symbol: Symbol) -> Value {
+        return self.values[symbol.0 as usize].clone();
+    }
+
+}
+
+
+// Symbols are already unique so there is no need for a hash function that
+// resists collisions; spreading the bits with a single multiplication will do
+#[derive(Default)]
+pub struct SymbolHasher {
+    hash: u64,
+}
+
+
+impl Hasher for SymbolHasher {
+
+    fn finish(&self) -> u64 {
+        return self.hash;
+    }
+
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash = (self.hash << 8) | *byte as u64;
+        }
+        self.hash = self.hash.wrapping_mul(0x9e3779b97f4a7c15);
+    }
+
+
+    fn write_u32(&mut self, n: u32) {
+        self.hash = (n as u64).wrapping_mul(0x9e3779b97f4a7c15);
+    }
+
+}
+
+
+pub type SymbolMap<V> = HashMap<Symbol, V, BuildHasherDefault<SymbolHasher>>;
@@ -0,0 +1,50 @@
+
+
+use super::{Symbol, SymbolMap};
+
+
+#[test]
+fn symbol_intern_same_name() {
+    let a = Symbol::intern("symbol_same");
+    let b = Symbol::intern("symbol_same");
+    assert_eq!(a, b);
+}
+
+#[test]
+fn symbol_intern_different_names() {
+    let a = Symbol::intern("symbol_first");
+    let b = Symbol::intern("symbol_second");
+    assert_ne!(a, b);
+}
+
+#[test]
+fn symbol_name() {
+    let symbol = Symbol::intern("symbol_name");
+    assert_eq!(&*symbol.name(), "symbol_name");
+    assert_eq!(format!("{}", symbol), "symbol_name");
+}
+
+#[test]
+fn symbol_value_shared() {
+    let a = Symbol::intern("symbol_value").value();
+    let b = Symbol::intern("symbol_value").value();
+    assert!(a.is(&b));
+    assert_eq!(a.as_string().as_str(), "symbol_value");
+}
+
+#[test]
+fn symbol_find() {
+    assert_eq!(Symbol::find("symbol_never_interned"), None);
+    let symbol = Symbol::intern("symbol_found");
+    assert_eq!(Symbol::find("symbol_found"), Some(symbol));
+}
+
+#[test]
+fn symbol_map() {
+    let mut map = SymbolMap::default();
+    map.insert(Symbol::intern("symbol_key_a"), 1);
+    map.insert(Symbol::intern("symbol_key_b"), 2);
+    assert_eq!(map.get(&Symbol::intern("symbol_key_a")), Some(&1));
+    assert_eq!(map.get(&Symbol::intern("symbol_key_b")), Some(&2));
+    assert_eq!(map.len(), 2);
+}
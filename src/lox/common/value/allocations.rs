@@ -0,0 +1,195 @@
+
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use std::mem::size_of;
+
+
+use super::{Obj, Value};
+
+// Every heap object is allocated through Value, which has no access to the VM.
+// Allocations are therefore logged here, per thread, until a VM adopts them
+// into its Heap. Only weak references are kept so the log never keeps an
+// object alive.
+// While a VM runs, its Account is made active here so that allocations and the
+// approximate number of bytes used are counted against that VM alone, even with
+// several VMs on one thread. Objects allocated while no VM runs (e.g. by the
+// compiler) go to a separate log that the next VM to collect garbage adopts.
+
+
+const PRUNE_MINIMUM: usize = 1024;
+
+// Approximate sizes, see Obj::size()
+pub const OBJ_SIZE: usize = size_of::<RefCell<Obj>>() + 2 * size_of::<usize>(); // Including the Rc counts
+pub const VALUE_SIZE: usize = size_of::<Value>();
+
+
+thread_local! {
+    static LOG: RefCell<Vec<Weak<RefCell<Obj>>>> = RefCell::new(vec![]);
+    static PRUNE_AT: Cell<usize> = Cell::new(PRUNE_MINIMUM);
+    static ACTIVE: RefCell<Option<Account>> = RefCell::new(None);
+    static CHANGED: Cell<bool> = Cell::new(false);
+}
+
+
+// Allocations and memory use of one VM, kept by its Heap
+pub struct Account {
+    log: Vec<Weak<RefCell<Obj>>>,
+    bytes: usize, // Measured by the last garbage collection, plus everything allocated or grown since
+    max_bytes: usize,
+}
+
+
+impl Account {
+
+    pub fn new() -> Self {
+        Account {
+            log: vec![],
+            bytes: 0,
+            max_bytes: usize::MAX,
+        }
+    }
+
+
+    // Number of allocations logged since the last take()
+    pub fn pending(&self) -> usize {
+        return self.log.len();
+    }
+
+
+    // Hand over all logged allocations to the caller
+    pub fn take(&mut self) -> Vec<Weak<RefCell<Obj>>> {
+        return std::mem::take(&mut self.log);
+    }
+
+
+    pub fn bytes(&self) -> usize {
+        return self.bytes;
+    }
+
+
+    // Called by the garbage collector after measuring the live objects
+    pub fn set_bytes(&mut self, bytes: usize) {
+        self.bytes = bytes;
+    }
+
+
+    pub fn max_bytes(&self) -> usize {
+        return self.max_bytes;
+    }
+
+
+    // Set by the VM from its Limits
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+    }
+
+}
+
+
+// Count allocations against account until deactivate().
+// Returns the account that was active, if a VM was already running
+pub fn activate(account: Account) -> Option<Account> {
+    ACTIVE.with(|active| active.borrow_mut().replace(account))
+}
+
+
+// Make outer active again, returning the account given to activate()
+pub fn deactivate(outer: Option<Account>) -> Account {
+    ACTIVE.with(|active| std::mem::replace(&mut *active.borrow_mut(), outer))
+        .expect("Internal error: deactivate() without activate()")
+}
+
+
+// Run f with the active account, if any
+fn with_active<T>(f: impl FnOnce(&mut Account) -> T) -> Option<T> {
+    ACTIVE.with(|active| active.borrow_mut().as_mut().map(f))
+}
+
+
+// Called by Value for every new Obj
+pub fn register(obj: &Rc<RefCell<Obj>>) {
+    CHANGED.with(|changed| changed.set(true));
+    let size = obj.borrow().size();
+    let logged = with_active(|account| {
+        account.bytes = account.bytes.saturating_add(size);
+        account.log.push(Rc::downgrade(obj));
+    });
+    if logged.is_some() { return; }
+    LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        log.push(Rc::downgrade(obj));
+        // If nobody adopts the log (e.g. compile only), at least drop the dead entries
+        if log.len() >= PRUNE_AT.with(|p| p.get()) {
+            log.retain(|weak| weak.strong_count() > 0);
+            PRUNE_AT.with(|p| p.set(std::cmp::max(PRUNE_MINIMUM, log.len() * 2)));
+        }
+    });
+}
+
+
+// Check if anything was allocated or grown since the last call.
+// Lets the VM skip its garbage collection and memory checks for instructions that allocate nothing
+pub fn take_changed() -> bool {
+    return CHANGED.with(|changed| changed.replace(false));
+}
+
+
+// Number of allocations logged for the running VM since the last take()
+pub fn pending() -> usize {
+    return with_active(|account| account.pending()).unwrap_or(0);
+}
+
+
+// Hand over the allocations logged for the running VM
+pub fn take() -> Vec<Weak<RefCell<Obj>>> {
+    return with_active(|account| account.take()).unwrap_or_default();
+}
+
+
+// Number of allocations logged while no VM was running
+pub fn pending_outside() -> usize {
+    LOG.with(|log| log.borrow().len())
+}
+
+
+// Hand over the allocations logged while no VM was running
+pub fn take_outside() -> Vec<Weak<RefCell<Obj>>> {
+    PRUNE_AT.with(|p| p.set(PRUNE_MINIMUM));
+    LOG.with(|log| log.borrow_mut().split_off(0))
+}
+
+
+// Called when an existing object grows, e.g. by pushing to an array
+pub fn grow(bytes: usize) {
+    CHANGED.with(|changed| changed.set(true));
+    with_active(|account| account.bytes = account.bytes.saturating_add(bytes));
+}
+
+
+// Bytes used by the running VM, see Account
+pub fn bytes() -> usize {
+    return with_active(|account| account.bytes).unwrap_or(0);
+}
+
+
+// Called by the garbage collector of the running VM
+pub fn set_bytes(bytes: usize) {
+    with_active(|account| account.set_bytes(bytes));
+}
+
+
+pub fn max_bytes() -> usize {
+    return with_active(|account| account.max_bytes).unwrap_or(usize::MAX);
+}
+
+
+pub fn set_max_bytes(max_bytes: usize) {
+    with_active(|account| account.set_max_bytes(max_bytes));
+}
+
+
+// Check if this many more bytes can be allocated by the running VM without exceeding its max_bytes
+pub fn fits(bytes: usize) -> bool {
+    return self::bytes().saturating_add(bytes) <= max_bytes();
+}
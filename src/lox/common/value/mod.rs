@@ -5,11 +5,12 @@ mod test;
 
 
 //mod array;
+pub mod allocations;
 mod obj;
 mod value;
 mod value_iterator;
 
 //pub use array::Array;
-pub use obj::{Array, Closure, Function, FunctionKind, NativeFn, NativeCallable, NativeCallables, Obj};
+pub use obj::{Array, Closure, Function, FunctionKind, InlineCaches, Map, Module, NativeFn, NativeContextFn, NativeCallable, NativeCallables, NativeFunction, Obj};
 pub use value::Value;
 pub use value_iterator::ValueIterator;
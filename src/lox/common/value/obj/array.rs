@@ -5,6 +5,7 @@ mod test;
 
 
 use crate::lox::common::Value;
+use crate::lox::common::allocations::{self, VALUE_SIZE};
 
 
 #[derive(Debug, Clone)]
@@ -29,6 +30,7 @@ impl Array {
 
 
     pub fn push(&mut self, value: Value) {
+        allocations::grow(VALUE_SIZE);
         self.values.push(value);
     }
 
@@ -57,6 +59,7 @@ impl Array {
 
     pub fn extend_from_slice(&mut self, slice: &[Value]) 
     {
+        allocations::grow(slice.len() * VALUE_SIZE);
         self.values.extend_from_slice(slice);
     }
 
@@ -65,6 +68,35 @@ impl Array {
         self.values.truncate(len);
     }
 
+
+    pub fn as_mut_slice(&mut self) -> &mut [Value] {
+        return self.values.as_mut_slice();
+    }
+
+
+    pub fn insert(&mut self, index: usize, value: Value) -> Result<(), String> {
+        if index > self.values.len() { return Err(format!("Bad index {} for insert into array of length {}", index, self.values.len())) };
+        allocations::grow(VALUE_SIZE);
+        self.values.insert(index, value);
+        Ok(())
+    }
+
+
+    pub fn remove(&mut self, index: usize) -> Result<Value, String> {
+        if index >= self.values.len() { return Err(format!("Bad index {} for remove from array of length {}", index, self.values.len())) };
+        return Ok(self.values.remove(index));
+    }
+
+
+    pub fn reverse(&mut self) {
+        self.values.reverse();
+    }
+
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
 }
 
 
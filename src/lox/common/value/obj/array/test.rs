@@ -330,3 +330,39 @@ fn get_array_elements() {
     assert_eq!(array.get(6), None);
 }
 
+
+#[test]
+fn insert() {
+    let mut array: Array = Array::new();
+    array.push(Value::Number(1.0));
+    array.push(Value::Number(3.0));
+    assert_eq!(array.insert(1, Value::Number(2.0)), Ok(()));
+    assert_eq!(array.insert(3, Value::Number(4.0)), Ok(()));
+    assert_eq!(array.to_string(), "[1, 2, 3, 4]");
+    assert_eq!(array.insert(5, Value::Number(5.0)).is_err(), true);
+}
+
+#[test]
+fn remove() {
+    let v = vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)];
+    let mut array: Array = Array::from(v.as_slice());
+    assert_eq!(array.remove(1), Ok(Value::Number(2.0)));
+    assert_eq!(array.to_string(), "[1, 3]");
+    assert_eq!(array.remove(2).is_err(), true);
+}
+
+#[test]
+fn reverse() {
+    let v = vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)];
+    let mut array: Array = Array::from(v.as_slice());
+    array.reverse();
+    assert_eq!(array.to_string(), "[3, 2, 1]");
+}
+
+#[test]
+fn clear() {
+    let v = vec![Value::Number(1.0), Value::Number(2.0)];
+    let mut array: Array = Array::from(v.as_slice());
+    array.clear();
+    assert_eq!(array.len(), 0);
+}
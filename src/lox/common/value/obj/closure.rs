@@ -41,6 +41,11 @@ impl Closure {
     }
 
 
+    pub fn function_value(&self) -> &Value {
+        return &self.function_value;
+    }
+
+
     pub fn add_upvalue(&mut self, upvalue: Upvalue<Value>) {
         println!("Closure.add_upvalue() adding value={} as index={} of closure \"{}\"", upvalue, self.upvalues.len(), self.function_ref().name());
         self.upvalues.push(upvalue);
@@ -54,5 +59,9 @@ impl Closure {
         return &mut self.upvalues[id];
     }
 
+    pub fn upvalues(&self) -> &Vec<Upvalue<Value>> {
+        return &self.upvalues;
+    }
+
 }
 
@@ -4,48 +4,101 @@
 mod test;
 
 
+use std::rc::Rc;
+
+
+use crate::lox::common::Symbol;
+use super::Value;
+
+
 #[derive(Clone)]
 pub struct Constants<T> {
-    values: Vec<T>,
+    values: Rc<Vec<T>>, // Shared with the call frames executing the function
+    // Symbol of each constant that names a field or method, so the VM never
+    // has to hash the string at runtime
+    symbols: Rc<Vec<Option<Symbol>>>,
 }
 
 
-impl<T: std::cmp::PartialEq> Constants<T> {
+impl Constants<Value> {
 
     pub fn new() -> Self {
         Self {
-            values:	vec![],
+            values:	Rc::new(vec![]),
+            symbols:	Rc::new(vec![]),
         }
     }
 
     // O(n) - used at compile time
-    pub fn make(&mut self, value: T) -> usize {
+    pub fn make(&mut self, value: Value) -> usize {
         let id = self.id_by_value(&value);
         match id {
             Some(id) => {
                 return id;
             }
             None => {
-                let id = self.values.len();
-                self.values.push(value);
-                return id; 
+                return self.push(value);
             }
         }
     }
 
     // O(n) - used at compile time
-    pub fn id_by_value(&self, value: &T) -> Option<usize> {
+    // Names are left out: strings are mutable, so a literal must never share their value
+    pub fn id_by_value(&self, value: &Value) -> Option<usize> {
         for (id, v) in self.values.iter().enumerate() {
-            if v == value { return Some(id); }
+            if self.symbols[id].is_none() && v == value { return Some(id); }
         }
         return None;
     }
 
+    // O(n) - used at compile time for the name operand of a property or method opcode
+    pub fn make_name(&mut self, name: &str) -> usize {
+        let symbol = Symbol::intern(name);
+        match self.symbols.iter().position(|s| *s == Some(symbol)) {
+            Some(id) => return id,
+            None => return self.push_name(name),
+        }
+    }
+
+    // O(1) - used when loading precompiled code, where ids must not change
+    pub fn push(&mut self, value: Value) -> usize {
+        Rc::make_mut(&mut self.symbols).push(None);
+        Rc::make_mut(&mut self.values).push(value);
+        return self.values.len() - 1;
+    }
+
+    // O(1) - also used when loading precompiled code.
+    // The constant is the string Value shared by every use of the name
+    pub fn push_name(&mut self, name: &str) -> usize {
+        let symbol = Symbol::intern(name);
+        Rc::make_mut(&mut self.symbols).push(Some(symbol));
+        Rc::make_mut(&mut self.values).push(symbol.value());
+        return self.values.len() - 1;
+    }
+
     // O(1) - used at runtime
-    pub fn value_by_id(&self, id: usize) -> &T {
+    pub fn value_by_id(&self, id: usize) -> &Value {
         return &self.values[id];
     }
 
+    // O(1) - used at runtime
+    pub fn symbol_by_id(&self, id: usize) -> Option<Symbol> {
+        return self.symbols[id];
+    }
+
+    pub fn as_slice(&self) -> &[Value] {
+        return self.values.as_slice();
+    }
+
+    // O(1) - used by the VM to read constants without borrowing the function
+    pub fn shared_values(&self) -> Rc<Vec<Value>> {
+        return Rc::clone(&self.values);
+    }
+
+    pub fn shared_symbols(&self) -> Rc<Vec<Option<Symbol>>> {
+        return Rc::clone(&self.symbols);
+    }
+
 }
 
 
@@ -94,3 +94,32 @@ fn constants_debug() {
     assert_eq!(result, expect);
 }
 
+#[test]
+fn constants_make_name_shared() {
+    let mut constants1 = Constants::<Value>::new();
+    let mut constants2 = Constants::<Value>::new();
+    let id1 = constants1.make_name("constants_shared");
+    let id2 = constants2.make_name("constants_shared");
+    assert!(constants1.value_by_id(id1).is(constants2.value_by_id(id2)));
+    assert_eq!(constants1.value_by_id(id1), constants2.value_by_id(id2));
+    assert_eq!(constants1.symbol_by_id(id1), constants2.symbol_by_id(id2));
+    // Unlike a string literal, which is a string of its own
+    assert!(!constants1.value_by_id(id1).is(&Value::string("constants_shared")));
+}
+
+#[test]
+fn constants_make_name_duplicate() {
+    let mut constants = Constants::<Value>::new();
+    let id1 = constants.make_name("constants_duplicate");
+    let id2 = constants.make_name("constants_duplicate");
+    assert_eq!(id1, id2);
+}
+
+#[test]
+fn constants_literal_not_a_name() {
+    let mut constants = Constants::<Value>::new();
+    let id1 = constants.make_name("constants_literal");
+    let id2 = constants.make(Value::string("constants_literal"));
+    assert_ne!(id1, id2);
+    assert_eq!(constants.symbol_by_id(id2), None);
+}
@@ -1,8 +1,12 @@
 
-use crate::lox::common::{At, Chunk};
+use std::rc::Rc;
+
+
+use crate::lox::common::{Chunk, Location};
 use super::Value;
 use super::constants::Constants;
 use super::function_kind::FunctionKind;
+use super::inline_caches::InlineCaches;
 
 
 //#[allow(dead_code)]
@@ -14,13 +18,15 @@ pub struct Function {
     chunk: Chunk,
     constants: Constants<Value>,
     upvalue_count: usize,
-    at: Option<At>,
+    at: Option<Location>,
+    local_names: Vec<(u32, usize, String)>, // (ip, slot, name) in order of declaration, for disassembly
+    inline_caches: Rc<InlineCaches>, // Runtime state, shared with the call frames executing it
 }
 
 
 #[allow(dead_code)]
 impl Function {
-    pub fn new(name: &str, kind: FunctionKind, at: Option<At>) -> Function {
+    pub fn new(name: &str, kind: FunctionKind, at: Option<Location>) -> Function {
         Function {
             name:		name.to_string(),
             kind,
@@ -29,6 +35,8 @@ impl Function {
             constants:		Constants::new(),
             upvalue_count:	0,
             at,
+            local_names:	vec![],
+            inline_caches:	Rc::new(InlineCaches::new()),
         }
     }
 
@@ -83,10 +91,39 @@ impl Function {
     }
 
 
-    pub fn at(&self) -> &Option<At> {
+    pub fn at(&self) -> &Option<Location> {
         return &self.at;
     }
 
+
+    // Record that code from ip onward refers to a local variable by this name
+    pub fn name_local(&mut self, ip: u32, slot: usize, name: &str) {
+        self.local_names.push((ip, slot, name.to_string()));
+    }
+
+
+    pub fn inline_caches(&self) -> &InlineCaches {
+        return &self.inline_caches;
+    }
+
+
+    pub fn shared_inline_caches(&self) -> Rc<InlineCaches> {
+        return Rc::clone(&self.inline_caches);
+    }
+
+
+    pub fn local_names(&self) -> &[(u32, usize, String)] {
+        return &self.local_names;
+    }
+
+
+    // Name of the local variable held in a slot at ip, if known
+    pub fn local_name(&self, slot: usize, ip: u32) -> Option<&str> {
+        return self.local_names.iter().rev()
+            .find(|(start, s, _)| *s == slot && *start <= ip)
+            .map(|(_, _, name)| name.as_str());
+    }
+
 }
 
 
@@ -6,6 +6,7 @@ pub enum FunctionKind {
     Function,
     Initializer,
     Method,
+    Module,
     Script,
 }
 
@@ -29,8 +30,20 @@ impl FunctionKind {
     }
 
 
+    pub fn as_str(&self) -> &str {
+        match self {
+            FunctionKind::Function => "Function",
+            FunctionKind::Initializer => "Initializer",
+            FunctionKind::Method => "Method",
+            FunctionKind::Module => "Module",
+            FunctionKind::Script => "Script",
+        }
+    }
+
+
     pub fn is_toplevel(&self) -> bool {
         return match self {
+            FunctionKind::Module => true,
             FunctionKind::Script => true,
             _ => false,
         }
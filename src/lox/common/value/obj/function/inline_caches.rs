@@ -0,0 +1,80 @@
+
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+
+use crate::lox::common::Symbol;
+use super::{Obj, Value};
+
+
+// Each method call site and property read of a function has a cache slot
+// remembering which method it found on the class of the last receiver. A call
+// on an instance of the same class can then skip looking the method up by name.
+// Fields are kept per instance rather than laid out by the class, so there is
+// nothing to cache when a property read finds a field or a property is set.
+// Only weak references are kept, so the caches never keep a class alive and
+// never form reference cycles through the functions that own them. As long
+// as the weak reference exists, the address of the class can not be reused,
+// so comparing addresses is enough to identify the class.
+
+
+#[derive(Clone)]
+struct InlineCache {
+    class: Weak<RefCell<Obj>>,
+    name: Symbol,
+    method: Weak<RefCell<Obj>>,
+}
+
+
+pub struct InlineCaches {
+    entries: RefCell<Vec<Option<InlineCache>>>,
+}
+
+
+impl InlineCaches {
+
+    pub fn new() -> Self {
+        InlineCaches {
+            entries:	RefCell::new(vec![]),
+        }
+    }
+
+
+    // Used by the compiler, one slot per call site
+    pub fn new_slot(&self) -> usize {
+        let mut entries = self.entries.borrow_mut();
+        entries.push(None);
+        return entries.len() - 1;
+    }
+
+
+    // Return the method cached for this call site, if the class and name match
+    pub fn lookup(&self, slot: usize, class: &Value, name: Symbol) -> Option<Value> {
+        let class = match class {
+            Value::Obj(class) => class,
+            _ => return None,
+        };
+        let entries = self.entries.borrow();
+        let entry = entries.get(slot)?.as_ref()?;
+        if entry.name != name || entry.class.as_ptr() != Rc::as_ptr(class) { return None; }
+        return entry.method.upgrade().map(Value::Obj);
+    }
+
+
+    pub fn update(&self, slot: usize, class: &Value, name: Symbol, method: &Value) {
+        let (class, method) = match (class, method) {
+            (Value::Obj(class), Value::Obj(method)) => (class, method),
+            _ => return,
+        };
+        let mut entries = self.entries.borrow_mut();
+        // Precompiled code does not record how many slots it uses
+        if slot >= entries.len() { entries.resize(slot + 1, None); }
+        entries[slot] = Some(InlineCache {
+            class: Rc::downgrade(class),
+            name,
+            method: Rc::downgrade(method),
+        });
+    }
+
+}
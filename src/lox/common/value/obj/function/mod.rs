@@ -3,9 +3,11 @@
 mod constants;
 mod function;
 mod function_kind;
+mod inline_caches;
 
 
 pub use constants::Constants;
 pub use function::Function;
 pub use function_kind::FunctionKind;
+pub use inline_caches::InlineCaches;
 pub use super::{Obj, Value};
\ No newline at end of file
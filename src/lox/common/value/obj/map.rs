@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod test;
+
+
+use std::collections::HashMap;
+
+
+use crate::lox::common::Value;
+use crate::lox::common::allocations::{self, VALUE_SIZE};
+
+
+// Hashable representation of a key; only primitives and strings qualify
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MapKey {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+}
+
+
+impl MapKey {
+
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(MapKey::Null),
+            Value::Bool(b) => Ok(MapKey::Bool(*b)),
+            Value::Number(n) => {
+                if n.is_nan() { return Err(format!("Can not use {} as a map key", value)) }
+                // Note: 0 and -0 are equal so they must also be the same key
+                let n = if *n == 0.0 { 0.0 } else { *n };
+                Ok(MapKey::Number(n.to_bits()))
+            }
+            Value::Obj(rc) => {
+                // Note: try_borrow() because the key could be the map itself, borrowed for writing
+                if let Ok(obj) = rc.try_borrow() {
+                    if obj.is_string() { return Ok(MapKey::String(obj.as_string().clone())) }
+                }
+                Err(String::from("Can not use object as a map key"))
+            }
+        }
+    }
+
+}
+
+
+// Entries are kept in insertion order so iteration is predictable.
+// A deleted entry leaves a hole so the others keep their positions, which
+// is what an iterator going through the map holds on to. The holes are
+// removed when a new key is added and they outnumber the entries.
+// Note: Adding keys while iterating may therefore skip some
+#[derive(Debug, Clone)]
+pub struct Map {
+    entries: Vec<Option<(Value, Value)>>,
+    index: HashMap<MapKey, usize>,
+}
+
+
+#[allow(dead_code)]
+impl Map {
+
+    pub fn new() -> Self {
+        Map {
+            entries: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+
+    pub fn len(&self) -> usize {
+        return self.index.len();
+    }
+
+
+    pub fn is_key(key: &Value) -> bool {
+        return MapKey::from_value(key).is_ok();
+    }
+
+
+    pub fn has(&self, key: &Value) -> bool {
+        match MapKey::from_value(key) {
+            Ok(key) => self.index.contains_key(&key),
+            Err(_) => false,
+        }
+    }
+
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        let key = MapKey::from_value(key).ok()?;
+        let index = self.index.get(&key)?;
+        return self.entries[*index].as_ref().map(|(_, value)| value);
+    }
+
+
+    pub fn set(&mut self, key: &Value, value: Value) -> Result<(), String> {
+        let map_key = MapKey::from_value(key)?;
+        match self.index.get(&map_key) {
+            Some(index) => if let Some(entry) = self.entries[*index].as_mut() { entry.1 = value },
+            None => {
+                // Store a copy of a string key so later changes to the original can not affect the map
+                allocations::grow(2 * VALUE_SIZE);
+                if self.entries.len() > 2 * self.index.len() { self.compact(); }
+                self.index.insert(map_key, self.entries.len());
+                self.entries.push(Some((Value::from(key), value)));
+            }
+        }
+        Ok(())
+    }
+
+
+    pub fn delete(&mut self, key: &Value) -> Option<Value> {
+        let key = MapKey::from_value(key).ok()?;
+        let index = self.index.remove(&key)?;
+        let (_, value) = self.entries[index].take()?;
+        return Some(value);
+    }
+
+
+    // Remove the holes left by delete(), renumbering the positions of the entries
+    fn compact(&mut self) {
+        let mut positions = Vec::with_capacity(self.entries.len());
+        let mut next = 0;
+        for entry in &self.entries {
+            positions.push(next);
+            if entry.is_some() { next = next + 1; }
+        }
+        for index in self.index.values_mut() {
+            *index = positions[*index];
+        }
+        self.entries.retain(|entry| entry.is_some());
+    }
+
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.clear();
+    }
+
+
+    // The first entry at or after position, with the position it was found at.
+    // Positions only change when set() removes the holes
+    pub fn entry(&self, position: usize) -> Option<(usize, &Value, &Value)> {
+        let skip = self.entries.get(position..)?;
+        return skip.iter().enumerate()
+            .find_map(|(offset, entry)| entry.as_ref().map(|(key, value)| (position + offset, key, value)));
+    }
+
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Value, Value)> {
+        return self.entries.iter().flatten();
+    }
+
+
+    // Copies, like the keys stored by set(), so the caller can not change a key in place
+    pub fn keys(&self) -> Vec<Value> {
+        return self.iter().map(|(key, _)| Value::from(key)).collect();
+    }
+
+
+    pub fn values(&self) -> Vec<Value> {
+        return self.iter().map(|(_, value)| value.clone()).collect();
+    }
+
+}
+
+
+// Traits
+
+impl std::fmt::Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{{{}}}", self.iter().map(|(key, value)| format!("{}: {}", key, value)).collect::<Vec<String>>().join(", "))
+    }
+}
+
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        if self.len() != other.len() { return false }
+        self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
@@ -0,0 +1,158 @@
+
+
+use super::{Map, Value};
+use crate::lox::common::Array;
+
+
+#[test]
+fn new() {
+    let _map: Map = Map::new();
+}
+
+#[test]
+fn display() {
+    let map: Map = Map::new();
+    let string = format!("{}", map);
+    assert_eq!(string.as_str(), "{}");
+}
+
+#[test]
+fn len_zero() {
+    let map: Map = Map::new();
+    assert_eq!(map.len(), 0);
+}
+
+#[test]
+fn set_get() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    map.set(&Value::Number(2.0), Value::string("b")).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&Value::string("a")), Some(&Value::Number(1.0)));
+    assert_eq!(map.get(&Value::Number(2.0)), Some(&Value::string("b")));
+    assert_eq!(map.get(&Value::string("2")), None);
+    let string = format!("{}", map);
+    assert_eq!(string.as_str(), "{a: 1, 2: b}");
+}
+
+#[test]
+fn set_replace() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    map.set(&Value::string("a"), Value::Number(2.0)).unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&Value::string("a")), Some(&Value::Number(2.0)));
+}
+
+#[test]
+fn primitive_keys() {
+    let mut map: Map = Map::new();
+    map.set(&Value::Null, Value::Number(1.0)).unwrap();
+    map.set(&Value::Bool(true), Value::Number(2.0)).unwrap();
+    map.set(&Value::Bool(false), Value::Number(3.0)).unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(&Value::Null), Some(&Value::Number(1.0)));
+    assert_eq!(map.get(&Value::Bool(true)), Some(&Value::Number(2.0)));
+    assert_eq!(map.get(&Value::Bool(false)), Some(&Value::Number(3.0)));
+}
+
+#[test]
+fn negative_zero_key() {
+    let mut map: Map = Map::new();
+    map.set(&Value::Number(-0.0), Value::Number(1.0)).unwrap();
+    assert_eq!(map.get(&Value::Number(0.0)), Some(&Value::Number(1.0)));
+}
+
+#[test]
+fn bad_keys() {
+    let mut map: Map = Map::new();
+    assert!(map.set(&Value::Number(f64::NAN), Value::Null).is_err());
+    assert!(map.set(&Value::array(Array::new()), Value::Null).is_err());
+    assert_eq!(map.len(), 0);
+}
+
+#[test]
+fn has() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Null).unwrap();
+    assert_eq!(map.has(&Value::string("a")), true);
+    assert_eq!(map.has(&Value::string("b")), false);
+}
+
+#[test]
+fn delete() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    map.set(&Value::string("b"), Value::Number(2.0)).unwrap();
+    map.set(&Value::string("c"), Value::Number(3.0)).unwrap();
+    assert_eq!(map.delete(&Value::string("b")), Some(Value::Number(2.0)));
+    assert_eq!(map.delete(&Value::string("b")), None);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&Value::string("c")), Some(&Value::Number(3.0)));
+    let string = format!("{}", map);
+    assert_eq!(string.as_str(), "{a: 1, c: 3}");
+}
+
+#[test]
+fn delete_many() {
+    let mut map: Map = Map::new();
+    for i in 0..1000 {
+        map.set(&Value::Number(i as f64), Value::Number(i as f64)).unwrap();
+    }
+    // Delete every entry but each tenth, then add one which removes the holes
+    for i in 0..1000 {
+        if i % 10 != 0 { map.delete(&Value::Number(i as f64)); }
+    }
+    map.set(&Value::Number(5.0), Value::Null).unwrap();
+    map.delete(&Value::Number(0.0));
+    let expected: Vec<Value> = (1..100).map(|i| Value::Number(i as f64 * 10.0)).chain([Value::Number(5.0)]).collect();
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.keys(), expected);
+    assert_eq!(map.get(&Value::Number(990.0)), Some(&Value::Number(990.0)));
+    assert_eq!(map.get(&Value::Number(5.0)), Some(&Value::Null));
+}
+
+#[test]
+fn delete_all() {
+    let mut map: Map = Map::new();
+    for i in 0..100 {
+        map.set(&Value::Number(i as f64), Value::Null).unwrap();
+    }
+    for i in 0..100 {
+        assert_eq!(map.delete(&Value::Number(i as f64)), Some(Value::Null));
+    }
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.keys(), Vec::<Value>::new());
+    map.set(&Value::string("a"), Value::Null).unwrap();
+    assert_eq!(map.keys(), vec![Value::string("a")]);
+}
+
+#[test]
+fn keys_values() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    map.set(&Value::string("b"), Value::Number(2.0)).unwrap();
+    assert_eq!(map.keys(), vec![Value::string("a"), Value::string("b")]);
+    assert_eq!(map.values(), vec![Value::Number(1.0), Value::Number(2.0)]);
+}
+
+#[test]
+fn keys_are_copies() {
+    let mut map: Map = Map::new();
+    map.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    map.keys()[0].as_string_mut().push_str("b");
+    assert_eq!(map.keys(), vec![Value::string("a")]);
+}
+
+#[test]
+fn eq_ignores_order() {
+    let mut a: Map = Map::new();
+    a.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    a.set(&Value::string("b"), Value::Number(2.0)).unwrap();
+    let mut b: Map = Map::new();
+    b.set(&Value::string("b"), Value::Number(2.0)).unwrap();
+    b.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    assert_eq!(a == b, true);
+    b.set(&Value::string("a"), Value::Number(3.0)).unwrap();
+    assert_eq!(a == b, false);
+}
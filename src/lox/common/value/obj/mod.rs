@@ -3,14 +3,18 @@
 mod array;
 mod closure;
 mod function;
+mod map;
+mod module;
 mod native;
 mod obj;
 
 
 pub use array::Array;
 pub use closure::Closure;
-pub use function::{Function, FunctionKind};
-pub use native::{NativeFn, NativeCallable, NativeCallables};
+pub use function::{Function, FunctionKind, InlineCaches};
+pub use map::Map;
+pub use module::Module;
+pub use native::{NativeFn, NativeContextFn, NativeCallable, NativeCallables, NativeFunction};
 pub use obj::Obj;
 
 
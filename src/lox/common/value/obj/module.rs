@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+
+use crate::lox::common::Value;
+
+
+// A compiled source file imported by another. The module's globals live in the
+// VM's table under names that can not collide with those of other modules;
+// exports map the names visible to importers to their global ids.
+#[derive(Debug, Clone)]
+pub struct Module {
+    name: String,
+    main: Value, // Function containing the top level code
+    exports: HashMap<String, usize>,
+}
+
+
+#[allow(dead_code)]
+impl Module {
+
+    pub fn new(name: &str, main: Value, exports: HashMap<String, usize>) -> Self {
+        if !main.is_function() {
+            panic!("{} is not a Function", main);
+        }
+        Module {
+            name: String::from(name),
+            main,
+            exports,
+        }
+    }
+
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+
+    pub fn main(&self) -> &Value {
+        return &self.main;
+    }
+
+
+    // Return the global id of an exported name
+    pub fn export(&self, name: &str) -> Option<usize> {
+        return self.exports.get(name).cloned();
+    }
+
+
+    pub fn exports(&self) -> &HashMap<String, usize> {
+        return &self.exports;
+    }
+
+}
+
+
+impl std::fmt::Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
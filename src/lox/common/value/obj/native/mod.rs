@@ -2,11 +2,14 @@
 mod native_callable;
 mod native_callables;
 
-pub use native_callable::NativeCallable;
+pub use native_callable::{NativeCallable, NativeFunction};
 pub use native_callables::NativeCallables;
 
 use crate::lox::common::Value;
-use crate::lox::vm::RuntimeError;
+use crate::lox::vm::{RuntimeError, VM};
 
 pub type NativeFn = fn(&mut [Value]) -> Result<Value, RuntimeError>;
 
+// Natives that need to call back into the VM, e.g. a closure passed as an argument.
+// May capture Rust state. Note: args is a copy of the arguments on the stack.
+pub type NativeContextFn = dyn Fn(&mut VM, &mut [Value]) -> Result<Value, RuntimeError>;
@@ -1,12 +1,23 @@
 
 
-use super::NativeFn;
+use std::rc::Rc;
+
+
+use super::{NativeFn, NativeContextFn};
+
+
+// How a native callable is invoked by the VM
+#[derive(Clone)]
+pub enum NativeFunction {
+    Plain(NativeFn),                // Operates on the stack in place
+    Context(Rc<NativeContextFn>),   // Receives a handle to the VM
+}
 
 
 #[derive(Clone)]
 pub struct NativeCallable {
     name: String,
-    callable: NativeFn,
+    callable: NativeFunction,
     arity: usize,
 }
 
@@ -16,7 +27,16 @@ impl NativeCallable {
     pub fn new(name: String, callable: NativeFn, arity: usize) -> Self {
         NativeCallable {
             name, 
-            callable, 
+            callable: NativeFunction::Plain(callable), 
+            arity, 
+        }
+    }
+
+
+    pub fn with_context(name: String, callable: Box<NativeContextFn>, arity: usize) -> Self {
+        NativeCallable {
+            name, 
+            callable: NativeFunction::Context(Rc::from(callable)), 
             arity, 
         }
     }
@@ -32,7 +52,7 @@ impl NativeCallable {
     }
 
 
-    pub fn callable(&self) -> &NativeFn {
+    pub fn callable(&self) -> &NativeFunction {
         return &self.callable;
     }
 
@@ -3,16 +3,19 @@
 use std::collections::HashMap;
 
 
+use crate::lox::common::{Symbol, SymbolMap};
 use super::Value;
-use super::{NativeFn, NativeCallable};
+use super::{NativeFn, NativeContextFn, NativeCallable};
 
 // Built-in functions and methods
 
 
 #[allow(dead_code)]
 pub struct NativeCallables {
-    methods: HashMap<String, Value>,
+    methods: SymbolMap<Value>, // Looked up by the VM for every property not found on an instance
     functions: HashMap<String, Value>,
+    constants: HashMap<String, Value>, // Globals that are not callable, e.g. namespaces
+    globals: Vec<String>, // Names of the functions and constants in the order they were inserted
 }
 
 
@@ -21,30 +24,88 @@ impl NativeCallables {
 
     pub fn new() -> Self {
         NativeCallables {
-            methods: HashMap::new(),
+            methods: SymbolMap::default(),
             functions: HashMap::new(),
+            constants: HashMap::new(),
+            globals: vec![],
         }
     }
 
 
     pub fn insert_method(&mut self, name: &str, method: NativeFn, arity: usize) {
         let callable = NativeCallable::new(name.to_string(), method, arity);
-        let _ = self.methods.insert(name.to_string(), Value::native(callable));
+        let _ = self.methods.insert(Symbol::intern(name), Value::native(callable));
     }
 
 
-    pub fn insert_function(&mut self, name: &str, function: NativeFn, arity: usize) {
+    // Like insert_method() but the callable receives a handle to the VM
+    pub fn insert_context_method(&mut self, name: &str, method: Box<NativeContextFn>, arity: usize) {
+        let callable = NativeCallable::with_context(name.to_string(), method, arity);
+        let _ = self.methods.insert(Symbol::intern(name), Value::native(callable));
+    }
+
+
+    // Functions and values share one namespace, the globals of a script.
+    // Returns false and keeps the existing global if the name is already taken
+    pub fn insert_function(&mut self, name: &str, function: NativeFn, arity: usize) -> bool {
         let callable = NativeCallable::new(name.to_string(), function, arity);
-        let _ = self.methods.insert(name.to_string(), Value::native(callable));
+        return self.insert_global(name, Value::native(callable), true);
+    }
+
+
+    // Like insert_function() but the callable receives a handle to the VM
+    pub fn insert_context_function(&mut self, name: &str, function: Box<NativeContextFn>, arity: usize) -> bool {
+        let callable = NativeCallable::with_context(name.to_string(), function, arity);
+        return self.insert_global(name, Value::native(callable), true);
+    }
+
+
+    // Any other global provided by the host, e.g. a namespace object
+    pub fn insert_value(&mut self, name: &str, value: Value) -> bool {
+        return self.insert_global(name, value, false);
+    }
+
+
+    fn insert_global(&mut self, name: &str, value: Value, callable: bool) -> bool {
+        if self.functions.contains_key(name) || self.constants.contains_key(name) { return false; }
+        if callable {
+            self.functions.insert(name.to_string(), value);
+        } else {
+            self.constants.insert(name.to_string(), value);
+        }
+        self.globals.push(name.to_string());
+        return true;
     }
 
 
     pub fn get_method(&self, name: &str) -> Option<&Value> {
-        return self.methods.get(name);
+        return self.methods.get(&Symbol::find(name)?);
+    }
+
+    pub fn get_method_by_symbol(&self, name: Symbol) -> Option<&Value> {
+        return self.methods.get(&name);
     }
 
     pub fn get_function(&self, name: &str) -> Option<&Value> {
         return self.functions.get(name);
     }
 
+    pub fn get_value(&self, name: &str) -> Option<&Value> {
+        return self.constants.get(name);
+    }
+
+    // Used by the compiler to declare the functions and other values as globals.
+    // The order is stable so precompiled code finds them at the same ids
+    pub fn globals(&self) -> impl Iterator<Item = (&String, &Value)> {
+        return self.globals.iter().map(move |name| {
+            let value = self.functions.get(name).or_else(|| self.constants.get(name));
+            (name, value.expect("Internal error: global without a value"))
+        });
+    }
+
+    // Iterate over all methods, functions and other values
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        return self.methods.values().chain(self.functions.values()).chain(self.constants.values());
+    }
+
 }
@@ -3,7 +3,8 @@
 use crate::lox::common::Function;
 use crate::lox::vm::{Class, Instance, Method, NativeMethod};
 use crate::lox::common::Closure;
-use crate::lox::common::{Array, Value, ValueIterator};
+use crate::lox::common::{Array, Map, Module, Value, ValueIterator};
+use crate::lox::common::allocations::{OBJ_SIZE, VALUE_SIZE};
 use super::NativeCallable;
 
 
@@ -16,7 +17,9 @@ pub enum Obj {
     Closure(Closure),
     Instance(Instance),
     Iterator(ValueIterator),
+    Map(Map),
     Method(Method),
+    Module(Module),
     Native(NativeCallable),
     NativeMethod(NativeMethod),
     String(String),
@@ -45,10 +48,17 @@ impl Obj {
     pub fn iterator(i: ValueIterator) -> Obj {
         Obj::Iterator(i)
     }
+    pub fn map(m: Map) -> Obj {
+        Obj::Map(m)
+    }
     pub fn method(m: Method) -> Obj {
         Obj::Method(m)
     }
 
+    pub fn module(m: Module) -> Obj {
+        Obj::Module(m)
+    }
+
 
     pub fn native(nc: NativeCallable) -> Obj {
         Obj::Native(nc)
@@ -66,6 +76,22 @@ impl Obj {
 
 }
 
+
+impl Obj {
+    // Approximate number of bytes used, for the memory limit.
+    // Only objects that can grow without bounds count their contents.
+    pub fn size(&self) -> usize {
+        let contents = match self {
+            Obj::Array(array) => array.len() * VALUE_SIZE,
+            Obj::Instance(instance) => instance.field_count() * 2 * VALUE_SIZE,
+            Obj::Map(map) => map.len() * 2 * VALUE_SIZE,
+            Obj::String(string) => string.len(),
+            _ => 0,
+        };
+        return OBJ_SIZE + contents;
+    }
+}
+
 #[allow(dead_code)]
 impl Obj {
     // ======== Variant checks ========
@@ -112,6 +138,13 @@ impl Obj {
         }
     }
 
+    pub fn is_map(&self) -> bool {
+        match self {
+            Obj::Map(_) 	=> true,
+            _			=> false,
+        }
+    }
+
     pub fn is_method(&self) -> bool {
         match self {
             Obj::Method(_) 	=> true,
@@ -119,6 +152,13 @@ impl Obj {
         }
     }
 
+    pub fn is_module(&self) -> bool {
+        match self {
+            Obj::Module(_) 	=> true,
+            _			=> false,
+        }
+    }
+
     pub fn is_native(&self) -> bool {
         match self {
             Obj::Native(_) 	=> true,
@@ -152,6 +192,7 @@ impl Obj {
             Obj::Array(_) => true,
             Obj::Class(_) => true,
             Obj::Instance(_) => true,
+            Obj::Map(_) => true,
             Obj::String(_) => true,
             _ => false,
         }
@@ -168,6 +209,7 @@ impl Obj {
             }
             Obj::Class(c) => if key.is_string() { c.get(key.as_string().as_str()).cloned() } else { None },
             Obj::Instance(i) => if key.is_string() { i.get(key.as_string().as_str()).cloned() } else { None },
+            Obj::Map(m) => m.get(key).cloned(),
             Obj::String(s) => {
                 if !key.is_number() { return None }
                 let index = key.as_number().floor();
@@ -187,6 +229,7 @@ impl Obj {
             Obj::Array(_) => true,
             Obj::Class(_) => false, // MUST NOT modify a class after declaration!
             Obj::Instance(_) => true,
+            Obj::Map(_) => true,
             Obj::String(_) => true,
             _ => false,
         }
@@ -201,9 +244,13 @@ impl Obj {
                 if index < 0.0 || index >= a.len() as f64 { return Err(format!("Bad subscript {} for {}", key, a)) }
                 return a.set(index as usize, value);
             }         
-            Obj::Instance(i) => if key.is_string() { Ok(i.set(key.as_string().as_str(), value)) } else { 
+            Obj::Instance(i) => if key.is_string() {
+                if i.is_read_only() { return Err(format!("Can not set field '{}' of read-only {}", key.as_string().as_str(), i)) }
+                Ok(i.set(key.as_string().as_str(), value))
+            } else { 
                 return Err(format!("Invalid subscript '{}' for {}", key, i)); 
             },
+            Obj::Map(m) => m.set(key, value),
             Obj::String(s) => {
                 // Check the index (note that index >= s.chars() will be checked further down so we don't walk the string twice)
                 if !key.is_number() { return Err(format!("Invalid subscript index '{}' for {}", key, self)) }
@@ -329,6 +376,20 @@ impl Obj {
         }
     }
 
+    pub fn as_map(&self) -> &Map {
+        match self {
+            Obj::Map(m) => return m,
+            _ => panic!("{:?} is not a Map Object", self),
+        }
+    }
+
+    pub fn as_map_mut(&mut self) -> &mut Map {
+        match self {
+            Obj::Map(m) => return m,
+            _ => panic!("{:?} is not a Map Object", self),
+        }
+    }
+
     pub fn as_method(&self) -> &Method {
         match self {
             Obj::Method(m) => return m,
@@ -336,6 +397,13 @@ impl Obj {
         }
     }
 
+    pub fn as_module(&self) -> &Module {
+        match self {
+            Obj::Module(m) => return m,
+            _ => panic!("{:?} is not a Module Object", self),
+        }
+    }
+
     pub fn as_native(&self) -> &NativeCallable {
         match self {
             Obj::Native(nc) => return nc,
@@ -372,6 +440,7 @@ impl Obj {
     pub fn len(&self) -> Option<usize> {
         match self {
             Obj::Array(a) => Some(a.len()),
+            Obj::Map(m) => Some(m.len()),
             Obj::String(s) => Some(s.chars().count()),
             _ => None,
         }
@@ -504,6 +573,7 @@ impl PartialEq for Obj {
                 //println!("comparing Obj::Arrays");
                 a.eq(b)
             }
+            (Obj::Map(a), Obj::Map(b))           => a.eq(b),
             (Obj::String(a), Obj::String(b)) 	 => a.eq(b),
             // All other Obj types must be same object
             (Obj::Function(a), Obj::Function(b)) => std::ptr::eq(a, b),
@@ -512,6 +582,7 @@ impl PartialEq for Obj {
             (Obj::Instance(a), Obj::Instance(b)) => std::ptr::eq(a, b),
             (Obj::Iterator(a), Obj::Iterator(b)) => std::ptr::eq(a, b),
             (Obj::Method(a), Obj::Method(b)) 	 => std::ptr::eq(a, b),
+            (Obj::Module(a), Obj::Module(b)) 	 => std::ptr::eq(a, b),
             _ => false, // Obj types mismatch
         }
     }
@@ -548,9 +619,15 @@ impl std::fmt::Display for Obj {
             Obj::Iterator(iter) => {
                 write!(f, "Obj::Iterator({})", iter)
             }
+            Obj::Map(m) => {
+                write!(f, "Obj::Map({})", m)
+            }
             Obj::Method(m) => {
                 write!(f, "Obj::Method({}.{})", m.receiver_class_name(), m.method_name())
             }
+            Obj::Module(m) => {
+                write!(f, "Obj::Module({})", m)
+            }
             Obj::Native(nc) => {
                 write!(f, "Obj::Native({})", nc.name())
             }
@@ -572,7 +649,9 @@ impl From<&Obj> for Obj {
             Obj::Closure(c) => Obj::Closure(c.clone()),
             Obj::Instance(i) => Obj::Instance(i.clone()),
             Obj::Iterator(i) => Obj::Iterator(i.clone()),
+            Obj::Map(m) => Obj::Map(m.clone()),
             Obj::Method(m) => Obj::Method(m.clone()),
+            Obj::Module(m) => Obj::Module(m.clone()),
             Obj::Native(nc) => Obj::Native(nc.clone()),
             Obj::NativeMethod(nm) => Obj::NativeMethod(nm.clone()),
             Obj::String(s) => Obj::String(s.clone()),
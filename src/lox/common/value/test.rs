@@ -216,3 +216,31 @@ fn set_into_utf8_string() {
     assert_eq!(s, Value::string("abc"));
 }
 
+
+#[test]
+fn bitwise_integer_part() {
+    let a = Value::number(6.9);
+    let b = Value::number(3.2);
+    assert_eq!(a.bitwise_and(&b), Ok(Value::number(2.0)));
+    assert_eq!(a.bitwise_or(&b), Ok(Value::number(7.0)));
+    assert_eq!(a.bitwise_xor(&b), Ok(Value::number(5.0)));
+    assert_eq!(a.bitwise_not(), Ok(Value::number(-7.0)));
+}
+
+#[test]
+fn bitwise_not_a_number() {
+    let a = Value::number(1.0);
+    assert_eq!(a.bitwise_and(&Value::string("1")).is_err(), true);
+    assert_eq!(a.bitwise_or(&Value::null()).is_err(), true);
+    assert_eq!(a.bitwise_xor(&Value::number(f64::NAN)).is_err(), true);
+    assert_eq!(Value::boolean(true).bitwise_not().is_err(), true);
+}
+
+#[test]
+fn shift() {
+    let a = Value::number(-8.0);
+    assert_eq!(a.shift_left(&Value::number(2.0)), Ok(Value::number(-32.0)));
+    assert_eq!(a.shift_right(&Value::number(2.0)), Ok(Value::number(-2.0)));
+    assert_eq!(a.shift_left(&Value::number(64.0)).is_err(), true);
+    assert_eq!(a.shift_right(&Value::number(-1.0)).is_err(), true);
+}
@@ -3,12 +3,13 @@ use std::rc::Rc;
 use std::cell::{RefCell, Ref, RefMut};
 
 
-use super::{Closure, Function, NativeCallable, Obj};
+use super::{Closure, Function, Map, Module, NativeCallable, Obj};
 use crate::lox::vm::{Class, Method, NativeMethod, Instance};
 
 
 use super::Array;
 use super::ValueIterator;
+use super::allocations;
 
 
 #[derive(Debug)]
@@ -37,47 +38,63 @@ impl Value {
     }
 
     pub fn array(a: Array) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::array(a))))
+        Value::alloc(Obj::array(a))
     }
 
     pub fn function(f: Function) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::function(f))))
+        Value::alloc(Obj::function(f))
     }
 
     pub fn class(c: Class) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::class(c))))
+        Value::alloc(Obj::class(c))
     }
 
     pub fn closure(c: Closure) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::closure(c))))
+        Value::alloc(Obj::closure(c))
     }
 
     pub fn instance(i: Instance) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::instance(i))))
+        Value::alloc(Obj::instance(i))
     }
 
     pub fn iterator(i: ValueIterator) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::iterator(i))))
+        Value::alloc(Obj::iterator(i))
+    }
+
+    pub fn map(m: Map) -> Value {
+        Value::alloc(Obj::map(m))
     }
 
     pub fn method(m: Method) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::method(m))))
+        Value::alloc(Obj::method(m))
+    }
+
+    pub fn module(m: Module) -> Value {
+        Value::alloc(Obj::module(m))
     }
 
     // A "native" in this context is a Rust function that may be called as a function or bound as a native method
     // Prepared as part of the VM setup and stored in NativeCallables as either a function or method
     pub fn native(nc: NativeCallable) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::native(nc))))
+        Value::alloc(Obj::native(nc))
     }
 
     // A "native_method" is a Rust function that has been bound to a receiver and is ready to be called as a method
     // Generated by the VM at runtime
     pub fn native_method(nm: NativeMethod) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::native_method(nm))))
+        Value::alloc(Obj::native_method(nm))
     }
 
     pub fn string(s: &str) -> Value {
-        Value::Obj(Rc::new(RefCell::new(Obj::string(s))))
+        Value::alloc(Obj::string(s))
+    }
+
+
+    // All heap objects are allocated here so the garbage collector can find them
+    fn alloc(obj: Obj) -> Value {
+        let rc = Rc::new(RefCell::new(obj));
+        allocations::register(&rc);
+        Value::Obj(rc)
     }
 
 
@@ -151,6 +168,20 @@ impl Value {
         }
     }
 
+    pub fn is_map(&self) -> bool {
+        match self {
+            Value::Obj(obj) 	=> RefCell::borrow(obj).is_map(),
+            _ 			=> false
+        }
+    }
+
+    pub fn is_module(&self) -> bool {
+        match self {
+            Value::Obj(obj) 	=> RefCell::borrow(obj).is_module(),
+            _ 			=> false
+        }
+    }
+
     pub fn is_iterator(&self) -> bool {
         match self {
             Value::Obj(obj) 	=> RefCell::borrow(obj).is_iterator(),
@@ -406,6 +437,39 @@ impl Value {
         }
     }
     
+    pub fn as_map(&self) -> Ref<'_, Map> {
+        match self {
+            Value::Obj(obj)	=> {
+                Ref::map(obj.borrow(), |o| o.as_map())
+            }
+            _			=> {
+                panic!("{} is not an object", self)
+            }
+        }
+    }
+    
+    pub fn as_map_mut(&self) -> RefMut<'_, Map> {
+        match self {
+            Value::Obj(obj)	=> {
+                RefMut::map(obj.borrow_mut(), |o| o.as_map_mut())
+            }
+            _			=> {
+                panic!("{} is not an object", self)
+            }
+        }
+    }
+    
+    pub fn as_module(&self) -> Ref<'_, Module> {
+        match self {
+            Value::Obj(obj)	=> {
+                Ref::map(obj.borrow(), |o| o.as_module())
+            }
+            _			=> {
+                panic!("{} is not an object", self)
+            }
+        }
+    }
+    
     pub fn as_method(&self) -> Ref<'_, Method> {
         match self {
             Value::Obj(obj)	=> {
@@ -553,6 +617,62 @@ impl Value {
         return Err(format!("Can not divide operands {} and {}", &self, &other));
     }
 
+    // Bitwise operators work on the integer part of a number
+    fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) if n.is_finite() => Some(n.trunc() as i64),
+            _ => None,
+        }
+    }
+
+    pub fn bitwise_and(self: &Value, other: &Value) -> Result<Value, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => return Ok(Value::number((a & b) as f64)),
+            _ => return Err(format!("Can not bitwise AND operands {} and {}", &self, &other)),
+        }
+    }
+
+    pub fn bitwise_or(self: &Value, other: &Value) -> Result<Value, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => return Ok(Value::number((a | b) as f64)),
+            _ => return Err(format!("Can not bitwise OR operands {} and {}", &self, &other)),
+        }
+    }
+
+    pub fn bitwise_xor(self: &Value, other: &Value) -> Result<Value, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => return Ok(Value::number((a ^ b) as f64)),
+            _ => return Err(format!("Can not bitwise XOR operands {} and {}", &self, &other)),
+        }
+    }
+
+    pub fn bitwise_not(self: &Value) -> Result<Value, String> {
+        match self.as_integer() {
+            Some(a) => return Ok(Value::number(!a as f64)),
+            _ => return Err(format!("Can not bitwise NOT operand {}", &self)),
+        }
+    }
+
+    pub fn shift_left(self: &Value, other: &Value) -> Result<Value, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => {
+                if b < 0 || b > 63 { return Err(format!("Can not shift by {} bits", b)) }
+                return Ok(Value::number((a << b) as f64));
+            }
+            _ => return Err(format!("Can not shift operands {} and {}", &self, &other)),
+        }
+    }
+
+    pub fn shift_right(self: &Value, other: &Value) -> Result<Value, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => {
+                if b < 0 || b > 63 { return Err(format!("Can not shift by {} bits", b)) }
+                return Ok(Value::number((a >> b) as f64)); // Note: Arithmetic shift, keeps the sign
+            }
+            _ => return Err(format!("Can not shift operands {} and {}", &self, &other)),
+        }
+    }
+
 }
 
 
@@ -577,6 +697,8 @@ impl PartialEq for Value {
             (Value::Bool(a), Value::Bool(b)) => a == b,
             // Note: NAN != NAN, INF != INF, -INF != -INF
             (Value::Number(a), Value::Number(b)) => a.eq(b),
+            // Interned names share one string, so comparing them costs no more than a pointer
+            (Value::Obj(ra), Value::Obj(rb)) if Rc::ptr_eq(ra, rb) && ra.borrow().is_string() => true,
             (Value::Obj(ra), Value::Obj(rb)) => ra.borrow().eq(&rb.borrow()),
             _ => false, // Value types mismatch
         }    
@@ -619,7 +741,7 @@ impl From<&Value> for Value {
             // Clone the inner Obj, not the Rc<RefCell<Obj>>
             Value::Obj(obj) => {
                 let copy = Obj::from(obj.borrow().clone());
-                Value::Obj(Rc::new(RefCell::new(copy)))
+                Value::alloc(copy)
             }
         }
     }
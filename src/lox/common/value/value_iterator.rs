@@ -14,6 +14,7 @@ pub enum ValueIterator {
     String(Value, usize, Value), // String value, byte index of next character, last value (or Value::Null)
     Array(Value, usize, Value), // Array value, index of next element, last value (or Value::Null)
     Instance(Value, Value), // Instance value, last value (or Value::Null)
+    Map(Value, usize, Value), // Map value, index of next entry, last key (or Value::Null)
 }
 
 
@@ -23,6 +24,7 @@ impl ValueIterator {
         if value.is_string() { return ValueIterator::new_string(value) }
         if value.is_array() { return ValueIterator::new_array(value) }
         if value.is_instance() { return ValueIterator::new_instance(value) }
+        if value.is_map() { return ValueIterator::new_map(value) }
         Err(format!("Can not iterate over {}", value))
     }
 
@@ -37,6 +39,11 @@ impl ValueIterator {
     }
 
 
+    fn new_map(m: Value) -> Result<Self, String> {
+        Ok(ValueIterator::Map(m, 0, Value::Null))
+    }
+
+
     fn new_instance(ivalue: Value) -> Result<Self, String> {
         //println!("new_instance() iterator from {}", ivalue);
         match ivalue.as_instance().class().get(&Value::string(KEYWORD_NEXT)) {
@@ -56,6 +63,7 @@ impl ValueIterator {
             ValueIterator::String(..) => return self.next_in_string(),
             ValueIterator::Array(..) => return self.next_in_array(),
             ValueIterator::Instance(..) => return self.next_in_instance(),
+            ValueIterator::Map(..) => return self.next_in_map(),
         }
     }
 
@@ -97,6 +105,30 @@ impl ValueIterator {
     }
 
 
+    fn next_in_map(&mut self) -> (&Value, Option<&Value>) {
+        // Iterating over a map yields copies of the keys, in insertion order
+        if let ValueIterator::Map(m, index, last) = self {
+            let key = match m.as_map().entry(*index) {
+                None => None,
+                Some((position, key, _)) => Some((position, Value::from(key))),
+            };
+            match key {
+                None => {
+                    // Reached end of map
+                    return (m, None);
+                }
+                Some((position, key)) => {
+                    *last = key;
+                    *index = position + 1;
+                    return (m, Some(last));
+                }
+            }
+        } else {
+            panic!("Internal error");
+        }
+    }
+
+
     fn next_in_instance(&self) -> (&Value, Option<&Value>) {
         // This one is a little different; rather than provide the next value we provide the means to get it.
         // The VM will know what to do when it sees a Method value.
@@ -113,6 +145,7 @@ impl ValueIterator {
             ValueIterator::String(_, _, last) => return last,
             ValueIterator::Array(_, _, last) => return last,
             ValueIterator::Instance(_, last) => return last,
+            ValueIterator::Map(_, _, last) => return last,
         }
     }
 
@@ -131,6 +164,9 @@ impl std::fmt::Display for ValueIterator {
             ValueIterator::Instance(ivalue, ..) => {
                 write!(f, "ValueIterator::Instance({})", ivalue.as_instance())
             }
+            ValueIterator::Map(mvalue, ..) => {
+                write!(f, "ValueIterator::Map({})", mvalue.as_map())
+            }
         }
     }
 }
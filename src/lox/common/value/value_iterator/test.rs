@@ -1,6 +1,7 @@
 
 
 use super::Array;
+use crate::lox::common::Map;
 use super::Value;
 use super::ValueIterator;
 
@@ -68,3 +69,26 @@ fn array_iterator_three() {
     let (val, next) = iter.next();
     assert_eq!((val, next), (&array, None));
 }
+
+#[test]
+fn map_iterator_empty() {
+    let map = Value::map(Map::new());
+    let mut iter = ValueIterator::new(map.clone()).unwrap();
+    let (val, next) = iter.next();
+    assert_eq!((val, next), (&map, None));
+}
+
+#[test]
+fn map_iterator_keys() {
+    let mut m = Map::new();
+    m.set(&Value::string("a"), Value::Number(1.0)).unwrap();
+    m.set(&Value::Number(2.0), Value::string("b")).unwrap();
+    let map = Value::map(m);
+    let mut iter = ValueIterator::new(map.clone()).unwrap();
+    let (val, next) = iter.next();
+    assert_eq!((val, next.unwrap()), (&map, &Value::string("a")));
+    let (val, next) = iter.next();
+    assert_eq!((val, next.unwrap()), (&map, &Value::Number(2.0)));
+    let (val, next) = iter.next();
+    assert_eq!((val, next), (&map, None));
+}
@@ -7,22 +7,29 @@
 // user perspective, I have chosen to call this a ChunkWriter instead.
 // What I now call the "compiler" is what drives the parser.
 
-use crate::lox::common::{OpCode, OpCodeSet, Value, Function};
+use std::rc::Rc;
+
+
+use crate::lox::common::{At, OpCode, OpCodeSet, Value, Function};
 
 
 // ======== Layout ========
 pub struct ChunkWriter {
     function: 	Option<Function>,
+    file:	Rc<str>, // Name of the source file, for the locations in the chunk
+    at:		Option<At>, // Source location of the code being emitted
 }
 
 
 // ======== Public interface ========
 //#[allow(dead_code)]
 impl ChunkWriter {
-    pub fn new(function: Function) -> ChunkWriter {
+    pub fn new(function: Function, file: &Rc<str>) -> ChunkWriter {
         //println!("Compiler::new()");
         ChunkWriter {
             function: 	Some(function),
+            file:	Rc::clone(file),
+            at:		None,
         }
     }
 
@@ -32,6 +39,24 @@ impl ChunkWriter {
         return function;
     }
 
+    pub fn file(&self) -> &Rc<str> {
+        return &self.file;
+    }
+
+    // Set the source location for subsequent opcodes, return the previous one
+    pub fn set_at(&mut self, at: Option<At>) -> Option<At> {
+        return std::mem::replace(&mut self.at, at);
+    }
+
+    // Keep the name of a local variable for disassembly
+    pub fn name_local(&mut self, slot: usize, name: &str) {
+        let ip = self.current_ip();
+        self.function
+            .as_mut()
+            .expect("Internal error: self.function is None")
+            .name_local(ip, slot, name);
+    }
+
     pub fn current_ip(&self) -> u32 {
         return self.function
             .as_ref()
@@ -68,6 +93,13 @@ impl ChunkWriter {
     }
 
     pub fn emit_op(&mut self, opcode: &OpCode) {
+        if let Some(at) = &self.at {
+            self.function
+                .as_mut()
+                .expect("Internal error: self.function is None")
+                .chunk()
+                .mark_at(&self.file, at);
+        }
         self.emit_bytes(opcode.as_byte() as u32, 1);
     }
 
@@ -81,6 +113,28 @@ impl ChunkWriter {
     }
 
     
+    // Method call, each call site gets an inline cache slot of its own.
+    // Slots wrap around after 0xffff; sharing one costs speed, not correctness.
+    pub fn emit_invoke(&mut self, name_id: usize, arg_count: u8) {
+        self.emit_op_variant(&OpCodeSet::invoke(), name_id as u64);
+        self.emit_bytes(arg_count as u32, 1);
+        self.emit_inline_cache_slot();
+    }
+
+
+    // Property read, with a cache slot like emit_invoke() for when it finds a method
+    pub fn emit_getproperty(&mut self, name_id: usize) {
+        self.emit_op_variant(&OpCodeSet::getproperty(), name_id as u64);
+        self.emit_inline_cache_slot();
+    }
+
+
+    fn emit_inline_cache_slot(&mut self) {
+        let slot = self.function().inline_caches().new_slot();
+        self.emit_bytes((slot & 0xffff) as u32, 2);
+    }
+
+    
     pub fn emit_jmp(&mut self, opcode: &OpCode) -> u32 {
         self.emit_op(opcode);
         let current_ip = self.current_ip();
@@ -110,5 +164,13 @@ impl ChunkWriter {
             .constants()
             .make(value);
     }
+
+    pub fn make_name_constant(&mut self, name: &str) -> usize {
+        return self.function
+            .as_mut()
+            .expect("Internal error: self.function is None")
+            .constants()
+            .make_name(name);
+    }
 }
 
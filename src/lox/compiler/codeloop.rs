@@ -7,15 +7,17 @@
 pub struct CodeLoop {
     continue_addr:	u32,
     scope_depth:	usize,
+    try_depth:	usize,
     break_addrs:	Vec<u32>,
 }
 
 
 impl CodeLoop {
-    pub fn new(continue_addr: u32, scope_depth: usize) -> Self {
+    pub fn new(continue_addr: u32, scope_depth: usize, try_depth: usize) -> Self {
         Self {
             continue_addr,
             scope_depth,
+            try_depth,
             break_addrs:	vec![],
         }
     }
@@ -28,6 +30,10 @@ impl CodeLoop {
         return self.scope_depth;
     } 
     
+    pub fn try_depth(&self) -> usize {
+        return self.try_depth;
+    } 
+    
     pub fn add_break(&mut self, break_addr: u32) {
         self.break_addrs.push(break_addr);
     } 
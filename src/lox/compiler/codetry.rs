@@ -0,0 +1,82 @@
+// The CodeTry struct is used by the compiler to keep track of surrounding 'try'
+// statements, so 'return', 'break' and 'continue' can run their 'finally' blocks
+
+
+// Ways to leave a 'try' or 'catch' block other than reaching its end or throwing
+#[derive(Clone, Copy, PartialEq)]
+pub enum Exit {
+    Return,
+    Break,
+    Continue,
+}
+
+
+impl Exit {
+    // Stored in the exit slot until the 'finally' block is done
+    pub fn code(&self) -> f64 {
+        match self {
+            Exit::Return => 1.0,
+            Exit::Break => 2.0,
+            Exit::Continue => 3.0,
+        }
+    }
+}
+
+
+pub struct CodeTry {
+    scope_depth:	usize,
+    try_depth:	usize,
+    loop_depth:	usize,
+    exit_slot:	usize, // Local holding the Exit code, or null
+    value_slot:	usize, // Local holding the value to return
+    exits:	Vec<Exit>,
+    exit_addrs:	Vec<u32>,
+}
+
+
+impl CodeTry {
+    pub fn new(scope_depth: usize, try_depth: usize, loop_depth: usize, exit_slot: usize, value_slot: usize) -> Self {
+        Self {
+            scope_depth,
+            try_depth,
+            loop_depth,
+            exit_slot,
+            value_slot,
+            exits:	vec![],
+            exit_addrs:	vec![],
+        }
+    }
+    
+    pub fn scope_depth(&self) -> usize {
+        return self.scope_depth;
+    }
+    
+    pub fn try_depth(&self) -> usize {
+        return self.try_depth;
+    }
+    
+    pub fn loop_depth(&self) -> usize {
+        return self.loop_depth;
+    }
+    
+    pub fn exit_slot(&self) -> usize {
+        return self.exit_slot;
+    }
+    
+    pub fn value_slot(&self) -> usize {
+        return self.value_slot;
+    }
+    
+    pub fn add_exit(&mut self, exit: Exit, exit_addr: u32) {
+        if !self.exits.contains(&exit) { self.exits.push(exit); }
+        self.exit_addrs.push(exit_addr);
+    }
+    
+    pub fn exits(&self) -> &Vec<Exit> {
+        return &self.exits;
+    }
+    
+    pub fn exit_addrs(&self) -> &Vec<u32> {
+        return &self.exit_addrs;
+    }
+}
@@ -1,19 +1,23 @@
 
 
 use std::marker::PhantomData;
+use std::rc::Rc;
 use log::{debug};
 
 
 use scanner::Scanner;
 
-use crate::lox::{common::{ByteCode, Function, FunctionKind, Globals}, compiler::Tokenize};
+use crate::lox::{common::{ByteCode, Function, FunctionKind, Globals, IdentifierKind, Location, NativeCallables, Value}, compiler::{Tokenize, TokenKind}};
 
 
-use super::{ChunkWriter, CompileError, Locals, Parser, ParserOutput, Tokenizer};
+use super::{ChunkWriter, CompileError, Locals, Modules, Parser, ParserOutput, Tokenizer};
 
 
 pub struct Compiler<R> {
     reader: PhantomData<R>, // 0-byte marker needed for rustc to accept the <R>
+    globals: Globals<Value>, // Declared before compiling, e.g. native functions
+    builtins: usize, // Number of globals declared by with_natives()
+    modules: Modules, // Imported by earlier calls to compile_incremental()
 }
 
 
@@ -22,30 +26,93 @@ impl<R: std::io::BufRead> Compiler<R> {
     pub fn new() -> Self {
         Compiler {
             reader: PhantomData, // 0-byte marker, ignore
+            globals: Globals::new(),
+            builtins: 0,
+            modules: Modules::new(0),
         }
     }
 
-    pub fn compile(&self, filename: &str, reader: R) -> Result<ByteCode, CompileError> {
+
+    // Make the native functions registered with a VM visible to scripts
+    // Note: The VM binds them by name when the ByteCode is executed
+    pub fn with_natives(natives: &NativeCallables) -> Self {
+        let mut compiler = Compiler::new();
+        for (name, value) in natives.globals() {
+            let id = compiler.globals.declare(name, IdentifierKind::Constant)
+                .expect("Native global names are unique, see insert_function()");
+            compiler.globals.global_mutref_by_id(id).define();
+            compiler.globals.define_by_id(id, value.clone());
+        }
+        compiler.builtins = compiler.globals.count();
+        compiler.modules = Modules::new(compiler.builtins);
+        return compiler;
+    }
+
+    pub fn compile(&self, filename: &str, reader: R) -> Result<ByteCode, Vec<CompileError>> {
+        let mut globals = self.globals.clone();
+        let mut modules = Modules::new(self.builtins);
+        let function = self.compile_with(filename, reader, &mut globals, &mut modules, false)?;
+        return Ok(ByteCode::new(function, globals));
+    }
+
+
+    // Compile one REPL entry. Globals declared and modules imported by earlier
+    // entries remain visible, and the value of a top level expression statement
+    // is printed rather than discarded.
+    // Note: Nothing is kept if the entry fails to compile
+    pub fn compile_incremental(&mut self, filename: &str, reader: R) -> Result<ByteCode, Vec<CompileError>> {
+        let mut globals = self.globals.clone();
+        let mut modules = self.modules.clone();
+        let function = self.compile_with(filename, reader, &mut globals, &mut modules, true)?;
+        self.globals = globals.clone();
+        self.modules = modules;
+        return Ok(ByteCode::new(function, globals));
+    }
+
+
+    // Return false if source ends inside a string or has unclosed brackets,
+    // meaning a REPL should read more lines before compiling it
+    pub fn is_complete(&self, source: &str) -> bool {
+        let scanner = Scanner::new("INPUT", std::io::Cursor::new(source));
+        let mut input = Tokenizer::new(scanner);
+        let mut depth: i32 = 0;
+        while !input.eof() {
+            match input.current().kind() {
+                TokenKind::LeftBracket | TokenKind::LeftCurly | TokenKind::LeftParen => depth += 1,
+                TokenKind::RightBracket | TokenKind::RightCurly | TokenKind::RightParen => depth -= 1,
+                TokenKind::Error => return input.current().lexeme() != "Unterminated string", // Let the compiler report anything else
+                _ => {}
+            }
+            input.advance();
+        }
+        return depth <= 0;
+    }
+
+
+    fn compile_with(&self, filename: &str, reader: R, globals: &mut Globals<Value>, modules: &mut Modules, echo: bool) -> Result<Function, Vec<CompileError>> {
 
         let scanner = Scanner::new(filename, reader);
         let mut input = Tokenizer::new(scanner);
+        modules.set_script(filename);
 
-        let at = input.current().get_at().cloned();
+        let file: Rc<str> = Rc::from(filename);
+        let at = input.current().get_at().map(|at| Location::from_at(&file, at));
         let function = Function::new("__main__", FunctionKind::Script, at);    
-        let mut writer = ChunkWriter::new(function);
+        let mut writer = ChunkWriter::new(function, &file);
 
         let mut parser = Parser::new();
-        let mut globals = Globals::new();
+        if echo { parser.echo_expressions(); }
 
         let mut output = ParserOutput {
             writer: 	&mut writer,
-            globals: 	&mut globals,
+            globals: 	globals,
             locals:	&mut Locals::new(false),
+            modules:	modules,
         };
 
         let function = parser.parse(&mut input, &mut output)?;
         debug!("{:#?}", function);
-        return Ok(ByteCode::new(function, globals));
+        return Ok(function);
 
     }
 
@@ -8,10 +8,12 @@ mod test;
 mod compiler; // TODO: This should be named "compiler"
 mod class;
 mod codeloop;
+mod codetry;
 mod compile_error;
 mod chunk_writer;
 mod hierarchy;
 mod locals;
+mod modules;
 mod parser;
 mod scope;
 mod tokenizer;
@@ -21,9 +23,11 @@ pub use compiler::Compiler;
 pub use chunk_writer::ChunkWriter;
 pub use class::Class;
 pub use codeloop::CodeLoop;
+pub use codetry::{CodeTry, Exit};
 pub use compile_error::{CompileError, c_error};
 pub use hierarchy::Hierarchy;
 pub use locals::Locals;
+pub use modules::Modules;
 pub use parser::{Parser, ParserOutput};
 pub use scope::Scope;
 pub use tokenizer::{Token, TokenKind, Tokenizer, Tokenize};
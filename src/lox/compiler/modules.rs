@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+
+use scanner::Scanner;
+
+
+use crate::lox::common::{Function, FunctionKind, Globals, Location, Module, Value};
+use super::{ChunkWriter, CompileError, Locals, Parser, ParserOutput, Tokenize, Tokenizer};
+
+
+// A module whose top level code is being compiled
+#[derive(Clone)]
+struct Importing {
+    name: String,
+    key: PathBuf,
+    exports: HashMap<String, usize>,
+}
+
+
+// Keeps track of modules imported while compiling a script.
+// Each file is compiled once and the resulting Module reused by later imports.
+// Globals declared by a module are prefixed with its name so they can not collide
+// with those of the script or other modules.
+// Module paths are relative to the file that imports them, and a module is
+// known by its canonical path so "a.lox" and "./a.lox" are the same module.
+#[derive(Clone)]
+pub struct Modules {
+    builtins: usize, // Globals declared before compiling, e.g. native functions, are visible everywhere
+    script: String, // Filename of the script being compiled
+    importing: Vec<Importing>, // Innermost last
+    imported: HashMap<PathBuf, Value>,
+}
+
+
+#[allow(dead_code)]
+impl Modules {
+
+    pub fn new(builtins: usize) -> Self {
+        Modules {
+            builtins,
+            script: String::new(),
+            importing: vec![],
+            imported: HashMap::new(),
+        }
+    }
+
+
+    // Relative module paths are resolved against the directory of this file
+    pub fn set_script(&mut self, filename: &str) {
+        self.script = filename.to_string();
+    }
+
+
+    // Compile the module found at path, unless it has already been compiled
+    // Returns every error found in the module
+    pub fn import(&mut self, path: &str, globals: &mut Globals<Value>) -> Result<Value, Vec<CompileError>> {
+        let importer = match self.importing.last() {
+            Some(importing) => importing.name.as_str(),
+            None => self.script.as_str(),
+        };
+        let resolved = match Path::new(importer).parent() {
+            Some(directory) => directory.join(path),
+            None => PathBuf::from(path),
+        };
+        let name = resolved.to_string_lossy().to_string();
+        let key = match std::fs::canonicalize(&resolved) {
+            Ok(key) => key,
+            Err(io_error) => return Err(vec![CompileError::new(format!("Error importing module '{}': {}", name, io_error))]),
+        };
+
+        if let Some(module) = self.imported.get(&key) { return Ok(module.clone()); }
+        if self.importing.iter().any(|importing| importing.key == key) {
+            return Err(vec![CompileError::new(format!("Circular import of module '{}'", name))]);
+        }
+
+        let file = match std::fs::File::open(&key) {
+            Ok(file) => file,
+            Err(io_error) => return Err(vec![CompileError::new(format!("Error importing module '{}': {}", name, io_error))]),
+        };
+        let scanner = Scanner::new(name.as_str(), std::io::BufReader::new(file));
+        let mut input = Tokenizer::new(scanner);
+
+        let file: Rc<str> = Rc::from(name.as_str());
+        let at = input.current().get_at().map(|at| Location::from_at(&file, at));
+        let function = Function::new(&name, FunctionKind::Module, at);
+        let mut writer = ChunkWriter::new(function, &file);
+
+        self.importing.push(Importing { name: name.clone(), key: key.clone(), exports: HashMap::new() });
+        let result = {
+            let mut output = ParserOutput {
+                writer: 	&mut writer,
+                globals: 	globals,
+                locals:	&mut Locals::new(false),
+                modules:	self,
+            };
+            Parser::new().parse(&mut input, &mut output)
+        };
+        let importing = self.importing.pop().unwrap();
+
+        let module = Value::module(Module::new(&name, Value::function(result?), importing.exports));
+        self.imported.insert(key, module.clone());
+        return Ok(module);
+    }
+
+
+    // Return true while compiling the top level code of a module
+    pub fn in_module(&self) -> bool {
+        return !self.importing.is_empty();
+    }
+
+
+    // Return the name under which the code being compiled declares a global
+    pub fn qualify(&self, name: &str) -> String {
+        match self.importing.last() {
+            Some(importing) => format!("{}::{}", importing.name, name),
+            None => String::from(name),
+        }
+    }
+
+
+    pub fn is_builtin(&self, id: usize) -> bool {
+        return id < self.builtins;
+    }
+
+
+    // Make a global declared by the module being compiled visible to importers
+    pub fn export(&mut self, name: &str, id: usize) {
+        if let Some(importing) = self.importing.last_mut() {
+            importing.exports.insert(name.to_string(), id);
+        }
+    }
+
+}
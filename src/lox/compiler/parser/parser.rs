@@ -1,21 +1,34 @@
 
+use std::rc::Rc;
 use log::{trace, debug};
 
 
-use crate::lox::compiler::{Class, CodeLoop, CompileError, c_error, ChunkWriter, Hierarchy, Scope, Token, Tokenize, TokenKind};
-use crate::lox::common::{Function, FunctionKind, OpCode, OpCodeSet, IdentifierKind, Value};
+use crate::lox::compiler::{Class, CodeLoop, CodeTry, CompileError, Exit, c_error, ChunkWriter, Hierarchy, Scope, Token, Tokenize, TokenKind};
+use crate::lox::common::{Function, FunctionKind, Location, OpCode, OpCodeSet, IdentifierKind, Value};
 use crate::lox::common::keyword::*;
 
 
 use super::{ParserOutput, ParserPrec, ParserRule};
 
 
+// Something that can be updated in place by compound assignment or ++/--
+enum Target {
+    Variable(OpCodeSet, OpCodeSet, usize), // Get and set opcodes, id
+    Property(usize), // Name constant, receiver on the stack
+    Subscript, // Subscriptable value and array of keys on the stack
+}
+
+
 
 #[allow(dead_code)]
 pub struct Parser<I> {
     scopes: 	Vec<Scope>,
     classes:    Hierarchy<Class>,
     codeloops:	Vec<CodeLoop>,
+    codetries:	Vec<CodeTry>, // Enclosing 'try' statements with a 'finally' to run on the way out
+    try_depth:	usize, // Number of enclosing exception handlers in this function
+    echo:	bool, // Print the value of top level expression statements (REPL)
+    errors:	Vec<CompileError>, // Reported so far, parsing resumes after each one
     _unused: std::marker::PhantomData<*const I>,
 }
 
@@ -27,19 +40,35 @@ impl<I: Tokenize> Parser<I> {
             scopes: 	vec![],
             classes:    Hierarchy::new(),
             codeloops:	vec![],
+            codetries:	vec![],
+            try_depth:	0,
+            echo:	false,
+            errors:	vec![],
             _unused: std::marker::PhantomData,
         }
     }
 
+    // Used by the REPL to show the result of each expression entered
+    pub fn echo_expressions(&mut self) {
+        self.echo = true;
+    }
+
     // Parse __main__ function only (See: parse_function())    
-    pub fn parse(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<Function, CompileError> {
+    // Returns every error found, in the order they appear in the source
+    pub fn parse(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<Function, Vec<CompileError>> {
         
         loop {
             trace!("loop begins");
             if input.eof() { break; }
-            self.declaration(input, output)?;
+            self.declaration_or_recover(input, output);
+        }
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+        match output.writer.function().kind() {
+            FunctionKind::Module => self.emit_return(output), // Back to the importing code
+            _ => self.emit_exit(output),
         }
-        self.emit_exit(output);
         
         return Ok(output.writer.take_function());
     }
@@ -56,14 +85,18 @@ impl<I: Tokenize> Parser<I> {
 
 
     fn emit_return(&self, output: &mut ParserOutput) {
+        self.emit_return_value(output);
+        output.writer.emit_op(&OpCode::Return);
+    }
+
+    // Pushes 'this' from an initializer, null otherwise
+    fn emit_return_value(&self, output: &mut ParserOutput) {
         if output.writer.function().kind().return_self() {
             output.writer.emit_op(&OpCode::GetLocal8);
             output.writer.emit_bytes(0, 1);
         } else {
             output.writer.emit_op(&OpCode::Null);
         }
-        //}
-        output.writer.emit_op(&OpCode::Return);
     }
 
     fn emit_exit(&self, output: &mut ParserOutput) {
@@ -96,6 +129,7 @@ impl<I: Tokenize> Parser<I> {
 
         input.advance();
         trace!("begin compiling precedence at token={:?}", input.previous());
+        let outer_at = output.writer.set_at(input.previous().get_at().cloned());
         let rule = self.previous_token_rule(input);
         
         match rule.prefix {
@@ -108,6 +142,7 @@ impl<I: Tokenize> Parser<I> {
                     if precedence > rule.precedence { break; }
                     
                     input.advance();
+                    output.writer.set_at(input.previous().get_at().cloned());
 
                     match rule.infix {
                         Some(method) => {
@@ -120,13 +155,14 @@ impl<I: Tokenize> Parser<I> {
                     }
                 }
                 
-                if can_assign && input.matches(TokenKind::Equal) {
+                if can_assign && (input.matches(TokenKind::Equal) || self.compound_operator(input.current().kind()).is_some()) {
                     c_error!(format!("Invalid assignment target"), input.current())
                 }
                 
             }
             None => c_error!(format!("Expected expression"), input.current()),
         }
+        output.writer.set_at(outer_at);
         Ok(())
     }
 
@@ -134,18 +170,30 @@ impl<I: Tokenize> Parser<I> {
     fn parse_function(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         self.begin_scope();
 
-        // Parameter list        
-        self.consume(TokenKind::LeftParen, "Expected '(' after function name", input, output)?;
+        // Parameter list
+        // Note: grouping() has already consumed the '(' of an arrow function
+        let arrow = input.previous().matches(TokenKind::LeftParen);
+        if !arrow {
+            self.consume(TokenKind::LeftParen, "Expected '(' after function name", input, output)?;
+        }
         let arity = self.parse_function_params(input, output)?;
         output.writer.function().set_arity(arity);
 
         self.consume(TokenKind::RightParen, "Expected ')' after parameters.", input, output)?;
         
         // Body
-        self.consume(TokenKind::LeftCurly, "Expected '{' before function body.", input, output)?;
-        self.block(input, output)?; // Handles the closing curly
-        
-        self.emit_return(output);
+        if arrow {
+            self.consume(TokenKind::EqualGreater, "Expected '=>' after parameters.", input, output)?;
+        }
+        if arrow && !input.matches(TokenKind::LeftCurly) {
+            // Concise body, the value of a single expression is returned
+            self.expression(input, output)?;
+            output.writer.emit_op(&OpCode::Return);
+        } else {
+            self.consume(TokenKind::LeftCurly, "Expected '{' before function body.", input, output)?;
+            self.block(input, output)?; // Handles the closing curly
+            self.emit_return(output);
+        }
 
         self.end_scope(output);
         
@@ -176,8 +224,8 @@ impl<I: Tokenize> Parser<I> {
         self.declare_identifier(kind.clone(), input, output)?;
         if let Some(_) = self.scope() { return Ok(0); }
         
-        let name = input.previous().lexeme();
-        match output.globals.declare(name, kind) {
+        let name = output.modules.qualify(input.previous().lexeme());
+        match output.globals.declare(&name, kind) {
             Err(mut compile_error) => {
                 compile_error.set_at(input.previous().get_at());
                 return Err(compile_error);
@@ -186,11 +234,10 @@ impl<I: Tokenize> Parser<I> {
         }
     }
     
-    // Make a constant containing the variable name as a Value::String
+    // Make a constant naming a field or method, for the VM to look up by its symbol
     fn identifier_constant(&mut self, token: &Token, output: &mut ParserOutput) -> usize {
-        let name = Value::string(token.lexeme());  
-        debug!("make constant={}", name);
-        return output.writer.make_constant(name);
+        debug!("make name constant={}", token.lexeme());
+        return output.writer.make_name_constant(token.lexeme());
     }
  
     fn declare_identifier(&mut self, kind: IdentifierKind, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
@@ -212,6 +259,7 @@ impl<I: Tokenize> Parser<I> {
                 }
 
                 output.locals.declare_local(name, scope_depth, kind); // Add local variable
+                output.writer.name_local(output.locals.local_count() - 1, name);
                 Ok(())
             }
         }
@@ -236,10 +284,11 @@ impl<I: Tokenize> Parser<I> {
     }
 
     fn resolve_global(&mut self, name: &str, output: &mut ParserOutput) -> Option<usize> {
-        let result = output.globals.id_by_name(name);
+        let result = output.globals.id_by_name(&output.modules.qualify(name));
         match result {
             Some(id)	=> Some(id),
-            None	=> None,
+            // Code in a module can not see the globals of other modules or the script
+            None	=> output.globals.id_by_name(name).filter(|id| output.modules.is_builtin(*id)),
         }
     }
     
@@ -335,6 +384,12 @@ impl<I: Tokenize> Parser<I> {
                     }
                     self.expression(input, output)?;
                     output.writer.emit_op_variant(&set_ops, id as u64);
+                } else if self.update_ahead(can_assign, input) {
+                    if name_token.matches(TokenKind::This) || !self.identifier_is_mutable(name_token, output)? {
+                        c_error!(format!("Can not assign to immutable '{}'", name_token.lexeme()), input.current())
+                    }
+                    input.advance();
+                    self.update_target(&Target::Variable(get_ops, set_ops, id), input.previous().kind(), true, input, output)?;
                 } else {
                     output.writer.emit_op_variant(&get_ops, id as u64);
                 }
@@ -343,6 +398,91 @@ impl<I: Tokenize> Parser<I> {
         }
     }
     
+    // Return the arithmetic opcode of a compound assignment operator such as '+='
+    fn compound_operator(&self, kind: TokenKind) -> Option<OpCode> {
+        match kind {
+            TokenKind::MinusEqual	=> Some(OpCode::Sub),
+            TokenKind::PercentEqual	=> Some(OpCode::Mod),
+            TokenKind::PlusEqual	=> Some(OpCode::Add),
+            TokenKind::SlashEqual	=> Some(OpCode::Div),
+            TokenKind::StarEqual	=> Some(OpCode::Mul),
+            _ => None,
+        }
+    }
+
+    // Postfix ++/-- binds tighter than any operator so it does not need can_assign
+    fn update_ahead(&self, can_assign: bool, input: &mut I) -> bool {
+        if input.matches(TokenKind::PlusPlus) || input.matches(TokenKind::MinusMinus) { return true; }
+        return can_assign && self.compound_operator(input.current().kind()).is_some();
+    }
+
+    fn emit_get_target(&self, target: &Target, output: &mut ParserOutput) {
+        match target {
+            Target::Variable(get_ops, _, id) => output.writer.emit_op_variant(get_ops, *id as u64),
+            Target::Property(name_id)   => output.writer.emit_getproperty(*name_id),
+            Target::Subscript           => output.writer.emit_op(&OpCode::GetSubscript),
+        }
+    }
+
+    fn emit_set_target(&self, target: &Target, output: &mut ParserOutput) {
+        match target {
+            Target::Variable(_, set_ops, id) => output.writer.emit_op_variant(set_ops, *id as u64),
+            Target::Property(name_id)   => output.writer.emit_op_variant(&OpCodeSet::setproperty(), *name_id as u64),
+            Target::Subscript           => output.writer.emit_op(&OpCode::SetSubscript),
+        }
+    }
+
+    // Emit code for 'target op= expression', '++target' or 'target++'.
+    // Any receiver or subscript keys of the target are on the stack
+    // already and are evaluated only once. Leaves the value of the expression on the stack.
+    fn update_target(&mut self, target: &Target, operator: TokenKind, postfix: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        let increment = operator == TokenKind::PlusPlus || operator == TokenKind::MinusMinus;
+
+        // Read the current value, keeping a copy of the operands for writing it back
+        match target {
+            Target::Variable(..)    => {}
+            Target::Property(_)     => output.writer.emit_op(&OpCode::Dup),
+            Target::Subscript       => output.writer.emit_op(&OpCode::Dup2),
+        }
+        self.emit_get_target(target, output);
+
+        let opcode = match operator {
+            TokenKind::PlusPlus     => OpCode::Add,
+            TokenKind::MinusMinus   => OpCode::Sub,
+            _ => match self.compound_operator(operator) {
+                Some(opcode) => {
+                    self.expression(input, output)?;
+                    opcode
+                }
+                None => panic!("Internal Error: Unhandled update operator {:?}", operator),
+            }
+        };
+        if increment {
+            if postfix {
+                // Keep the value from before the update below the operands of the set
+                let count = match target {
+                    Target::Variable(..)    => 0,
+                    Target::Property(_)     => 2, // Receiver and value
+                    Target::Subscript       => 3, // Subscriptable value, keys and value
+                };
+                if count == 0 {
+                    output.writer.emit_op(&OpCode::Dup);
+                } else {
+                    output.writer.emit_op(&OpCode::Tuck);
+                    output.writer.emit_bytes(count, OpCode::Tuck.len());
+                }
+            }
+            self.emit_constant(Value::number(1.0), output);
+        }
+        output.writer.emit_op(&opcode);
+        self.emit_set_target(target, output);
+
+        if increment && postfix {
+            output.writer.emit_op(&OpCode::Pop); // Discard the new value
+        }
+        Ok(())
+    }
+
     fn begin_scope(&mut self) {
         let depth = self.scopes.len() as u32;
         self.scopes.push(Scope::new(depth));
@@ -379,20 +519,27 @@ impl<I: Tokenize> Parser<I> {
         output.locals.begin_function(kind.has_receiver());
     
         // Create a new compilation unit
-        let at = input.previous().get_at().cloned();
+        let file = Rc::clone(output.writer.file());
+        let at = input.previous().get_at().map(|at| Location::from_at(&file, at));
         let mut function = Function::new(name, kind, at);    
-        let mut writer = ChunkWriter::new(function);        
+        let mut writer = ChunkWriter::new(function, &file);        
         
         let mut inner_output = ParserOutput {
             writer:   &mut writer,
             globals:    output.globals,
             locals:	output.locals,
+            modules:	output.modules,
         };
         
         // Create a new Parser and call parse_function()
         let mut parser = Parser::new();
         parser.classes = self.classes.clone();
-        parser.parse_function(input, &mut inner_output)?;
+        let result = parser.parse_function(input, &mut inner_output);
+        self.errors.append(&mut parser.errors); // Found and recovered from in the function body
+        if let Err(compile_error) = result {
+            output.locals.end_function();
+            return Err(compile_error);
+        }
         
         // Wrap the compiled Function in a Closure and store as a constant
         function = inner_output.writer.take_function();
@@ -464,6 +611,10 @@ impl<I: Tokenize> Parser<I> {
             self.print_statement(input, output)
         } else if input.advance_on(TokenKind::Return) {
             self.return_statement(input, output)
+        } else if input.advance_on(TokenKind::Throw) {
+            self.throw_statement(input, output)
+        } else if input.advance_on(TokenKind::Try) {
+            self.try_statement(input, output)
         } else if input.advance_on(TokenKind::While) {
             self.while_statement(input, output)
         } else if input.advance_on(TokenKind::Else) {
@@ -477,7 +628,7 @@ impl<I: Tokenize> Parser<I> {
         loop {
             if input.eof() { break; }
             if input.matches(TokenKind::RightCurly) { break; }
-            self.declaration(input, output)?;
+            self.declaration_or_recover(input, output);
         }
         self.consume(TokenKind::RightCurly, "Expected '}' after block", input, output)?;
         Ok(())
@@ -517,7 +668,11 @@ impl<I: Tokenize> Parser<I> {
     fn expression_statement(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         self.expression(input, output)?;
         self.want_semicolon_after("expression", input, output)?;
-        output.writer.emit_op(&OpCode::Pop); // Discard result
+        if self.echo && self.scope().is_none() && output.writer.function().kind() == &FunctionKind::Script {
+            output.writer.emit_op(&OpCode::Print); // Show result
+        } else {
+            output.writer.emit_op(&OpCode::Pop); // Discard result
+        }
         Ok(())
     }
 
@@ -570,7 +725,7 @@ impl<I: Tokenize> Parser<I> {
             c_error!(format!("Can not '{}' from top level code", KEYWORD_RETURN), input.previous())
         }
         if input.advance_on(TokenKind::Semicolon) {
-            self.emit_return(output); // Pushes 'this' or null as needed
+            self.emit_return_value(output); // Pushes 'this' or null as needed
         } else {
             if function_kind.return_self() { 
                 c_error!(format!("Can not '{}' a value from initializer", KEYWORD_RETURN), input.previous())
@@ -578,10 +733,184 @@ impl<I: Tokenize> Parser<I> {
             self.expression(input, output)?;
             self.want_semicolon_after("expression", input, output)?;
         }
-        output.writer.emit_op(&OpCode::Return);
+        self.leave_function(output);
+        Ok(())
+    }
+
+    fn throw_statement(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        self.expression(input, output)?;
+        self.want_semicolon_after("expression", input, output)?;
+        output.writer.emit_op(&OpCode::Throw);
+        Ok(())
+    }
+
+    // try { } catch (e) { } finally { }
+    // Either 'catch' or 'finally' may be left out, but not both
+    fn try_statement(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        // 'return', 'break' and 'continue' leave through the finally block: they record
+        // where they were going (and the value to return) in these slots, then jump there.
+        self.begin_scope();
+        output.writer.emit_op(&OpCode::Null);
+        self.declare_internal_variable("__finally:exit__", output);
+        let exit_slot = output.locals.local_count() - 1;
+        output.writer.emit_op(&OpCode::Null);
+        self.declare_internal_variable("__finally:value__", output);
+        let value_slot = output.locals.local_count() - 1;
+        self.codetries.push(CodeTry::new(self.scopes.len(), self.try_depth, self.codeloops.len(), exit_slot, value_slot));
+
+        // try..
+        let handler_jmp = output.writer.emit_jmp(&OpCode::Try);
+        self.protected_block(input, output)?;
+        output.writer.emit_op(&OpCode::EndTry);
+
+        // Every path into the finally block leaves the same slots on the stack;
+        // the last two are the exception (or null) and whether to rethrow it when done.
+        let slots;
+        let mut finally_jmps = vec![];
+        if input.advance_on(TokenKind::Catch) {
+            slots = vec!["__finally:caught__", "__finally:exception__", "__finally:rethrow__"];
+            output.writer.emit_op(&OpCode::Null);
+            output.writer.emit_op(&OpCode::Null);
+            output.writer.emit_op(&OpCode::False);
+            finally_jmps.push(output.writer.emit_jmp(&OpCode::Jmp));
+
+            // ..catch, the VM has pushed the exception onto the stack.
+            // The catch block is protected too so the finally block (if any) runs
+            // even if it throws. We can not know if there is a finally block until later.
+            output.writer.patch_jmp(handler_jmp);
+            let rethrow_jmp = output.writer.emit_jmp(&OpCode::Try);
+            self.try_depth = self.try_depth + 1;
+            let result = self.catch_clause(input, output);
+            self.try_depth = self.try_depth - 1;
+            result?;
+            output.writer.emit_op(&OpCode::EndTry);
+            output.writer.emit_op(&OpCode::Null);
+            output.writer.emit_op(&OpCode::Null);
+            output.writer.emit_op(&OpCode::False);
+            finally_jmps.push(output.writer.emit_jmp(&OpCode::Jmp));
+
+            // The caught exception is still on the stack, the new one on top
+            output.writer.patch_jmp(rethrow_jmp);
+            output.writer.emit_op(&OpCode::True);
+        } else if input.matches(TokenKind::Finally) {
+            slots = vec!["__finally:exception__", "__finally:rethrow__"];
+            output.writer.emit_op(&OpCode::Null);
+            output.writer.emit_op(&OpCode::False);
+            finally_jmps.push(output.writer.emit_jmp(&OpCode::Jmp));
+            output.writer.patch_jmp(handler_jmp);
+            output.writer.emit_op(&OpCode::True);
+        } else {
+            c_error!(format!("Expected '{}' or '{}' after '{}' block, got '{}'", KEYWORD_CATCH, KEYWORD_FINALLY, KEYWORD_TRY, input.current().lexeme()), input.current())
+        }
+
+        // Jumps out of the try or catch block arrive here with nothing to rethrow
+        let codetry = self.codetries.pop().unwrap();
+        if !codetry.exit_addrs().is_empty() {
+            finally_jmps.push(output.writer.emit_jmp(&OpCode::Jmp));
+            for address in codetry.exit_addrs() {
+                output.writer.patch_jmp(*address);
+            }
+            for _slot in 1..slots.len() {
+                output.writer.emit_op(&OpCode::Null);
+            }
+            output.writer.emit_op(&OpCode::False);
+        }
+        for jmp in finally_jmps { output.writer.patch_jmp(jmp); }
+
+        // ..finally
+        self.begin_scope();
+        for name in slots { self.declare_internal_variable(name, output); }
+        if input.advance_on(TokenKind::Finally) {
+            self.consume(TokenKind::LeftCurly, format!("Expected '{{' after '{}'", KEYWORD_FINALLY).as_str(), input, output)?;
+            self.begin_scope();
+            self.block(input, output)?;
+            self.end_scope(output);
+        }
+        self.get_internal_variable("__finally:rethrow__", output);
+        let end_jmp = output.writer.emit_jmp(&OpCode::JmpFalseP);
+        self.get_internal_variable("__finally:exception__", output);
+        output.writer.emit_op(&OpCode::Throw);
+        output.writer.patch_jmp(end_jmp);
+        self.end_scope(output);
+
+        // Then carry on where the jump out of the try or catch block was going
+        for exit in codetry.exits() {
+            output.writer.emit_op_variant(&OpCodeSet::getlocal(), codetry.exit_slot() as u64);
+            self.emit_constant(Value::number(exit.code()), output);
+            output.writer.emit_op(&OpCode::Equal);
+            let next_jmp = output.writer.emit_jmp(&OpCode::JmpFalseP);
+            match exit {
+                Exit::Return => {
+                    output.writer.emit_op_variant(&OpCodeSet::getlocal(), codetry.value_slot() as u64);
+                    self.leave_function(output);
+                }
+                Exit::Break => self.emit_break(output),
+                Exit::Continue => self.emit_continue(output),
+            }
+            output.writer.patch_jmp(next_jmp);
+        }
+        self.end_scope(output);
         Ok(())
     }
 
+    // The value to return is on the stack
+    fn leave_function(&mut self, output: &mut ParserOutput) {
+        if self.codetries.is_empty() {
+            output.writer.emit_op(&OpCode::Return);
+        } else {
+            self.leave_try(Exit::Return, output);
+        }
+    }
+
+    // Jump to the finally block of the innermost 'try' statement, which
+    // takes over the exit once it is done
+    fn leave_try(&mut self, exit: Exit, output: &mut ParserOutput) {
+        let codetry = self.codetries.last().unwrap();
+        let (scope_depth, try_depth) = (codetry.scope_depth(), codetry.try_depth());
+        let (exit_slot, value_slot) = (codetry.exit_slot(), codetry.value_slot());
+        if exit == Exit::Return {
+            output.writer.emit_op_variant(&OpCodeSet::setlocal(), value_slot as u64);
+            output.writer.emit_op(&OpCode::Pop);
+        }
+        self.discard_locals(scope_depth, output);
+        for _i in try_depth..self.try_depth {
+            output.writer.emit_op(&OpCode::EndTry);
+        }
+        self.emit_constant(Value::number(exit.code()), output);
+        output.writer.emit_op_variant(&OpCodeSet::setlocal(), exit_slot as u64);
+        output.writer.emit_op(&OpCode::Pop);
+        let exit_addr = output.writer.emit_jmp(&OpCode::Jmp);
+        self.codetries.last_mut().unwrap().add_exit(exit, exit_addr);
+    }
+
+    fn protected_block(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        self.consume(TokenKind::LeftCurly, format!("Expected '{{' after '{}'", KEYWORD_TRY).as_str(), input, output)?;
+        self.try_depth = self.try_depth + 1;
+        self.begin_scope();
+        let result = self.block(input, output);
+        self.end_scope(output);
+        self.try_depth = self.try_depth - 1;
+        return result;
+    }
+
+    // The exception is on the stack, bind it to a local variable if one is named
+    fn catch_clause(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        self.begin_scope();
+        if input.advance_on(TokenKind::LeftParen) {
+            self.parse_identifier(IdentifierKind::Variable, format!("Expected variable name after '{}'", KEYWORD_CATCH).as_str(), input, output)?;
+            self.define_initializer(0, output);
+            self.consume(TokenKind::RightParen, "Expected ')' after variable name", input, output)?;
+        } else {
+            self.declare_internal_variable("__catch__", output);
+        }
+        self.consume(TokenKind::LeftCurly, format!("Expected '{{' after '{}'", KEYWORD_CATCH).as_str(), input, output)?;
+        self.begin_scope();
+        let result = self.block(input, output);
+        self.end_scope(output);
+        self.end_scope(output);
+        return result;
+    }
+
     fn while_statement(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         self.begin_scope();
         self.begin_loop(output);
@@ -728,9 +1057,16 @@ impl<I: Tokenize> Parser<I> {
 
     // Used by FOR..IN to reserve stack slots for iterator use
     fn make_internal_variable(&mut self, name: &str, output: &mut ParserOutput) {
+        self.declare_internal_variable(name, output);
+        output.writer.emit_op(&OpCode::Null);
+    }
+
+
+    // Used by TRY..CATCH..FINALLY to name a stack slot that already holds a value
+    fn declare_internal_variable(&mut self, name: &str, output: &mut ParserOutput) {
         output.locals.declare_local(name, self.scopes.len(), IdentifierKind::Variable);
+        output.writer.name_local(output.locals.local_count() - 1, name);
         output.locals.last_local().unwrap().define();        
-        output.writer.emit_op(&OpCode::Null);
     }
 
 
@@ -763,7 +1099,7 @@ impl<I: Tokenize> Parser<I> {
     fn begin_loop(&mut self, output: &mut ParserOutput) -> u32 {
         let continue_addr = output.writer.current_ip();
         let scope_depth = self.scopes.len();
-        self.codeloops.push(CodeLoop::new(continue_addr, scope_depth));
+        self.codeloops.push(CodeLoop::new(continue_addr, scope_depth, self.try_depth));
         return 0;
     }
     
@@ -772,44 +1108,74 @@ impl<I: Tokenize> Parser<I> {
     }
     
     fn continue_loop(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
-        let scope_depth = self.scopes.len();
-        let inner_loop = self.inner_loop();
-        match inner_loop {
-            Some(codeloop) => {
-                // Reset to loop's scope
-                let end_scopes = scope_depth - codeloop.scope_depth();
-                for _i in 0..end_scopes {
-                    self.end_scope(output);
-                }
-            }
-            None => {
-                c_error!(format!("Keyword '{}' is misplaced", KEYWORD_CONTINUE), input.previous())
-            }
+        if self.inner_loop().is_none() {
+            c_error!(format!("Keyword '{}' is misplaced", KEYWORD_CONTINUE), input.previous())
         }
-        // At this point we know the inner_loop exists
-        let codeloop = self.inner_loop().unwrap();
-        output.writer.emit_op(&OpCode::Jmp);
-        output.writer.emit_bytes(codeloop.continue_addr(), OpCode::Jmp.len());
+        self.emit_continue(output);
         Ok(())
     }
     
     fn break_loop(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
-        let scope_depth = self.scopes.len();
-        let inner_loop = self.inner_loop();
-        match inner_loop {
-            Some(codeloop) => {
-                // Reset to loop's scope
-                let end_scopes = scope_depth - codeloop.scope_depth();
-                for _i in 0..end_scopes {
-                    self.end_scope(output);
-                }
-            }
-            None => c_error!(format!("Keyword '{}' is misplaced", KEYWORD_BREAK), input.previous()),
+        if self.inner_loop().is_none() {
+            c_error!(format!("Keyword '{}' is misplaced", KEYWORD_BREAK), input.previous())
         }
-        // At this point we know the inner_loop exists
+        self.emit_break(output);
+        Ok(())
+    }
+
+    // The innermost loop must exist
+    fn emit_continue(&mut self, output: &mut ParserOutput) {
+        if self.try_in_loop() { return self.leave_try(Exit::Continue, output); }
+        // Reset to loop's scope
+        let scope_depth = self.inner_loop().unwrap().scope_depth();
+        self.discard_locals(scope_depth, output);
+        self.end_tries_in_loop(output);
+        let codeloop = self.inner_loop().unwrap();
+        output.writer.emit_op(&OpCode::Jmp);
+        output.writer.emit_bytes(codeloop.continue_addr(), OpCode::Jmp.len());
+    }
+
+    // The innermost loop must exist
+    fn emit_break(&mut self, output: &mut ParserOutput) {
+        if self.try_in_loop() { return self.leave_try(Exit::Break, output); }
+        // Reset to loop's scope
+        let scope_depth = self.inner_loop().unwrap().scope_depth();
+        self.discard_locals(scope_depth, output);
+        self.end_tries_in_loop(output);
         let codeloop = self.inner_loop().unwrap();
         codeloop.add_break(output.writer.emit_jmp(&OpCode::Jmp));
-        Ok(())
+    }
+
+    // Is there a 'try' statement between here and the loop being left?
+    fn try_in_loop(&self) -> bool {
+        match self.codetries.last() {
+            Some(codetry) => codetry.loop_depth() == self.codeloops.len(),
+            None => false,
+        }
+    }
+    
+    // Jumping out of a loop must remove the locals declared inside it from the stack,
+    // but the scopes stay open for whatever code follows 'break' or 'continue'
+    fn discard_locals(&mut self, scope_depth: usize, output: &mut ParserOutput) {
+        let mut id = output.locals.local_count();
+        while id > 0 {
+            let local = output.locals.local_ref_by_id(id - 1);
+            if local.depth() <= scope_depth { break; }
+            if local.is_captured() {
+                output.writer.emit_op(&OpCode::CloseUpvalue);
+            } else {
+                output.writer.emit_op(&OpCode::Pop);
+            }
+            id = id - 1;
+        }
+    }
+
+    // Jumping out of a 'try' block must remove its exception handler
+    fn end_tries_in_loop(&mut self, output: &mut ParserOutput) {
+        let try_depth = self.inner_loop().unwrap().try_depth();
+        for _i in try_depth..self.try_depth {
+            output.writer.emit_op(&OpCode::EndTry);
+        }
     }
     
     fn end_loop(&mut self, output: &mut ParserOutput) -> u32 {
@@ -837,20 +1203,99 @@ impl<I: Tokenize> Parser<I> {
 
     fn declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         debug!("begin compiling declaration");
-        match input.current().kind() {
+        let outer_at = output.writer.set_at(input.current().get_at().cloned());
+        let result = match input.current().kind() {
             TokenKind::Class 	=> self.class_declaration(input, output),
             TokenKind::Const 	=> self.const_declaration(input, output),
-            TokenKind::Fun 	    => self.fun_declaration(input, output),
+            TokenKind::Export 	=> self.export_declaration(input, output),
+            TokenKind::Fun 	    if !input.peek(1).matches(TokenKind::LeftParen) => self.fun_declaration(input, output),
+            TokenKind::Import 	=> self.import_declaration(input, output),
             TokenKind::Var	    => self.var_declaration(input, output),
             _			        => self.statement(input, output),
+        };
+        output.writer.set_at(outer_at);
+        return result;
+    }
+
+    // Parse a declaration. If that fails, record the error and skip ahead to where
+    // parsing can resume, so that errors further down are reported in the same pass.
+    fn declaration_or_recover(&mut self, input: &mut I, output: &mut ParserOutput) {
+        let scopes = self.scopes.len();
+        let codeloops = self.codeloops.len();
+        let codetries = self.codetries.len();
+        let try_depth = self.try_depth;
+        let classes = self.classes.current_depth();
+        let locals = output.locals.local_count();
+        let globals = output.globals.count();
+
+        let compile_error = match self.declaration(input, output) {
+            Ok(()) => return,
+            Err(compile_error) => compile_error,
+        };
+        debug!("recovering from {:?}", compile_error);
+        self.errors.push(compile_error);
+
+        // Forget whatever the failed declaration had begun
+        self.scopes.truncate(scopes);
+        self.codeloops.truncate(codeloops);
+        self.codetries.truncate(codetries);
+        self.try_depth = try_depth;
+        while self.classes.current_depth() > classes { self.classes.pop(); }
+        while output.locals.local_count() > locals && output.locals.last_local().unwrap().depth() > self.scopes.len() {
+            output.locals.pop_local();
+        }
+
+        // Consider any name it declared as defined, or every use would be an error too
+        if output.locals.local_count() > locals { output.locals.last_local().unwrap().define(); }
+        for id in globals..output.globals.count() {
+            output.globals.global_mutref_by_id(id).define();
+        }
+
+        self.synchronize(input);
+    }
+
+
+    // Skip tokens until the start of what looks like the next statement.
+    // Blocks entered along the way are skipped as a whole.
+    fn synchronize(&mut self, input: &mut I) {
+        let mut depth = 0;
+        while !input.eof() {
+            if depth == 0 {
+                if input.previous().matches(TokenKind::Semicolon) { return; }
+                match input.current().kind() {
+                    TokenKind::Break    |
+                    TokenKind::Class    |
+                    TokenKind::Const    |
+                    TokenKind::Continue |
+                    TokenKind::Debug    |
+                    TokenKind::Exit     |
+                    TokenKind::Export   |
+                    TokenKind::For      |
+                    TokenKind::Fun      |
+                    TokenKind::If       |
+                    TokenKind::Import   |
+                    TokenKind::Print    |
+                    TokenKind::Return   |
+                    TokenKind::Throw    |
+                    TokenKind::Try      |
+                    TokenKind::Var      |
+                    TokenKind::While    |
+                    TokenKind::RightCurly => return, // End of the enclosing block
+                    _ => {}
+                }
+            }
+            if input.matches(TokenKind::LeftCurly) { depth = depth + 1; }
+            if input.matches(TokenKind::RightCurly) { depth = depth - 1; }
+            input.advance();
         }
     }
 
+
     fn class_declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError>{
         input.advance(); // Consume Class token
         let name_id = self.parse_identifier(IdentifierKind::Constant, "Expected class name", input, output)?;
         let name_token = input.previous().clone();
-        let name_constant = self.identifier_constant(&name_token, output);
+        let name_constant = output.writer.make_constant(Value::string(name_token.lexeme()));
         self.classes.push(name_token.lexeme(), Class::new(&name_token));
         output.writer.emit_op_variant(&OpCodeSet::class(), name_constant as u64);
         self.define_initializer(name_id, output); // At this point, the VM will have defined the (empty) class
@@ -867,6 +1312,7 @@ impl<I: Tokenize> Parser<I> {
 
             // Copy superclass from globals to a local variable 'super'
             output.locals.declare_local(KEYWORD_SUPER, 0, IdentifierKind::Constant);
+            output.writer.name_local(output.locals.local_count() - 1, KEYWORD_SUPER);
             self.identifier(&superclass_token, false, input, output)?;
             self.define_initializer(0, output);
 
@@ -909,6 +1355,30 @@ impl<I: Tokenize> Parser<I> {
         Ok(())
     } 
 
+    // export (class|const|fun|var) name ...
+    fn export_declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        input.advance(); // Consume Export token
+        if !output.modules.in_module() || self.scope().is_some() {
+            c_error!(format!("Can only '{}' from the top level of a module", KEYWORD_EXPORT), input.previous())
+        }
+        let first_id = output.globals.count();
+        match input.current().kind() {
+            TokenKind::Class 	=> self.class_declaration(input, output)?,
+            TokenKind::Const 	=> self.const_declaration(input, output)?,
+            TokenKind::Fun 	    => self.fun_declaration(input, output)?,
+            TokenKind::Var	    => self.var_declaration(input, output)?,
+            _ => c_error!(format!("Expected declaration after '{}', got '{}'", KEYWORD_EXPORT, input.current().lexeme()), input.current()),
+        }
+        // Export the global(s) declared by the declaration under their unqualified names
+        let prefix = output.modules.qualify("");
+        for id in first_id..output.globals.count() {
+            let name = output.globals.name_by_id(id);
+            let name = name.strip_prefix(&prefix).unwrap_or(name).to_string();
+            output.modules.export(&name, id);
+        }
+        Ok(())
+    }
+
     fn fun_declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         input.advance(); // Consume Fun token
         let name_id = self.parse_identifier(IdentifierKind::Constant, "Expected function name", input, output)?;
@@ -918,6 +1388,34 @@ impl<I: Tokenize> Parser<I> {
         Ok(())
     }
 
+    // import "path" as name;
+    fn import_declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        input.advance(); // Consume Import token
+        let path_token = input.current().clone();
+        self.consume(TokenKind::String, format!("Expected module path after '{}'", KEYWORD_IMPORT).as_str(), input, output)?;
+        self.consume(TokenKind::As, format!("Expected '{}' after module path", KEYWORD_AS).as_str(), input, output)?;
+        let name_id = self.parse_identifier(IdentifierKind::Constant, "Expected module name", input, output)?;
+
+        let module = match output.modules.import(path_token.lexeme(), output.globals) {
+            Ok(module) => module,
+            Err(mut compile_errors) => {
+                // Errors inside the module have a location already
+                for compile_error in compile_errors.iter_mut() {
+                    if compile_error.get_at().is_none() { compile_error.set_at(path_token.get_at()); }
+                }
+                let last = compile_errors.pop().expect("Failed import has at least one error");
+                self.errors.append(&mut compile_errors);
+                return Err(last);
+            }
+        };
+        let module_id = output.writer.make_constant(module);
+        output.writer.emit_op_variant(&OpCodeSet::import(), module_id as u64);
+
+        self.consume(TokenKind::Semicolon, "Expected ';' after module name", input, output)?;
+        self.define_initializer(name_id, output);
+        Ok(())
+    }
+
     fn var_declaration(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         input.advance(); // Consume Var token
         let name_id = self.parse_identifier(IdentifierKind::Variable, "Expected variable name", input, output)?;
@@ -1009,10 +1507,13 @@ impl<I: Tokenize> Parser<I> {
         
         match operator {
             // Single symbol
+            TokenKind::Amp		    => output.writer.emit_op(&OpCode::BitAnd),
+            TokenKind::Caret		=> output.writer.emit_op(&OpCode::BitXor),
             TokenKind::Greater		=> output.writer.emit_op(&OpCode::Greater),
             TokenKind::Less		    => output.writer.emit_op(&OpCode::Less),
             TokenKind::Minus		=> output.writer.emit_op(&OpCode::Sub),
             TokenKind::Percent		=> output.writer.emit_op(&OpCode::Mod),
+            TokenKind::Pipe		    => output.writer.emit_op(&OpCode::BitOr),
             TokenKind::Plus		    => output.writer.emit_op(&OpCode::Add),
             TokenKind::Star		    => output.writer.emit_op(&OpCode::Mul),
             TokenKind::Slash		=> output.writer.emit_op(&OpCode::Div),
@@ -1021,7 +1522,9 @@ impl<I: Tokenize> Parser<I> {
             TokenKind::BangEqual	=> output.writer.emit_op(&OpCode::NotEqual),
             TokenKind::EqualEqual	=> output.writer.emit_op(&OpCode::Equal),
             TokenKind::GreaterEqual	=> output.writer.emit_op(&OpCode::GreaterEqual),
+            TokenKind::GreaterGreater	=> output.writer.emit_op(&OpCode::ShiftRight),
             TokenKind::LessEqual	=> output.writer.emit_op(&OpCode::LessEqual),
+            TokenKind::LessLess	    => output.writer.emit_op(&OpCode::ShiftLeft),
 
             // Keyword
             TokenKind::Is	        => output.writer.emit_op(&OpCode::Same),
@@ -1050,18 +1553,106 @@ impl<I: Tokenize> Parser<I> {
         if can_assign && input.advance_on(TokenKind::Equal) {
             self.expression(input, output)?;
             output.writer.emit_op_variant(&OpCodeSet::setproperty(), name_id as u64);
+        } else if self.update_ahead(can_assign, input) {
+            input.advance();
+            self.update_target(&Target::Property(name_id), input.previous().kind(), true, input, output)?;
+        } else if input.advance_on(TokenKind::LeftParen) {
+            // Method call, look up and call in one go without binding the method
+            let arg_count = self.argument_list(input, output)?;
+            output.writer.emit_invoke(name_id, arg_count);
         } else {
-            output.writer.emit_op_variant(&OpCodeSet::getproperty(), name_id as u64);
+            output.writer.emit_getproperty(name_id);
         }
         Ok(())
     }
 
     pub(crate) fn grouping(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        if self.arrow_ahead(input) {
+            return self.function("lambda", FunctionKind::Function, input, output);
+        }
         self.expression(input, output)?;
         self.consume(TokenKind::RightParen, "Expect ')' after expression", input, output)?;
         Ok(())
     }
 
+    // Called just inside '(' to look for an arrow function: (a, b) => ...
+    fn arrow_ahead(&self, input: &mut I) -> bool {
+        if input.matches(TokenKind::RightParen) { return input.peek(1).matches(TokenKind::EqualGreater); }
+        if !input.matches(TokenKind::Identifier) { return false; }
+        let mut distance = 1;
+        // Parameter names separated by ',' with an optional trailing comma
+        while input.peek(distance).matches(TokenKind::Comma) {
+            distance = distance + 1;
+            if !input.peek(distance).matches(TokenKind::Identifier) { break; }
+            distance = distance + 1;
+        }
+        if !input.peek(distance).matches(TokenKind::RightParen) { return false; }
+        return input.peek(distance + 1).matches(TokenKind::EqualGreater);
+    }
+
+    // Prefix increment/decrement: ++target or --target
+    pub(crate) fn increment(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        let operator = input.previous().kind();
+        let target = self.increment_target(input, output)?;
+        return self.update_target(&target, operator, false, input, output);
+    }
+
+    // Parse the target of a prefix increment: a variable optionally followed by
+    // properties and subscripts. Code for all but the last part is emitted.
+    fn increment_target(&mut self, input: &mut I, output: &mut ParserOutput) -> Result<Target, CompileError> {
+        let mut target = None;
+        if input.advance_on(TokenKind::This) {
+            self.this_(false, input, output)?; // Must be followed by a property or subscript
+        } else if input.advance_on(TokenKind::Identifier) {
+            let name_token = input.previous().clone();
+            let (get_ops, set_ops, id) = match self.identifier_opcodes(&name_token, output) {
+                Ok(opcodes) => opcodes,
+                Err(mut compile_error) => {
+                    compile_error.set_at(name_token.get_at());
+                    return Err(compile_error);
+                }
+            };
+            target = Some(Target::Variable(get_ops, set_ops, id));
+        } else {
+            c_error!(format!("Expected variable, property or subscript after '{}'", input.previous().lexeme()), input.current())
+        }
+
+        loop {
+            if input.advance_on(TokenKind::Dot) {
+                if let Some(target) = &target { self.emit_get_target(target, output); }
+                self.consume(TokenKind::Identifier, "Expected property name after '.'", input, output)?;
+                target = Some(Target::Property(self.identifier_constant(input.previous(), output)));
+            } else if input.advance_on(TokenKind::LeftBracket) {
+                if let Some(target) = &target { self.emit_get_target(target, output); }
+                let indices = self.expressions_until(TokenKind::RightBracket, input, output)?;
+                self.consume(TokenKind::RightBracket, "Expected ']' after array elements", input, output)?;
+                if indices == 0 { c_error!(format!("Can not assign to subscript '[]'"), input.previous()) }
+                output.writer.emit_op_variant(&OpCodeSet::defarray(), indices);
+                target = Some(Target::Subscript);
+            } else {
+                break;
+            }
+        }
+
+        match target {
+            Some(Target::Variable(get_ops, set_ops, id)) => {
+                // Only a plain variable can be immutable
+                let name_token = input.previous().clone();
+                if !self.identifier_is_mutable(&name_token, output)? {
+                    c_error!(format!("Can not assign to immutable '{}'", name_token.lexeme()), &name_token)
+                }
+                return Ok(Target::Variable(get_ops, set_ops, id));
+            }
+            Some(target) => return Ok(target),
+            None => c_error!(format!("Can not assign to '{}'", KEYWORD_THIS), input.previous()),
+        }
+    }
+
+    // Anonymous function: fun (params) { body }
+    pub(crate) fn lambda(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        return self.function("lambda", FunctionKind::Function, input, output);
+    }
+
     pub(crate) fn literal(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         let literal = input.previous().kind();
         match literal {
@@ -1077,6 +1668,24 @@ impl<I: Tokenize> Parser<I> {
         Ok(())
     }
 
+    pub(crate) fn map(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        let mut pairs = 0;
+        if !input.matches(TokenKind::RightCurly) {
+            loop {
+                pairs = pairs + 1;
+                self.expression(input, output)?;
+                self.consume(TokenKind::Colon, "Expected ':' after map key", input, output)?;
+                self.expression(input, output)?;
+                // Keep going?
+                if !input.advance_on(TokenKind::Comma) { break; }
+                if input.matches(TokenKind::RightCurly) { break; } // That was a trailing comma
+            }
+        }
+        self.consume(TokenKind::RightCurly, "Expected '}' after map elements", input, output)?;
+        output.writer.emit_op_variant(&OpCodeSet::defmap(), pairs);
+        Ok(())
+    }
+
     pub(crate) fn or(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
         let else_jmp = output.writer.emit_jmp(&OpCode::JmpFalseQ);
         let end_jmp = output.writer.emit_jmp(&OpCode::Jmp);
@@ -1104,6 +1713,10 @@ impl<I: Tokenize> Parser<I> {
             if indices == 0 { c_error!(format!("Can not assign to subscript '[]'")) }
             self.expression(input, output)?;
             output.writer.emit_op(&OpCode::SetSubscript);
+        } else if self.update_ahead(can_assign, input) {
+            if indices == 0 { c_error!(format!("Can not assign to subscript '[]'")) }
+            input.advance();
+            self.update_target(&Target::Subscript, input.previous().kind(), true, input, output)?;
         } else {
             output.writer.emit_op(&OpCode::GetSubscript);
         }
@@ -1128,8 +1741,17 @@ impl<I: Tokenize> Parser<I> {
         Ok(())
     }
 
-    pub(crate) fn ternary(&mut self, _can_assign: bool, _input: &mut I, _output: &mut ParserOutput) -> Result<(), CompileError> {
-        panic!("Not yet implemented.");
+    pub(crate) fn ternary(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
+        // The condition is on the stack, JmpFalseP pops it either way
+        let else_jmp = output.writer.emit_jmp(&OpCode::JmpFalseP);
+        self.parse_precedence(ParserPrec::Conditional, input, output)?;
+        self.consume(TokenKind::Colon, "Expected ':' after '?' expression", input, output)?;
+        let end_jmp = output.writer.emit_jmp(&OpCode::Jmp);
+        output.writer.patch_jmp(else_jmp);
+        // Right associative: a ? b : c ? d : e is a ? b : (c ? d : e)
+        self.parse_precedence(ParserPrec::Conditional, input, output)?;
+        output.writer.patch_jmp(end_jmp);
+        Ok(())
     }
 
     pub(crate) fn this_(&mut self, _can_assign: bool, input: &mut I, output: &mut ParserOutput) -> Result<(), CompileError> {
@@ -1146,6 +1768,7 @@ impl<I: Tokenize> Parser<I> {
         match operator {
             TokenKind::Bang 	=> output.writer.emit_op(&OpCode::Not),
             TokenKind::Minus 	=> output.writer.emit_op(&OpCode::Negate),
+            TokenKind::Tilde 	=> output.writer.emit_op(&OpCode::BitNot),
             _ => {
                 panic!("Internal Error: Unhandled unary operator {:?}", operator);
             }
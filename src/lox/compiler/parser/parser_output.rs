@@ -1,6 +1,6 @@
 
 
-use crate::lox::compiler::{ChunkWriter, Locals};
+use crate::lox::compiler::{ChunkWriter, Locals, Modules};
 use crate::lox::common::{Globals, Value};
 
 
@@ -8,4 +8,5 @@ pub struct ParserOutput<'a> {
     pub writer: 	&'a mut ChunkWriter,
     pub globals: 	&'a mut Globals<Value>,
     pub locals: 	&'a mut Locals,
+    pub modules: 	&'a mut Modules,
 }
@@ -35,9 +35,9 @@ impl ParserPrec {
             ParserPrec::Conditional	=> ParserPrec::Or,
             ParserPrec::Or		    => ParserPrec::And,
             ParserPrec::And		    => ParserPrec::BinOr,
-            ParserPrec::BinOr		=> ParserPrec::BinAnd,
-            ParserPrec::BinAnd		=> ParserPrec::BinXor,
-            ParserPrec::BinXor		=> ParserPrec::Equality,
+            ParserPrec::BinOr		=> ParserPrec::BinXor,
+            ParserPrec::BinXor		=> ParserPrec::BinAnd,
+            ParserPrec::BinAnd		=> ParserPrec::Equality,
             ParserPrec::Equality	=> ParserPrec::Comparison,
             ParserPrec::Comparison	=> ParserPrec::Shift,
             ParserPrec::Shift		=> ParserPrec::Term,
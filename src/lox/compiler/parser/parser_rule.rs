@@ -44,6 +44,12 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		None, 
                 precedence: 	ParserPrec::None,
             },
+            TokenKind::Caret => return ParserRule {
+                prefix: 	None, 
+                infix: 		Some(Parser::binary), 
+                precedence: 	ParserPrec::BinXor,
+            },
+            TokenKind::Colon => return ParserRule::null(),
             TokenKind::Comma => return ParserRule::null(),
             TokenKind::Dot => return ParserRule {
                 prefix: 	None, 
@@ -61,7 +67,11 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		Some(Parser::subscr), 
                 precedence: 	ParserPrec::Subscript,
             },
-            TokenKind::LeftCurly => return ParserRule::null(),
+            TokenKind::LeftCurly => return ParserRule {
+                prefix:		Some(Parser::map), 
+                infix: 		None, 
+                precedence: 	ParserPrec::None,
+            },
             TokenKind::LeftParen => return ParserRule {
                 prefix: 	Some(Parser::grouping), 
                 infix: 		Some(Parser::call), 
@@ -92,6 +102,11 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		Some(Parser::binary), 
                 precedence: 	ParserPrec::Term,
             },
+            TokenKind::Question => return ParserRule {
+                prefix: 	None, 
+                infix: 		Some(Parser::ternary), 
+                precedence: 	ParserPrec::Conditional,
+            },
             TokenKind::RightBracket => return ParserRule::null(),
             TokenKind::RightCurly => return ParserRule::null(),
             TokenKind::RightParen => return ParserRule::null(),
@@ -105,6 +120,11 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		Some(Parser::binary), 
                 precedence: 	ParserPrec::Factor,
             },
+            TokenKind::Tilde => return ParserRule {
+                prefix: 	Some(Parser::unary), 
+                infix: 		None, 
+                precedence: 	ParserPrec::None,
+            },
             TokenKind::Semicolon => return ParserRule::null(),
             
             // Double character symbols
@@ -123,21 +143,47 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		Some(Parser::binary), 
                 precedence: 	ParserPrec::Equality,
             },
+            TokenKind::EqualGreater => return ParserRule::null(),
             TokenKind::GreaterEqual => return ParserRule {
                 prefix: 	None, 
                 infix: 		Some(Parser::binary), 
                 precedence: 	ParserPrec::Comparison,
             },
+            TokenKind::GreaterGreater => return ParserRule {
+                prefix: 	None, 
+                infix: 		Some(Parser::binary), 
+                precedence: 	ParserPrec::Shift,
+            },
             TokenKind::LessEqual => return ParserRule {
                 prefix: 	None, 
                 infix: 		Some(Parser::binary), 
                 precedence: 	ParserPrec::Comparison,
             },
+            TokenKind::LessLess => return ParserRule {
+                prefix: 	None, 
+                infix: 		Some(Parser::binary), 
+                precedence: 	ParserPrec::Shift,
+            },
+            TokenKind::MinusEqual => return ParserRule::null(),
+            TokenKind::MinusMinus => return ParserRule {
+                prefix: 	Some(Parser::increment), 
+                infix: 		None, 
+                precedence: 	ParserPrec::None,
+            },
+            TokenKind::PercentEqual => return ParserRule::null(),
             TokenKind::PipePipe => return ParserRule {
                 prefix: 	None, 
                 infix: 		Some(Parser::or), 
                 precedence: 	ParserPrec::Or,
             },
+            TokenKind::PlusEqual => return ParserRule::null(),
+            TokenKind::PlusPlus => return ParserRule {
+                prefix: 	Some(Parser::increment), 
+                infix: 		None, 
+                precedence: 	ParserPrec::None,
+            },
+            TokenKind::SlashEqual => return ParserRule::null(),
+            TokenKind::StarEqual => return ParserRule::null(),
 
             // Literals
             TokenKind::Base2Number => return ParserRule {
@@ -197,14 +243,19 @@ impl<I: Tokenize> ParserRule<I> {
             },
 
             // Keywords
+            TokenKind::As => return ParserRule::null(),
             TokenKind::Break => return ParserRule::null(),
+            TokenKind::Catch => return ParserRule::null(),
             TokenKind::Class => return ParserRule::null(),
             TokenKind::Const => return ParserRule::null(),
             TokenKind::Continue => return ParserRule::null(),
             TokenKind::Debug => return ParserRule::null(),
             TokenKind::Else => return ParserRule::null(),
             TokenKind::Exit => return ParserRule::null(),
+            TokenKind::Export => return ParserRule::null(),
+            TokenKind::Finally => return ParserRule::null(),
             TokenKind::If => return ParserRule::null(),
+            TokenKind::Import => return ParserRule::null(),
             TokenKind::In => return ParserRule::null(),
             TokenKind::Is => return ParserRule {
                 prefix: 	None, 
@@ -225,9 +276,15 @@ impl<I: Tokenize> ParserRule<I> {
                 infix: 		None, 
                 precedence: 	ParserPrec::None,
             },
+            TokenKind::Throw => return ParserRule::null(),
+            TokenKind::Try => return ParserRule::null(),
             TokenKind::Var => return ParserRule::null(),
             TokenKind::For => return ParserRule::null(),
-            TokenKind::Fun => return ParserRule::null(),
+            TokenKind::Fun => return ParserRule {
+                prefix: 	Some(Parser::lambda), 
+                infix: 		None, 
+                precedence: 	ParserPrec::None,
+            },
             TokenKind::While => return ParserRule::null(),
             
             // Internal
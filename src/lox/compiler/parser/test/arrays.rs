@@ -75,7 +75,8 @@ fn assign_three_selves_in_global() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'a' in its own initializer");
 }
 
@@ -85,7 +86,8 @@ fn assign_three_selves_in_local() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'a' in its own initializer");
 }
 
@@ -0,0 +1,26 @@
+use super::test;
+
+
+#[test]
+fn binary_operators() {
+    let code = "var a = 1 & 2 | 3 ^ 4 << 5 >> 6;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn unary_not() {
+    let code = "var a = ~1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn missing_operand() {
+    let code = "var a = 1 <<;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
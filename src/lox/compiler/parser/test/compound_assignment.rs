@@ -0,0 +1,91 @@
+
+use super::test;
+
+
+#[test]
+fn compound_assignment_global() {
+    let code = "var a = 1; a += 2; a -= 1; a *= 3; a /= 2; a %= 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn compound_assignment_local() {
+    let code = "{ var a = 1; a += 2; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn compound_assignment_subscript() {
+    let code = "var a = [1, 2]; a[0] += 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn compound_assignment_empty_subscript() {
+    let code = "var a = [1, 2]; a[] += 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn compound_assignment_constant() {
+    let code = "const a = 1; a += 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn compound_assignment_invalid_target() {
+    let code = "var a = 1; var b = 2; a + b += 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn increment_prefix_and_postfix() {
+    let code = "var a = 1; ++a; a++; --a; a--;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn increment_constant() {
+    let code = "const a = 1; a++;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn increment_prefix_constant() {
+    let code = "const a = 1; ++a;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn increment_prefix_invalid_target() {
+    let code = "var a = 1; ++(a);";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn increment_this() {
+    let code = "class A { f() { this++; } }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
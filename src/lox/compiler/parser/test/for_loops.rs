@@ -229,7 +229,8 @@ fn parser_naked_break() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'break' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -243,7 +244,8 @@ fn parser_naked_continue() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'continue' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
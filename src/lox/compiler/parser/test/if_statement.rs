@@ -48,7 +48,8 @@ fn parser_if_statement_missing_paren_1() {
     let code = "if true) {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '(' after 'if', got 'true'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -61,7 +62,8 @@ fn parser_if_statement_missing_paren_2() {
     let code = "if (true {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected ')' after 'if'-condition, got '{'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -74,7 +76,8 @@ fn parser_if_statement_missing_expression() {
     let code = "if () {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected conditional expression, got ')'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -87,7 +90,8 @@ fn parser_if_statement_naked_expression() {
     let code = "if true {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '(' after 'if', got 'true'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -100,7 +104,8 @@ fn parser_if_statement_missing_then() {
     let code = "if (true)";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected expression");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -113,7 +118,8 @@ fn parser_if_statement_open_then() {
     let code = "if (true) {";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '}' after block, got '\0'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -126,7 +132,8 @@ fn parser_if_statement_with_else_nothing() {
     let code = "if (true) {} else";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected expression");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -139,7 +146,8 @@ fn parser_if_statement_with_open_else() {
     let code = "if (true) {} else {";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '}' after block, got '\0'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -152,7 +160,8 @@ fn parser_else_not_after_if() {
     let code = "else exit;";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'else' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -0,0 +1,83 @@
+
+use super::test;
+
+
+#[test]
+fn lambda_expression() {
+    let code = "var f = fun (a, b) { return a + b; };";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn lambda_no_params() {
+    let code = "var f = fun () { return 1; };";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn lambda_statement() {
+    let code = "fun () { return 1; }();";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn lambda_missing_body() {
+    let code = "var f = fun (a);";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
+
+#[test]
+fn arrow_expression_body() {
+    let code = "var f = (x) => x * 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn arrow_block_body() {
+    let code = "var f = (x, y) => { return x * y; };";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn arrow_no_params() {
+    let code = "var f = () => 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn arrow_trailing_comma() {
+    let code = "var f = (x, y,) => x;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn arrow_grouping_still_works() {
+    let code = "var x = 1; var y = (x) + 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn arrow_missing_body() {
+    let code = "var f = (x) =>;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), false);
+}
@@ -31,7 +31,8 @@ fn parser_cannot_assign_to_null() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Invalid assignment target");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -67,7 +68,8 @@ fn parser_cannot_assign_to_true() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Invalid assignment target");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -103,7 +105,8 @@ fn parser_cannot_assign_to_false() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Invalid assignment target");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -0,0 +1,61 @@
+use super::test;
+
+
+#[test]
+fn assign_empty() {
+    let code = "var a={};";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+    let bytecode = res.unwrap();
+    assert_eq!(bytecode.globals().count(), 1);
+    assert_eq!(bytecode.main().clone().kind().is_toplevel(), true);
+}
+
+#[test]
+fn assign_one_pair() {
+    let code = "var a={'foo': 1};";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn assign_mixed_keys() {
+    let code = "var a={'foo': 1, 2: 'bar', true: null,};";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn block_is_not_map() {
+    let code = "{ var a=1; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn missing_colon() {
+    let code = "var a={'foo' 1};";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn missing_value() {
+    let code = "var a={'foo':};";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn missing_right_curly() {
+    let code = "var a={'foo': 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -1,6 +1,9 @@
 
 
-use crate::lox::common::{At, Function, FunctionKind};
+use std::rc::Rc;
+
+
+use crate::lox::common::{Function, FunctionKind, Location};
 use super::test;
 
 
@@ -49,7 +52,8 @@ fn parser_global_var_duplicate() {
     let code = "var a; var a;";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Global 'a' already declared");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -72,7 +76,8 @@ fn parser_global_function_duplicate() {
     let code = "fun f() {} fun f() {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Global 'f' already declared");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -95,7 +100,8 @@ fn parser_global_class_malformed_1() {
     let code = "class c() {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '{' after class name, got '('");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -108,7 +114,8 @@ fn parser_global_class_malformed_2() {
     let code = "class c {";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '}' after class body, got '\0'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -121,7 +128,8 @@ fn parser_global_class_malformed_3() {
     let code = "class {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected class name, got '{'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -134,7 +142,8 @@ fn parser_global_class_duplicate() {
     let code = "class c {} class c {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Global 'c' already declared");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -147,7 +156,8 @@ fn parser_break_not_in_loop() {
     let code = "break;";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'break' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -160,7 +170,8 @@ fn parser_continue_not_in_loop() {
     let code = "continue;";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'continue' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -189,7 +200,8 @@ fn parser_function_256_params_bad() {
     );
     let res = test(code.as_str());
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not have more than 255 parameters");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -222,7 +234,8 @@ fn parser_duplicate_locals() {
     let code = "fun f() { var v; var v; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Variable named 'v' already declared in this scope");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -235,7 +248,8 @@ fn parser_duplicate_arg_bad() {
     let code = "fun f(v, v) {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Variable named 'v' already declared in this scope");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -248,7 +262,8 @@ fn parser_same_arg_and_local_bad() {
     let code = "fun f(v) { var v; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Variable named 'v' already declared in this scope");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -261,7 +276,8 @@ fn parser_undeclared_local_bad() {
     let code = "fun f() { return v; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "'v' not declared");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -274,7 +290,8 @@ fn parser_self_initialize_global_bad() {
     let code = "var v=v;"; // Globals can no longer self-initialize
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'v' in its own initializer");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -287,7 +304,8 @@ fn parser_self_initialize_local_bad() {
     let code = "fun f() { var v=v; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'v' in its own initializer");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -300,7 +318,8 @@ fn parser_initialize_local_using_same_global_impossible() {
     let code = "var v; fun f() { var v=v; }"; // 'v' is ambiguous, compiler assumes local 'v'
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'v' in its own initializer");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -333,7 +352,8 @@ fn parser_class_inherit_self_bad() {
     let code = "class c1 {} class c2 of c2 {}";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Class 'c2' can not inherit from itself");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -366,7 +386,8 @@ fn parser_super_without_superclass() {
     let code = "class c1 { m() { return super.m(); } }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'super' in a class with no superclass");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -389,7 +410,8 @@ fn parser_super_is_accessor_only() {
     let code = "class c1 {} class c2 of c1 { m() { return super; } }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '.' after 'super', got ';'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -400,8 +422,9 @@ fn parser_super_is_accessor_only() {
 
 #[test]
 fn function_declared_at() {
-    let at = At::new("filename");
+    let at = Location::new(Rc::from("filename"), 3, 7);
     let function = Function::new("__test__", FunctionKind::Script, Some(at.clone()));
     assert_eq!(&Some(at), function.at());
+    assert_eq!(function.at().as_ref().unwrap().to_string(), "line 3 char 7 of filename");
 }
 
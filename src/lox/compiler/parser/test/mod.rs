@@ -1,10 +1,13 @@
 
 
+use std::rc::Rc;
+
+
 use scanner::Scanner;
 
 
 use crate::lox::common::{ByteCode, Function, FunctionKind, Globals};
-use crate::lox::compiler::{ChunkWriter, CompileError, Locals, Tokenizer};
+use crate::lox::compiler::{ChunkWriter, CompileError, Locals, Modules, Tokenizer};
 
 
 use super::Parser;
@@ -12,23 +15,31 @@ use super::ParserOutput;
 
 
 mod arrays;
+mod bitwise;
+mod maps;
+mod modules;
 mod base2numbers;
 mod base8numbers;
 mod base10numbers;
 mod base16numbers;
 mod for_loops;
 mod if_statement;
+mod lambdas;
 mod return_statement;
 mod trailing_comma;
 mod variables;
 mod constants;
+mod compound_assignment;
 mod while_loops;
 mod literals;
 mod strings;
+mod ternary;
+mod try_catch;
 mod misc;
+mod recovery;
 
 
-fn test(code: &str) -> Result<ByteCode, CompileError> {
+fn test(code: &str) -> Result<ByteCode, Vec<CompileError>> {
     
     // This code duplicates a lot of what the Compiler does. Hmm.
     let reader = std::io::Cursor::new(code);
@@ -36,13 +47,14 @@ fn test(code: &str) -> Result<ByteCode, CompileError> {
     let mut input = Tokenizer::new(scanner);
 
     let function = Function::new("__test__", FunctionKind::Script, None);
-    let mut writer = ChunkWriter::new(function);
+    let mut writer = ChunkWriter::new(function, &Rc::from("test"));
     let mut globals = Globals::new();
 
     let mut output = ParserOutput {
         writer: 	&mut writer,
         globals: 	&mut globals,
         locals:	&mut Locals::new(false),
+        modules:	&mut Modules::new(0),
     };
 
     // Be verbose to make debugging a little easier
@@ -51,9 +63,9 @@ fn test(code: &str) -> Result<ByteCode, CompileError> {
             println!("parse() returned Ok({:?})", function);
             return Ok(ByteCode::new(function, globals));
         }
-        Err(compile_error) => {
-            println!("parse() returned Err({:?})", compile_error);
-            return Err(compile_error);
+        Err(compile_errors) => {
+            println!("parse() returned Err({:?})", compile_errors);
+            return Err(compile_errors);
         }
     }
 }
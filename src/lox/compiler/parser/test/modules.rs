@@ -0,0 +1,98 @@
+use super::test;
+
+
+#[test]
+fn import_module() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn import_module_local() {
+    let code = "{ import 'src/lox/vm/test/testlib/greeter.lox' as g; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn import_missing_file() {
+    let code = "import 'not_found.lox' as m;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_missing_as() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' g;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_missing_name() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_missing_path() {
+    let code = "import as m;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_twice_same_name() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; import 'src/lox/vm/test/testlib/greeter.lox' as g;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_circular() {
+    let code = "import 'src/lox/vm/test/testlib/circular_a.lox' as a;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_private_globals() {
+    let code = "var secret = 1; import 'src/lox/vm/test/testlib/private.lox' as p;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn import_name_is_constant() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; g = 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn export_from_script() {
+    let code = "export var a = 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn export_without_declaration() {
+    let code = "export 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -0,0 +1,83 @@
+
+use super::test;
+
+
+#[test]
+fn recovery_reports_every_statement() {
+    let code = "var 1 = 2;\nprint 3;\nif true) print 4;\nprint 5;\nvar a = (1;\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 1);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 3);
+    assert_eq!(errors[2].get_at().unwrap().lineno(), 5);
+}
+
+#[test]
+fn recovery_inside_function_body() {
+    let code = "fun f() {\n  var 1;\n  return 2;\n}\nfun g() {\n  return (1;\n}\nprint f() + g();\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 2);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 6);
+}
+
+#[test]
+fn recovery_inside_nested_blocks() {
+    let code = "{\n  {\n    var 1;\n  }\n  var b = 1;\n  print (b;\n}\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 3);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 6);
+}
+
+#[test]
+fn recovery_skips_block_of_failed_statement() {
+    let code = "if (true +) {\n  print 1;\n}\nwhile false) {\n  print 2;\n}\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 1);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 4);
+}
+
+#[test]
+fn recovery_reports_errors_on_same_line() {
+    let code = "var 1 = 2; print 3; var 2 = 4;\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 1);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 1);
+}
+
+#[test]
+fn recovery_resumes_after_semicolon() {
+    // Parsing resumes inside the 'for' header, so the rest of it is reported as well
+    let code = "for (var i = 0 i < 3; i++) { print i; }\nprint 1;\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 1);
+}
+
+#[test]
+fn recovery_keeps_failed_declaration() {
+    // Using 'a' and 'f' after their declarations failed is not another error
+    let code = "var a = (1;\nfun f(1) { }\nprint a;\nprint f;\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn recovery_in_class() {
+    let code = "class A {\n  f() { return 1 +; }\n  g() { return this.f(); }\n}\nvar a = A(1;\n";
+    println!("code={}", code);
+    let errors = test(code).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].get_at().unwrap().lineno(), 2);
+    assert_eq!(errors[1].get_at().unwrap().lineno(), 5);
+}
@@ -8,7 +8,8 @@ fn parser_return_at_toplevel() {
     let code = "return;";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not 'return' from top level code");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -41,7 +42,8 @@ fn parser_return_super_from_function() {
     let code = "fun fn() { return super; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'super' outside of a class");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -54,7 +56,8 @@ fn parser_return_this_from_function() {
     let code = "fun fn() { return this; }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not use 'this' outside of a class");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -77,7 +80,8 @@ fn parser_return_value_from_initializer_bad() {
     let code = "class c { init() { return 1; } }";
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Can not 'return' a value from initializer");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
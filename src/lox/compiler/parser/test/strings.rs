@@ -64,7 +64,8 @@ fn parser_cannot_assign_to_literal_string() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Invalid assignment target");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -78,7 +79,8 @@ fn parser_unterminated_emptystring() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected expression");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -92,7 +94,8 @@ fn parser_unterminated_anystring() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected expression");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -0,0 +1,58 @@
+use super::test;
+
+
+#[test]
+fn ternary_literals() {
+    let code = "var a = true ? 1 : 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn ternary_nested() {
+    let code = "var a = 1; var b = a == 1 ? 'one' : a == 2 ? 'two' : 'many';";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn ternary_nested_in_then() {
+    let code = "var a = true ? false ? 1 : 2 : 3;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn ternary_missing_colon() {
+    let code = "var a = true ? 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn ternary_missing_else() {
+    let code = "var a = true ? 1 :;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn ternary_missing_condition() {
+    let code = "var a = ? 1 : 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn ternary_not_assignable() {
+    let code = "var a; var b; true ? a : b = 1;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -0,0 +1,114 @@
+use super::test;
+
+
+#[test]
+fn try_catch() {
+    let code = "try { var a = 1; } catch { var b = 2; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_catch_variable() {
+    let code = "try { throw 1; } catch (e) { print e; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_finally() {
+    let code = "try { var a = 1; } finally { var b = 2; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_catch_finally() {
+    let code = "try { throw 1; } catch (e) { print e; } finally { print 2; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_nested() {
+    let code = "try { try { throw 1; } finally { } } catch (e) { throw e; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_inside_loop() {
+    let code = "while (true) { try { break; } catch { continue; } }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_inside_function() {
+    let code = "fun f() { try { return 1; } catch (e) { return e; } }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_ok(), true);
+}
+
+#[test]
+fn try_without_catch_or_finally() {
+    let code = "try { var a = 1; }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn try_without_block() {
+    let code = "try var a = 1; catch { }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn catch_without_try() {
+    let code = "catch { }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn catch_missing_variable() {
+    let code = "try { } catch () { }";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn catch_variable_is_local() {
+    let code = "try { } catch (e) { } print e;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn throw_missing_expression() {
+    let code = "throw;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn throw_missing_semicolon() {
+    let code = "throw 1 print 2;";
+    println!("code={}", code);
+    let res = test(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -31,7 +31,8 @@ fn parser_while_missing_left_paren() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '(' after 'while', got 'true'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -45,7 +46,8 @@ fn parser_while_naked_expression() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected '(' after 'while', got 'true'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -59,7 +61,8 @@ fn parser_while_missing_right_paren() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected ')' after 'while'-condition, got '{'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -73,7 +76,8 @@ fn parser_while_missing_condition() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected conditional expression, got ')'");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -87,7 +91,8 @@ fn parser_while_missing_stmt() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Expected expression");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -101,7 +106,8 @@ fn parser_while_else() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'else' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -126,7 +132,8 @@ fn parser_naked_break() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'break' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -140,7 +147,8 @@ fn parser_naked_continue() {
     println!("code={}", code);
     let res = test(code);
     assert_eq!(res.is_err(), true);
-    let error = res.unwrap_err();
+    let errors = res.unwrap_err();
+    let error = &errors[0];
     assert_eq!(error.get_message(), "Keyword 'continue' is misplaced");
     assert_eq!(error.get_at().is_some(), true);
     let at = error.get_at().unwrap();
@@ -4,6 +4,9 @@
 // The compiler module is just a thin wrapper around the parser anyway
 
 
+use std::rc::Rc;
+
+
 use crate::lox::common::{Function, FunctionKind};
 use super::ChunkWriter;
 
@@ -11,6 +14,6 @@ use super::ChunkWriter;
 #[test]
 fn chunk_writer_new() {
     let function = Function::new("__test__", FunctionKind::Script, None);
-    let _writer = ChunkWriter::new(function);
+    let _writer = ChunkWriter::new(function, &Rc::from("test"));
 }
 
@@ -102,3 +102,77 @@ fn tokenizer_keyword_is() {
     assert_eq!(tokenizer.current().lexeme(), "\0");
 }
 
+
+#[test]
+fn tokenizer_bitwise_symbols() {
+    let code = "& | ^ ~ << >> < >";
+    let reader = std::io::Cursor::new(code);    
+    let scanner = Scanner::new("test", reader);
+    let mut tokenizer = Tokenizer::new(scanner);
+    let expected = [
+        (TokenKind::Amp, "&"),
+        (TokenKind::Pipe, "|"),
+        (TokenKind::Caret, "^"),
+        (TokenKind::Tilde, "~"),
+        (TokenKind::LessLess, "<<"),
+        (TokenKind::GreaterGreater, ">>"),
+        (TokenKind::Less, "<"),
+        (TokenKind::Greater, ">"),
+    ];
+    for (kind, lexeme) in expected.iter() {
+        assert_eq!(tokenizer.current().kind(), *kind);
+        assert_eq!(tokenizer.current().lexeme(), *lexeme);
+        tokenizer.advance();
+    }
+    assert_eq!(tokenizer.current().kind(), TokenKind::EOF);
+}
+
+#[test]
+fn tokenizer_arrow() {
+    let code = "= => ==";
+    let reader = std::io::Cursor::new(code);    
+    let scanner = Scanner::new("test", reader);
+    let mut tokenizer = Tokenizer::new(scanner);
+    assert_eq!(tokenizer.current().kind(), TokenKind::Equal);
+    tokenizer.advance();
+    assert_eq!(tokenizer.current().kind(), TokenKind::EqualGreater);
+    assert_eq!(tokenizer.current().lexeme(), "=>");
+    tokenizer.advance();
+    assert_eq!(tokenizer.current().kind(), TokenKind::EqualEqual);
+}
+
+#[test]
+fn tokenizer_peek() {
+    let code = "a + b";
+    let reader = std::io::Cursor::new(code);    
+    let scanner = Scanner::new("test", reader);
+    let mut tokenizer = Tokenizer::new(scanner);
+    assert_eq!(tokenizer.peek(2).lexeme(), "b");
+    assert_eq!(tokenizer.peek(1).kind(), TokenKind::Plus);
+    assert_eq!(tokenizer.peek(3).kind(), TokenKind::EOF);
+    assert_eq!(tokenizer.current().lexeme(), "a");
+    tokenizer.advance();
+    assert_eq!(tokenizer.previous().lexeme(), "a");
+    assert_eq!(tokenizer.current().kind(), TokenKind::Plus);
+    tokenizer.advance();
+    assert_eq!(tokenizer.current().lexeme(), "b");
+    tokenizer.advance();
+    assert_eq!(tokenizer.eof(), true);
+}
+
+#[test]
+fn tokenizer_update_symbols() {
+    let code = "+= -= *= /= %= ++ -- + -";
+    let reader = std::io::Cursor::new(code);    
+    let scanner = Scanner::new("test", reader);
+    let mut tokenizer = Tokenizer::new(scanner);
+    let kinds = [
+        TokenKind::PlusEqual, TokenKind::MinusEqual, TokenKind::StarEqual, TokenKind::SlashEqual,
+        TokenKind::PercentEqual, TokenKind::PlusPlus, TokenKind::MinusMinus, TokenKind::Plus, TokenKind::Minus,
+    ];
+    for kind in kinds {
+        assert_eq!(tokenizer.current().kind(), kind);
+        tokenizer.advance();
+    }
+    assert_eq!(tokenizer.eof(), true);
+}
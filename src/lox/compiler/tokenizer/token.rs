@@ -73,6 +73,8 @@ impl std::fmt::Debug for TokenKind {
             // Single character symbols
             TokenKind::Amp		=> write!(f, "Amp"),
             TokenKind::Bang 		=> write!(f, "Bang"),
+            TokenKind::Caret 		=> write!(f, "Caret"),
+            TokenKind::Colon 		=> write!(f, "Colon"),
             TokenKind::Comma 		=> write!(f, "Comma"),
             TokenKind::Dot 		=> write!(f, "Dot"),
             TokenKind::Equal 		=> write!(f, "Equal"),
@@ -85,20 +87,32 @@ impl std::fmt::Debug for TokenKind {
             TokenKind::Percent          => write!(f, "Percent"),
             TokenKind::Pipe		=> write!(f, "Pipe"),
             TokenKind::Plus 		=> write!(f, "Plus"),
+            TokenKind::Question 	=> write!(f, "Question"),
             TokenKind::RightBracket	=> write!(f, "RightBracket"),
             TokenKind::RightCurly	=> write!(f, "RightCurly"),
             TokenKind::RightParen	=> write!(f, "RightParen"),
             TokenKind::Semicolon	=> write!(f, "Semicolon"),
             TokenKind::Slash 		=> write!(f, "Slash"),
             TokenKind::Star 		=> write!(f, "Star"),
+            TokenKind::Tilde 		=> write!(f, "Tilde"),
             
             // Double character symbols
             TokenKind::AmpAmp		=> write!(f, "AmpAmp"),
             TokenKind::BangEqual 	=> write!(f, "BangEqual"),
             TokenKind::EqualEqual 	=> write!(f, "EqualEqual"),
+            TokenKind::EqualGreater	=> write!(f, "EqualGreater"),
             TokenKind::GreaterEqual	=> write!(f, "GreaterEqual"),
+            TokenKind::GreaterGreater	=> write!(f, "GreaterGreater"),
             TokenKind::LessEqual	=> write!(f, "LessEqual"),
+            TokenKind::LessLess	=> write!(f, "LessLess"),
+            TokenKind::MinusEqual	=> write!(f, "MinusEqual"),
+            TokenKind::MinusMinus	=> write!(f, "MinusMinus"),
+            TokenKind::PercentEqual	=> write!(f, "PercentEqual"),
             TokenKind::PipePipe		=> write!(f, "PipePipe"),
+            TokenKind::PlusEqual	=> write!(f, "PlusEqual"),
+            TokenKind::PlusPlus		=> write!(f, "PlusPlus"),
+            TokenKind::SlashEqual	=> write!(f, "SlashEqual"),
+            TokenKind::StarEqual	=> write!(f, "StarEqual"),
             
             // Literals
             TokenKind::Base2Number 	=> write!(f, "Base2Number"),
@@ -114,16 +128,21 @@ impl std::fmt::Debug for TokenKind {
             TokenKind::True		=> write!(f, "True"),
             
             // Keywords
+            TokenKind::As		=> write!(f, "As"),
             TokenKind::Break		=> write!(f, "Break"),
+            TokenKind::Catch		=> write!(f, "Catch"),
             TokenKind::Class		=> write!(f, "Class"),
             TokenKind::Const		=> write!(f, "Const"),
             TokenKind::Continue		=> write!(f, "Continue"),
             TokenKind::Debug		=> write!(f, "Debug"),
             TokenKind::Else		=> write!(f, "Else"),
             TokenKind::Exit		=> write!(f, "Exit"),
+            TokenKind::Export		=> write!(f, "Export"),
+            TokenKind::Finally		=> write!(f, "Finally"),
             TokenKind::For		=> write!(f, "For"),
             TokenKind::Fun		=> write!(f, "Fun"),
             TokenKind::If		=> write!(f, "If"),
+            TokenKind::Import		=> write!(f, "Import"),
             TokenKind::In		=> write!(f, "In"),
             TokenKind::Is		=> write!(f, "Is"),
             TokenKind::Not      => write!(f, "Not"),
@@ -132,6 +151,8 @@ impl std::fmt::Debug for TokenKind {
             TokenKind::Return 		=> write!(f, "Return"),
             TokenKind::Super 		=> write!(f, "Super"),
             TokenKind::This 		=> write!(f, "This"),
+            TokenKind::Throw 		=> write!(f, "Throw"),
+            TokenKind::Try 		=> write!(f, "Try"),
             TokenKind::Var		=> write!(f, "Var"),
             TokenKind::While		=> write!(f, "While"),
             
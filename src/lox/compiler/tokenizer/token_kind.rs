@@ -7,6 +7,8 @@ pub enum TokenKind {
     // Single symbol
     Amp,
     Bang,
+    Caret,
+    Colon,
     Comma,
     Dot,
     Equal,
@@ -19,20 +21,32 @@ pub enum TokenKind {
     Percent,
     Pipe,
     Plus,
+    Question,
     RightBracket,
     RightCurly,
     RightParen,
     Semicolon,
     Slash,
     Star,
+    Tilde,
 
     // Double symbol
     AmpAmp,
     BangEqual,
     EqualEqual,
+    EqualGreater,
     GreaterEqual,
+    GreaterGreater,
     LessEqual,
+    LessLess,
+    MinusEqual,
+    MinusMinus,
+    PercentEqual,
     PipePipe,
+    PlusEqual,
+    PlusPlus,
+    SlashEqual,
+    StarEqual,
 
     // Literals
     Base2Number,
@@ -48,16 +62,21 @@ pub enum TokenKind {
     True,
     
     // Keywords
+    As,
     Break,
+    Catch,
     Class,
     Const,
     Continue,
     Debug,
     Else,
     Exit,
+    Export,
+    Finally,
     For,
     Fun,
     If,
+    Import,
     In,
     Is,
     Not,
@@ -66,6 +85,8 @@ pub enum TokenKind {
     Return,
     Super,
     This,
+    Throw,
+    Try,
     Var,
     While,
     
@@ -8,6 +8,7 @@ pub trait Tokenize {
     fn previous(&self) -> &Token;
     fn eof(&self) -> bool;
     fn advance(&mut self);
+    fn peek(&mut self, distance: usize) -> &Token;
     fn matches(&self, kind: TokenKind) -> bool;
     fn advance_on(&mut self, kind: TokenKind) -> bool;
 }
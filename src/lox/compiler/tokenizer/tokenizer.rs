@@ -1,5 +1,8 @@
 
 
+use std::collections::VecDeque;
+
+
 use at::At;
 use scanner::{Scan, Scanner, Scanners};
 
@@ -20,6 +23,7 @@ pub struct Tokenizer<'a> {
     scanners: Scanners<'a>,
     current: Option<Token>,
     previous: Option<Token>,
+    lookahead: VecDeque<Token>, // Scanned by peek() but not yet current
     library: String,
     included: Vec<String>,
 }
@@ -40,6 +44,7 @@ impl<'a> Tokenizer<'a> {
             scanners: 	Scanners::new(scanner),
             current: 	None,
             previous:	None,
+            lookahead:	VecDeque::new(),
             library: String::from(library),
             included: vec![],
         };
@@ -77,34 +82,25 @@ impl<'a> Tokenize for Tokenizer<'a> {
     // Advance to next token
     fn advance(&mut self) {
         self.previous = self.current.take();
-
-        self.skip_whitespace();
-
-        // Process directives, if any
-        while self.scanner().current() == '#' { 
-            if let Err(msg) = self.directive() {
-                self.current = Some(Token::new_at(TokenKind::Error, msg.as_str(), self.scanner().at()));
-                return;
-            }
-            self.skip_whitespace();
-        }
-
-        let token;
-        if self.scanner().eof() {
-
-            // EOF will require some special handling when we get to 
-            // #include directives. For now, simply produce an EOF token.
-            token = Token::new_at(TokenKind::EOF, "\0", self.scanner().at());
-
-        } else {
-            token = self.scan_next_token();
-        }
-        
+        let token = match self.lookahead.pop_front() {
+            Some(token) => token,
+            None => self.next_token(),
+        };
         self.current = Some(token);
         
         //println!("Tokenizer.advance() previous={:?}, current={:?}", self.previous, self.current);
     }
 
+    // Return a reference to a token after current without advancing,
+    // peek(1) being the one that advance() will make current
+    fn peek(&mut self, distance: usize) -> &Token {
+        while self.lookahead.len() < distance {
+            let token = self.next_token();
+            self.lookahead.push_back(token);
+        }
+        return &self.lookahead[distance - 1];
+    }
+
     // Return true if current tokenkind matches
     fn matches(&self, kind: TokenKind) -> bool {
         return self.current().matches(kind);
@@ -127,6 +123,29 @@ impl<'a> Tokenizer<'a> {
         return &mut self.scanners;
     }
 
+    // Scan the next token from input
+    fn next_token(&mut self) -> Token {
+        self.skip_whitespace();
+
+        // Process directives, if any
+        while self.scanner().current() == '#' { 
+            if let Err(msg) = self.directive() {
+                return Token::new_at(TokenKind::Error, msg.as_str(), self.scanner().at());
+            }
+            self.skip_whitespace();
+        }
+
+        if self.scanner().eof() {
+
+            // EOF will require some special handling when we get to 
+            // #include directives. For now, simply produce an EOF token.
+            return Token::new_at(TokenKind::EOF, "\0", self.scanner().at());
+
+        } else {
+            return self.scan_next_token();
+        }
+    }
+
     // Use scanner to produce next Token
     fn scan_next_token(&mut self) -> Token {
         let c = self.scanner().current();
@@ -209,17 +228,22 @@ impl<'a> Tokenizer<'a> {
             self.scanner().advance();    
         }
         match lexeme.as_str() {
+            KEYWORD_AS 	    => return Token::new_at(TokenKind::As, &lexeme, &at),
             KEYWORD_BREAK 	=> return Token::new_at(TokenKind::Break, &lexeme, &at),
             KEYWORD_CLASS 	=> return Token::new_at(TokenKind::Class, &lexeme, &at),
             KEYWORD_CONST 	=> return Token::new_at(TokenKind::Const, &lexeme, &at),
+            KEYWORD_CATCH 	=> return Token::new_at(TokenKind::Catch, &lexeme, &at),
             KEYWORD_CONTINUE 	=> return Token::new_at(TokenKind::Continue, &lexeme, &at),
             KEYWORD_DEBUG 	=> return Token::new_at(TokenKind::Debug, &lexeme, &at),
             KEYWORD_ELSE 	=> return Token::new_at(TokenKind::Else, &lexeme, &at),
             KEYWORD_EXIT 	=> return Token::new_at(TokenKind::Exit, &lexeme, &at),
+            KEYWORD_EXPORT 	=> return Token::new_at(TokenKind::Export, &lexeme, &at),
+            KEYWORD_FINALLY 	=> return Token::new_at(TokenKind::Finally, &lexeme, &at),
             KEYWORD_FOR 	=> return Token::new_at(TokenKind::For, &lexeme, &at),
             KEYWORD_FUN 	=> return Token::new_at(TokenKind::Fun, &lexeme, &at),
             KEYWORD_FALSE 	=> return Token::new_at(TokenKind::False, &lexeme, &at),
             KEYWORD_IF 	=> return Token::new_at(TokenKind::If, &lexeme, &at),
+            KEYWORD_IMPORT 	=> return Token::new_at(TokenKind::Import, &lexeme, &at),
             KEYWORD_IN 	=> return Token::new_at(TokenKind::In, &lexeme, &at),
             KEYWORD_INF 	=> return Token::new_at(TokenKind::Inf, &lexeme, &at),
             KEYWORD_IS 	=> return Token::new_at(TokenKind::Is, &lexeme, &at),
@@ -231,7 +255,9 @@ impl<'a> Tokenizer<'a> {
             KEYWORD_RETURN 	=> return Token::new_at(TokenKind::Return, &lexeme, &at),
             KEYWORD_SUPER 	=> return Token::new_at(TokenKind::Super, &lexeme, &at),
             KEYWORD_THIS 	=> return Token::new_at(TokenKind::This, &lexeme, &at),
+            KEYWORD_THROW 	=> return Token::new_at(TokenKind::Throw, &lexeme, &at),
             KEYWORD_TRUE 	=> return Token::new_at(TokenKind::True, &lexeme, &at),
+            KEYWORD_TRY 	=> return Token::new_at(TokenKind::Try, &lexeme, &at),
             KEYWORD_VAR 	=> return Token::new_at(TokenKind::Var,	&lexeme, &at),
             KEYWORD_WHILE 	=> return Token::new_at(TokenKind::While, &lexeme, &at),
             _ => return Token::new_at(TokenKind::Identifier, &lexeme, &at),
@@ -396,12 +422,11 @@ impl<'a> Tokenizer<'a> {
         let at = self.scanner().at().clone();
         match self.scanner().current() {
             ',' => return self.make_token_at(",", TokenKind::Comma, &at),
+            ':' => return self.make_token_at(":", TokenKind::Colon, &at),
             '.' => return self.make_token_at(".", TokenKind::Dot, &at),
-            '+' => return self.make_token_at("+", TokenKind::Plus, &at),
-            '-' => return self.make_token_at("-", TokenKind::Minus, &at),
-            '*' => return self.make_token_at("*", TokenKind::Star, &at),
-            '/' => return self.make_token_at("/", TokenKind::Slash, &at),
-            '%' => return self.make_token_at("%", TokenKind::Percent, &at),
+            '?' => return self.make_token_at("?", TokenKind::Question, &at),
+            '^' => return self.make_token_at("^", TokenKind::Caret, &at),
+            '~' => return self.make_token_at("~", TokenKind::Tilde, &at),
             ';' => return self.make_token_at(";", TokenKind::Semicolon, &at),
             '[' => return self.make_token_at("[", TokenKind::LeftBracket, &at),
             '{' => return self.make_token_at("{", TokenKind::LeftCurly, &at),
@@ -415,6 +440,38 @@ impl<'a> Tokenizer<'a> {
                     _ => return self.make_token_at("&", TokenKind::Amp, &at),
                 }
             }
+            '+' => {
+                match self.scanner().peek() {
+                    '+' => return self.make_token_at("++", TokenKind::PlusPlus, &at),
+                    '=' => return self.make_token_at("+=", TokenKind::PlusEqual, &at),
+                    _ => return self.make_token_at("+", TokenKind::Plus, &at),
+                }
+            }
+            '-' => {
+                match self.scanner().peek() {
+                    '-' => return self.make_token_at("--", TokenKind::MinusMinus, &at),
+                    '=' => return self.make_token_at("-=", TokenKind::MinusEqual, &at),
+                    _ => return self.make_token_at("-", TokenKind::Minus, &at),
+                }
+            }
+            '*' => {
+                match self.scanner().peek() {
+                    '=' => return self.make_token_at("*=", TokenKind::StarEqual, &at),
+                    _ => return self.make_token_at("*", TokenKind::Star, &at),
+                }
+            }
+            '/' => {
+                match self.scanner().peek() {
+                    '=' => return self.make_token_at("/=", TokenKind::SlashEqual, &at),
+                    _ => return self.make_token_at("/", TokenKind::Slash, &at),
+                }
+            }
+            '%' => {
+                match self.scanner().peek() {
+                    '=' => return self.make_token_at("%=", TokenKind::PercentEqual, &at),
+                    _ => return self.make_token_at("%", TokenKind::Percent, &at),
+                }
+            }
             '|' => {
                 match self.scanner().peek() {
                     '|' => return self.make_token_at("||", TokenKind::PipePipe, &at),
@@ -424,12 +481,14 @@ impl<'a> Tokenizer<'a> {
             '>' => {
                 match self.scanner().peek() {
                     '=' => return self.make_token_at(">=", TokenKind::GreaterEqual, &at),
+                    '>' => return self.make_token_at(">>", TokenKind::GreaterGreater, &at),
                     _ => return self.make_token_at(">", TokenKind::Greater, &at),
                 }
             }
             '<' => {
                 match self.scanner().peek() {
                     '=' => return self.make_token_at("<=", TokenKind::LessEqual, &at),
+                    '<' => return self.make_token_at("<<", TokenKind::LessLess, &at),
                     _ => return self.make_token_at("<", TokenKind::Less, &at),
                 }
             }
@@ -442,6 +501,7 @@ impl<'a> Tokenizer<'a> {
             '=' => {
                 match self.scanner().peek() {
                     '=' => return self.make_token_at("==", TokenKind::EqualEqual, &at),
+                    '>' => return self.make_token_at("=>", TokenKind::EqualGreater, &at),
                     _ => return self.make_token_at("=", TokenKind::Equal, &at),
                 }
             }
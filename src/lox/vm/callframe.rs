@@ -1,12 +1,19 @@
 
 use std::cell::{Ref, RefMut};
+use std::rc::Rc;
 
 
-use crate::lox::common::{Closure, OpCode, Value};
+use crate::lox::common::{Closure, InlineCaches, Location, OpCode, Symbol, Value};
 
 
 pub struct CallFrame {
     closure_value:	Value,
+    // The code and constants of the function are cached here so the VM
+    // does not need to borrow the closure and function for every byte
+    code:		Rc<Vec<u8>>,
+    constants:		Rc<Vec<Value>>,
+    symbols:		Rc<Vec<Option<Symbol>>>,
+    inline_caches:	Rc<InlineCaches>,
     ip: 		u32,
     stack_bottom:	usize,
 }
@@ -18,8 +25,18 @@ impl CallFrame {
             panic!("{} is not a Closure", closure);
         }
         //println!("CallFrame.new() stack_bottom={}", stack_bottom);
+        let (code, constants, symbols, inline_caches) = {
+            let closure = closure.as_closure();
+            let function = closure.function_ref();
+            let function_constants = function.read_constants();
+            (function.read_chunk().shared_code(), function_constants.shared_values(), function_constants.shared_symbols(), function.shared_inline_caches())
+        };
         CallFrame { 
             closure_value:	closure,
+            code,
+            constants,
+            symbols,
+            inline_caches,
             ip: 		0,
             stack_bottom,
         }
@@ -35,23 +52,51 @@ impl CallFrame {
     pub fn closure_mut(&mut self) -> RefMut<'_, Closure> {
         return self.closure_value.as_closure_mut();
     }
+    // ...or as a Value
+    pub fn closure_value(&self) -> &Value {
+        return &self.closure_value;
+    }
 
 
     // Shorthand functions for reading the bytecode
+    #[inline]
     pub fn read_op(&mut self) -> OpCode {
-        let byte = self.closure_ref().function_ref().read_chunk().read_bytes(self.ip, 1) as u8;
+        let byte = self.code[self.ip as usize];
         self.ip = self.ip + 1;
         return byte.into();
     }
     
 
+    #[inline]
     pub fn read_bytes(&mut self, len: usize) -> u32 {
-        let result = self.closure_ref().function_ref().read_chunk().read_bytes(self.ip, len);
+        let start = self.ip as usize;
+        let mut result = 0;
+        for byte in &self.code[start..start + len] {
+            result = (result << 8) + *byte as u32;
+        }
         self.ip = self.ip + len as u32;
         return result;
     }
 
 
+    #[inline]
+    pub fn constant(&self, id: usize) -> &Value {
+        return &self.constants[id];
+    }
+
+
+    // Symbol of a constant naming a field or method
+    #[inline]
+    pub fn symbol(&self, id: usize) -> Option<Symbol> {
+        return self.symbols[id];
+    }
+
+
+    pub fn inline_caches(&self) -> &InlineCaches {
+        return &self.inline_caches;
+    }
+
+
     // State of the callframe itself
     pub fn ip(&self) -> u32 {
         return self.ip;
@@ -65,13 +110,23 @@ impl CallFrame {
         return self.stack_bottom;
     }
 
+
+    // Source location of the most recently read instruction
+    // Note: ip already points past the opcode at this point
+    pub fn at(&self) -> Option<Location> {
+        let ip = self.ip.saturating_sub(1);
+        return self.closure_ref().function_ref().read_chunk().at(ip).cloned();
+    }
+
 }
 
 
 // Used by the VM when generating a stack trace after a RuntimeError
 impl std::fmt::Debug for CallFrame {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.closure_ref().function_ref().at() {
+        // Fall back to where the function was declared
+        let at = self.at().or_else(|| self.closure_ref().function_ref().at().clone());
+        match at {
             Some(at) => write!(f, "{}:0x{:08x} at {}", self.closure_ref().name(), self.ip(), at),
             None => write!(f, "{}:0x{:08x}", self.closure_ref().name(), self.ip()),
         }
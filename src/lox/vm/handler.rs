@@ -0,0 +1,38 @@
+
+
+// Exception handler installed by the Try opcode and removed by EndTry.
+// If a RuntimeError occurs while the handler is active, the VM discards
+// any callframes and stack entries above it and jumps to ip.
+#[derive(Debug)]
+pub struct Handler {
+    depth:	    usize, // Number of callframes when the handler was installed
+    stack_size:	usize,
+    ip: 		u32,
+}
+
+
+impl Handler {
+    pub fn new(depth: usize, stack_size: usize, ip: u32) -> Self {
+        Handler {
+            depth,
+            stack_size,
+            ip,
+        }
+    }
+
+
+    pub fn depth(&self) -> usize {
+        return self.depth;
+    }
+
+
+    pub fn stack_size(&self) -> usize {
+        return self.stack_size;
+    }
+
+
+    pub fn ip(&self) -> u32 {
+        return self.ip;
+    }
+
+}
@@ -0,0 +1,357 @@
+
+
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+
+use log::{debug, trace};
+
+
+use crate::lox::common::{allocations, Obj, Value, ValueIterator};
+use crate::lox::common::allocations::{Account, OBJ_SIZE};
+use super::Upvalue;
+
+// Runtime representation of the heap, owned by the VM.
+
+// Every Obj lives in an Rc<RefCell<Obj>> so acyclic garbage is already freed
+// by reference counting; what leaks is cycles. The collector marks everything
+// reachable from the VM roots, then treats any remaining object that is also
+// referenced from outside the heap (by the host application, a ByteCode etc.)
+// as a root too. That is found by comparing each object's strong count with
+// the number of references it has from other unreachable objects.
+// Whatever is still unmarked after that is garbage; the sweep replaces each
+// garbage object with an empty string, breaking the cycles, and Rc does the rest.
+
+
+type ObjRef = Rc<RefCell<Obj>>;
+
+
+const FIRST_COLLECTION: usize = 16 * 1024; // Allocations before the first collection
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeapStats {
+    pub objects: usize,         // Live objects known to the heap, including pending
+    pub pending: usize,         // Allocations not yet seen by the collector
+    pub collections: usize,     // Number of completed collections
+    pub freed: usize,           // Total number of objects freed by the collector
+}
+
+
+pub struct Heap {
+    objects: Vec<Weak<RefCell<Obj>>>,
+    next_collection: usize,
+    collections: usize,
+    freed: usize,
+    account: Option<Account>, // None while the VM runs and its account is active in allocations
+    outer: Option<Account>,   // The account active before, when a VM runs inside another
+}
+
+
+impl Heap {
+
+    pub fn new() -> Self {
+        Heap {
+            objects: vec![],
+            next_collection: FIRST_COLLECTION,
+            collections: 0,
+            freed: 0,
+            account: Some(Account::new()),
+            outer: None,
+        }
+    }
+
+
+    // Count allocations against this heap from now on.
+    // Returns false if it was already active, e.g. when a native calls back into the VM
+    pub fn activate(&mut self) -> bool {
+        match self.account.take() {
+            Some(account) => {
+                self.outer = allocations::activate(account);
+                return true;
+            }
+            None => return false,
+        }
+    }
+
+
+    // Undo activate(), making the account of an outer VM active again
+    pub fn deactivate(&mut self) {
+        self.account = Some(allocations::deactivate(self.outer.take()));
+    }
+
+
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        match self.account.as_mut() {
+            Some(account) => account.set_max_bytes(max_bytes),
+            None => allocations::set_max_bytes(max_bytes),
+        }
+    }
+
+
+    // Called by the VM after an instruction allocated something
+    pub fn should_collect(&self) -> bool {
+        return self.pending() >= self.next_collection;
+    }
+
+
+    pub fn stats(&mut self) -> HeapStats {
+        let pending = self.pending();
+        self.adopt();
+        HeapStats {
+            objects: self.objects.len(),
+            pending,
+            collections: self.collections,
+            freed: self.freed,
+        }
+    }
+
+
+    // Collect cyclic garbage, return the number of objects freed
+    pub fn collect(&mut self, roots: &[&Value], upvalue_roots: &[&Upvalue<Value>]) -> usize {
+        self.adopt();
+        debug!("collecting garbage, {} objects", self.objects.len());
+
+        let objects: Vec<ObjRef> = self.objects.iter().filter_map(|weak| weak.upgrade()).collect();
+        let garbage = match find_garbage(&objects, roots, upvalue_roots) {
+            Some(garbage) => garbage,
+            None => {
+                debug!("heap is busy, collection skipped");
+                return 0;
+            }
+        };
+
+        // Sweep: empty each garbage object. Dropping the contents releases
+        // the references that kept the cycle alive.
+        let mut contents = vec![];
+        for id in garbage.iter() {
+            contents.push(std::mem::replace(&mut *objects[*id].borrow_mut(), Obj::string("")));
+        }
+        drop(objects);
+        drop(contents);
+
+        self.objects.retain(|weak| weak.strong_count() > 0);
+        let bytes = self.measure();
+        match self.account.as_mut() {
+            Some(account) => account.set_bytes(bytes),
+            None => allocations::set_bytes(bytes),
+        }
+        self.next_collection = std::cmp::max(FIRST_COLLECTION, self.objects.len() * 2);
+        self.collections = self.collections + 1;
+        self.freed = self.freed + garbage.len();
+        debug!("freed {} objects, {} remain", garbage.len(), self.objects.len());
+        return garbage.len();
+    }
+
+
+    // Approximate bytes used by the known objects, see Obj::size()
+    fn measure(&self) -> usize {
+        return self.objects.iter()
+            .filter_map(|weak| weak.upgrade())
+            .map(|rc| rc.try_borrow().map_or(OBJ_SIZE, |obj| obj.size()))
+            .sum();
+    }
+
+
+    // Allocations not yet adopted, including those made while no VM was running
+    fn pending(&self) -> usize {
+        let own = match self.account.as_ref() {
+            Some(account) => account.pending(),
+            None => allocations::pending(),
+        };
+        return own + allocations::pending_outside();
+    }
+
+
+    // Take over allocations logged since last time and forget dead objects
+    fn adopt(&mut self) {
+        match self.account.as_mut() {
+            Some(account) => self.objects.extend(account.take()),
+            None => self.objects.extend(allocations::take()),
+        }
+        self.objects.extend(allocations::take_outside());
+        self.objects.retain(|weak| weak.strong_count() > 0);
+    }
+
+}
+
+
+// An outgoing reference from a heap object
+enum Edge<'a> {
+    Value(&'a Value),
+    Upvalue(&'a Upvalue<Value>),
+}
+
+
+// Call visit() for every reference held by obj
+fn trace<'a>(obj: &'a Obj, visit: &mut dyn FnMut(Edge<'a>)) {
+    match obj {
+        Obj::Array(array) => {
+            for value in array.as_slice() { visit(Edge::Value(value)); }
+        }
+        Obj::Function(function) => {
+            for value in function.read_constants().as_slice() { visit(Edge::Value(value)); }
+        }
+        Obj::Class(class) => {
+            if let Some(superclass) = class.superclass() { visit(Edge::Value(superclass)); }
+            for value in class.methods().values() { visit(Edge::Value(value)); }
+        }
+        Obj::Closure(closure) => {
+            visit(Edge::Value(closure.function_value()));
+            for upvalue in closure.upvalues() { visit(Edge::Upvalue(upvalue)); }
+        }
+        Obj::Instance(instance) => {
+            visit(Edge::Value(instance.class()));
+            for value in instance.field_values() { visit(Edge::Value(value)); }
+        }
+        Obj::Iterator(iterator) => {
+            match iterator {
+                ValueIterator::String(value, _, last) |
+                ValueIterator::Array(value, _, last) |
+                ValueIterator::Map(value, _, last) |
+                ValueIterator::Instance(value, last) => {
+                    visit(Edge::Value(value));
+                    visit(Edge::Value(last));
+                }
+            }
+        }
+        Obj::Map(map) => {
+            for (key, value) in map.iter() {
+                visit(Edge::Value(key));
+                visit(Edge::Value(value));
+            }
+        }
+        Obj::Method(method) => {
+            visit(Edge::Value(method.receiver()));
+            visit(Edge::Value(method.method()));
+        }
+        Obj::Module(module) => {
+            visit(Edge::Value(module.main()));
+        }
+        Obj::NativeMethod(native_method) => {
+            visit(Edge::Value(native_method.receiver()));
+            visit(Edge::Value(native_method.method()));
+        }
+        Obj::Native(_) | Obj::String(_) => {}
+    }
+}
+
+
+struct Marker<'a> {
+    objects: &'a [ObjRef],
+    index: HashMap<*const RefCell<Obj>, usize>,
+    marked: Vec<bool>,
+    worklist: Vec<usize>,
+}
+
+
+impl<'a> Marker<'a> {
+
+    fn new(objects: &'a [ObjRef]) -> Self {
+        Marker {
+            objects,
+            index: objects.iter().enumerate().map(|(id, rc)| (Rc::as_ptr(rc), id)).collect(),
+            marked: vec![false; objects.len()],
+            worklist: vec![],
+        }
+    }
+
+
+    fn id_of(&self, value: &Value) -> Option<usize> {
+        match value {
+            Value::Obj(rc) => self.index.get(&Rc::as_ptr(rc)).cloned(),
+            _ => None,
+        }
+    }
+
+
+    fn mark(&mut self, value: &Value) {
+        if let Some(id) = self.id_of(value) {
+            if !self.marked[id] {
+                self.marked[id] = true;
+                self.worklist.push(id);
+            }
+        }
+    }
+
+
+    fn mark_upvalue(&mut self, upvalue: &Upvalue<Value>) {
+        if let Some(value) = upvalue.value_ref().as_ref() {
+            self.mark(value);
+        }
+    }
+
+
+    // Mark everything reachable from the worklist
+    // Return false if an object could not be inspected
+    fn propagate(&mut self) -> bool {
+        let objects = self.objects;
+        while let Some(id) = self.worklist.pop() {
+            let obj = match objects[id].try_borrow() {
+                Ok(obj) => obj,
+                Err(_) => return false, // Object is being modified right now
+            };
+            trace(&obj, &mut |edge| match edge {
+                Edge::Value(value) => self.mark(value),
+                Edge::Upvalue(upvalue) => self.mark_upvalue(upvalue),
+            });
+        }
+        return true;
+    }
+
+}
+
+
+// Return the ids of all unreachable objects, or None if the heap could not be inspected
+fn find_garbage(objects: &[ObjRef], roots: &[&Value], upvalue_roots: &[&Upvalue<Value>]) -> Option<Vec<usize>> {
+    let mut marker = Marker::new(objects);
+
+    // Mark everything reachable from the VM
+    for value in roots { marker.mark(value); }
+    for upvalue in upvalue_roots { marker.mark_upvalue(upvalue); }
+    if !marker.propagate() { return None; }
+
+    // Count references between the remaining objects.
+    // Upvalue cells may be shared by several closures, count each cell once.
+    let mut internal = vec![0usize; objects.len()];
+    let mut cells: HashMap<*const RefCell<Option<Value>>, (usize, Upvalue<Value>)> = HashMap::new();
+    for id in 0..objects.len() {
+        if marker.marked[id] { continue; }
+        let obj = match objects[id].try_borrow() {
+            Ok(obj) => obj,
+            Err(_) => return None,
+        };
+        trace(&obj, &mut |edge| match edge {
+            Edge::Value(value) => {
+                if let Some(child) = marker.id_of(value) { internal[child] = internal[child] + 1; }
+            }
+            Edge::Upvalue(upvalue) => {
+                cells.entry(upvalue.cell_ptr()).or_insert_with(|| (0, upvalue.clone())).0 += 1;
+            }
+        });
+    }
+    for (_, (refs, upvalue)) in cells.iter() {
+        // Note: One reference is our own clone
+        if upvalue.cell_count() - 1 > *refs {
+            // Someone outside the heap holds this cell, e.g. VM.open_upvalues
+            marker.mark_upvalue(upvalue);
+        } else if let Some(value) = upvalue.value_ref().as_ref() {
+            if let Some(child) = marker.id_of(value) { internal[child] = internal[child] + 1; }
+        }
+    }
+
+    // Anything with more references than explained above is held from outside
+    for id in 0..objects.len() {
+        if marker.marked[id] { continue; }
+        // Note: One reference is held by objects[]
+        if Rc::strong_count(&objects[id]) - 1 > internal[id] {
+            trace!("object {} is referenced from outside the heap", id);
+            marker.marked[id] = true;
+            marker.worklist.push(id);
+        }
+    }
+    if !marker.propagate() { return None; }
+
+    return Some((0..objects.len()).filter(|id| !marker.marked[*id]).collect());
+}
@@ -0,0 +1,34 @@
+
+use std::time::Duration;
+
+
+use crate::lox::common::allocations;
+use super::{RuntimeError, RuntimeErrorKind};
+
+
+// Limits for running untrusted scripts, set with VM::set_limits().
+// The VM stops with a RuntimeError that scripts can not catch when one is
+// exceeded. Counting starts over with each execute(), and with each call()
+// from the host application once execute() has returned. Calls made by
+// natives while a script runs count towards the script's limits.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Limits {
+    pub max_instructions: Option<u64>,  // Number of executed instructions
+    pub timeout: Option<Duration>,      // Wall-clock time
+    pub max_bytes: Option<usize>,       // Approximate memory used by live objects on the heap
+}
+
+
+// The VM checks the memory limit between instructions. Natives that build
+// a string or array much larger than their arguments call this first,
+// so a single call can not exhaust memory before the VM gets to check.
+pub fn reserve(bytes: usize) -> Result<(), RuntimeError> {
+    if allocations::fits(bytes) { return Ok(()); }
+    return Err(memory_limit_exceeded());
+}
+
+
+pub fn memory_limit_exceeded() -> RuntimeError {
+    let message = format!("Memory limit of {} bytes exceeded", allocations::max_bytes());
+    return RuntimeError::limit(RuntimeErrorKind::MemoryLimit, message);
+}
@@ -5,6 +5,9 @@ mod test;
 
 
 mod callframe;
+mod handler;
+mod heap;
+mod limits;
 mod runtime;
 mod runtime_error;
 mod stack;
@@ -12,8 +15,11 @@ mod vm;
 
 
 pub use callframe::CallFrame;
+pub use handler::Handler;
+pub use heap::{Heap, HeapStats};
+pub use limits::{Limits, memory_limit_exceeded, reserve};
 pub use runtime::{Class, Instance, Method, NativeMethod, Upvalue};
-pub use runtime_error::{RuntimeError, r_error};
+pub use runtime_error::{RuntimeError, RuntimeErrorKind, r_error};
 pub use stack::Stack;
 pub use vm::VM;
 
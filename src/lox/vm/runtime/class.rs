@@ -1,16 +1,13 @@
 
 
-use std::collections::HashMap;
-
-
-use crate::lox::common::Value;
+use crate::lox::common::{Symbol, SymbolMap, Value};
 
 
 #[derive(Clone)]
 pub struct Class {
     name: String,
     superclass: Option<Value>,
-    methods: HashMap<String, Value>,
+    methods: SymbolMap<Value>,
 }
 
 
@@ -21,7 +18,7 @@ impl Class {
         Self {
             name: name.to_string(),
             superclass: None,
-            methods: HashMap::new(),
+            methods: SymbolMap::default(),
         }
     }
 
@@ -40,15 +37,23 @@ impl Class {
     }
 
     pub fn set(&mut self, name: &str, value: Value) {
-        self.methods.insert(name.to_string(), value);
+        self.methods.insert(Symbol::intern(name), value);
+    }
+
+    pub fn set_by_symbol(&mut self, name: Symbol, value: Value) {
+        self.methods.insert(name, value);
     }
 
     pub fn get(&self, name: &str) -> Option<&Value> {
-        return self.methods.get(name);
+        return self.methods.get(&Symbol::find(name)?);
+    }
+
+    pub fn get_by_symbol(&self, name: Symbol) -> Option<&Value> {
+        return self.methods.get(&name);
     }
 
-    // Used exclusively by .inherit_from()
-    fn methods(&self) -> &HashMap<String, Value> {
+    // Used by .inherit_from() and the garbage collector
+    pub fn methods(&self) -> &SymbolMap<Value> {
         return &self.methods;
     }
 
@@ -1,15 +1,22 @@
 
 
 use std::collections::HashMap;
+use std::rc::Rc;
 
 
-use crate::lox::common::Value;
+use crate::lox::common::{Symbol, SymbolMap, Value};
+use crate::lox::common::allocations::{self, VALUE_SIZE};
 
 
 #[derive(Clone)]
 pub struct Instance {
     class_value:	Value,
-    fields:		HashMap<String, Value>,
+    fields:		SymbolMap<Value>,
+    // Fields whose names only exist at runtime, e.g. o["k" + i], are not interned
+    // so the interner does not grow with every key a script makes up.
+    // A name is in one of the two maps, never in both
+    named:		HashMap<Rc<str>, Value>,
+    read_only:	bool, // Fields can not be set by scripts, e.g. built-in namespaces
 }
 
 
@@ -21,7 +28,9 @@ impl Instance {
         }
         Instance {
             class_value:	class,
-            fields:		HashMap::new(),
+            fields:		SymbolMap::default(),
+            named:		HashMap::new(),
+            read_only:	false,
         }
     }
 }
@@ -38,7 +47,19 @@ impl Instance {
     }
 
     pub fn set(&mut self, field: &str, value: Value) {
-        self.fields.insert(field.to_string(), value);
+        match Symbol::find(field) {
+            Some(symbol) => self.set_by_symbol(symbol, value),
+            None => if self.named.insert(Rc::from(field), value).is_none() { allocations::grow(2 * VALUE_SIZE); },
+        }
+    }
+
+    pub fn set_by_symbol(&mut self, field: Symbol, value: Value) {
+        // The name may have been set at runtime before compiled code interned it
+        if !self.named.is_empty() && self.named.remove(&*field.name()).is_some() {
+            self.fields.insert(field, value);
+            return;
+        }
+        if self.fields.insert(field, value).is_none() { allocations::grow(2 * VALUE_SIZE); }
     }
 
 // Not sure we need this when get() can return None
@@ -47,7 +68,32 @@ impl Instance {
 //    }
     
     pub fn get(&self, field: &str) -> Option<&Value> {
-        return self.fields.get(field);
+        if let Some(value) = Symbol::find(field).and_then(|symbol| self.fields.get(&symbol)) {
+            return Some(value);
+        }
+        return self.named.get(field);
+    }
+
+    pub fn get_by_symbol(&self, field: Symbol) -> Option<&Value> {
+        if let Some(value) = self.fields.get(&field) { return Some(value); }
+        if self.named.is_empty() { return None; }
+        return self.named.get(&*field.name());
+    }
+
+    pub fn field_count(&self) -> usize {
+        return self.fields.len() + self.named.len();
+    }
+
+    pub fn field_values(&self) -> impl Iterator<Item = &Value> {
+        return self.fields.values().chain(self.named.values());
+    }
+
+    pub fn make_read_only(&mut self) {
+        self.read_only = true;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        return self.read_only;
     }
 }
 
@@ -59,6 +105,7 @@ impl std::fmt::Debug for Instance {
         f.debug_struct("Instance")
             .field("class", &self.class_name())
             .field("fields", &self.fields)
+            .field("named", &self.named)
             .finish()
     }
 }
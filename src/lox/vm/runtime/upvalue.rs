@@ -1,7 +1,7 @@
 
 
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 
 // This is the runtime representation of an upvalue.
 // Unlike local variables, each upvalue must be accessible 
@@ -44,6 +44,20 @@ impl<T: Clone + std::fmt::Display> Upvalue<T> {
             None	=> false,
         }
     }
+
+    // Look at the closed value without cloning it
+    pub fn value_ref(&self) -> Ref<'_, Option<T>> {
+        return self.value.borrow();
+    }
+
+    // All copies of an upvalue share the same cell; these identify it
+    pub fn cell_ptr(&self) -> *const RefCell<Option<T>> {
+        return Rc::as_ptr(&self.value);
+    }
+
+    pub fn cell_count(&self) -> usize {
+        return Rc::strong_count(&self.value);
+    }
     
 }
 
@@ -1,6 +1,6 @@
 
 
-use at::At;
+use crate::lox::common::{Location, Value};
 
 
 #[macro_export]
@@ -19,11 +19,22 @@ macro_rules! r_error {
 pub use r_error;
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuntimeErrorKind {
+    Error,              // Raised by the VM or thrown by the script
+    InstructionLimit,   // One of the Limits was exceeded, these can not be caught
+    Timeout,
+    MemoryLimit,
+}
+
+
+#[derive(Debug, Clone)]
 pub struct RuntimeError {
+    kind: RuntimeErrorKind,
     message: String,
-    at: Option<At>,
+    at: Option<Location>,
     stack_trace: Vec<String>,   
+    value: Option<Value>, // Set if the error was raised by 'throw'
 }
 
 
@@ -32,32 +43,83 @@ impl RuntimeError {
 
     pub fn new(message: String) -> Self {
         RuntimeError { 
+            kind: RuntimeErrorKind::Error,
             message,
             at: None,
             stack_trace: vec![],
+            value: None,
         }
     }
 
 
-    pub fn new_at(message: String, at: &At) -> Self {
+    pub fn new_at(message: String, at: &Location) -> Self {
         RuntimeError { 
+            kind: RuntimeErrorKind::Error,
             message,
             at: Some(at.clone()),
             stack_trace: vec![],
+            value: None,
+        }
+    }
+
+
+    // Used by the VM when a script throws a value
+    pub fn thrown(value: Value) -> Self {
+        let mut message = format!("Uncaught exception: {}", value);
+        if value.is_instance() {
+            if let Some(field) = value.as_instance().get("message") {
+                message = if field.is_string() { field.as_string().to_string() } else { field.to_string() };
+            }
+        }
+        RuntimeError { 
+            kind: RuntimeErrorKind::Error,
+            message,
+            at: None,
+            stack_trace: vec![],
+            value: Some(value),
         }
     }
 
 
+    // Used by the VM when one of the Limits is exceeded
+    pub fn limit(kind: RuntimeErrorKind, message: String) -> Self {
+        RuntimeError { 
+            kind,
+            message,
+            at: None,
+            stack_trace: vec![],
+            value: None,
+        }
+    }
+
+
+    pub fn get_kind(&self) -> RuntimeErrorKind {
+        return self.kind;
+    }
+
+
+    // Errors caused by exceeding the Limits can not be caught by the script
+    pub fn is_limit(&self) -> bool {
+        return self.kind != RuntimeErrorKind::Error;
+    }
+
+
     pub fn get_message(&self) -> &String {
         return &self.message;
     }
 
 
-    pub fn get_at(&self) -> Option<&At> {
+    pub fn get_at(&self) -> Option<&Location> {
         return self.at.as_ref();
     }
 
 
+    // Used by the VM if the error was created without a location
+    pub fn set_at(&mut self, at: &Location) {
+        self.at = Some(at.clone());
+    }
+
+
     pub fn get_stack_trace(&self) -> &Vec<String> {
         return &self.stack_trace;
     }
@@ -67,6 +129,17 @@ impl RuntimeError {
         self.stack_trace.extend(stack_trace.drain(..));
     }
 
+
+    pub fn get_value(&self) -> Option<&Value> {
+        return self.value.as_ref();
+    }
+
+
+    // Used by the VM to remember which value a caught error was handed over as
+    pub fn set_value(&mut self, value: Value) {
+        self.value = Some(value);
+    }
+
 }
 
 
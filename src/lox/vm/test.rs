@@ -6,18 +6,29 @@ use super::VM;
 
 
 mod arrays;
+mod bitwise;
 mod classes;
 mod closures;
+mod compound_assignment;
+mod embedding;
+mod exceptions;
 mod expressions;
 mod for_loops;
 mod functions;
 mod globals;
+mod heap;
 mod if_statement;
+mod lambdas;
+mod limits;
 mod literals;
 mod locals;
+mod maps;
 mod math;
+mod modules;
 mod numbers;
+mod repl;
 mod return_statement;
+mod stack_overflow;
 mod stack_trace;
 mod while_loops;
 
@@ -39,7 +50,7 @@ fn compile_and_execute(code: &str) -> Result<i32, RuntimeError> {
                 }
             }
         }
-        Err(error) => panic!("Compile failed: {}", error),
+        Err(errors) => panic!("Compile failed: {:?}", errors),
     }
 }
 
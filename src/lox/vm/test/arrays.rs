@@ -356,3 +356,27 @@ fn set_array_subscript_single_value_5() {
     assert_eq!(res.unwrap(), 1);
 }
 
+#[test]
+fn set_array_subscript_value() {
+    let code = "var a=[1,2,3]; var b = a[0] = 5; exit b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn set_array_subscript_local() {
+    let code = "{ var a=[1]; a[0]=2; var y=7; exit y; }";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn set_array_subscript_chained() {
+    let code = "var a=[0,0]; var b=[0]; var i=1; b[0] = a[i] = 7; exit a[1] + b[0];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 14);
+}
+
@@ -0,0 +1,118 @@
+use super::compile_and_execute;
+
+
+#[test]
+fn and() {
+    let code = "exit 5 & 3;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn or() {
+    let code = "exit 5 | 3;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn xor() {
+    let code = "exit 5 ^ 3;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 6);
+}
+
+#[test]
+fn not() {
+    let code = "exit ~5 == -6;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn shift_left() {
+    let code = "exit 1 << 4;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 16);
+}
+
+#[test]
+fn shift_right() {
+    let code = "exit 0xff >> 4;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 15);
+}
+
+#[test]
+fn integer_part() {
+    let code = "exit 7.9 & 3.5;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn precedence_shift_over_comparison() {
+    let code = "exit 1 << 2 == 4;"; // (1 << 2) == 4
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn precedence_shift_under_term() {
+    let code = "exit 1 << 1 + 1;"; // 1 << (1 + 1)
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 4);
+}
+
+#[test]
+fn precedence_and_over_xor_over_or() {
+    let code = "exit 1 | 6 ^ 3 & 2;"; // 1 | (6 ^ (3 & 2))
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn precedence_equality_over_and() {
+    let code = "var flags = 6; exit (flags & 4 == 4) == 0;"; // flags & (4 == 4) fails
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn flags() {
+    let code = "var flags = 0; flags = flags | 1 << 3; flags = flags | 1; exit (flags & 8) != 0 && flags == 9;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn not_a_number() {
+    let code = "exit 'a' & 1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn not_a_number_unary() {
+    let code = "exit ~true;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn shift_out_of_range() {
+    let code = "exit 1 << 64;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -1,5 +1,6 @@
 
 
+use crate::lox::common::Symbol;
 use super::compile_and_execute;
 
 
@@ -362,3 +363,128 @@ fn vm_class_instance_subscript() {
     assert_eq!(res.unwrap(), 123);
 }
 
+
+#[test]
+fn vm_class_instance_subscript_set() {
+    let code = "class c1 {} var i1=c1(); i1['f1']=123; exit i1.f1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 123);
+}
+
+#[test]
+fn vm_class_instance_subscript_not_identifier() {
+    let code = "class c1 {} var i1=c1(); i1['not a name']=123; exit i1['not a name'];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 123);
+}
+
+#[test]
+fn vm_class_instance_subscript_not_interned() {
+    let code = "class c1 {} var i1=c1(); var k='runtime_key_'; for (var i=0; i<3; i=i+1) { k=k+'x'; i1[k]=i; } exit i1[k];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+    assert_eq!(Symbol::find("runtime_key_xxx"), None);
+}
+
+#[test]
+fn vm_class_instance_unknown_field() {
+    let code = "class c1 {} var i1=c1(); i1.f1=123; exit i1.never_assigned_anywhere;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn vm_class_invoke_arguments() {
+    let code = "class c1 { m1(a, b) { return a - b; } } var i1=c1(); exit i1.m1(5, 3);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_class_invoke_field_shadows_method() {
+    let code = "class c1 { m1() { return 1; } } fun f1() { return 2; } var i1=c1(); i1.m1=f1; exit i1.m1();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_class_invoke_inherited() {
+    let code = "class c1 { m1() { return this.m2(); } m2() { return 1; } } class c2 of c1 { m2() { return 2; } } var i1=c2(); exit i1.m1();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_class_invoke_cache_per_class() {
+    // The same call site sees receivers of different classes
+    let code = "
+        class c1 { m1() { return 1; } }
+        class c2 { m1() { return 10; } }
+        var items = [c1(), c2(), c1(), c2()];
+        var total = 0;
+        for (var i = 0; i < 4; i = i + 1) { total = total + items[i].m1(); }
+        exit total;
+    ";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 22);
+}
+
+#[test]
+fn vm_class_invoke_cache_per_name() {
+    let code = "
+        class c1 { m1() { return 1; } m2() { return 10; } }
+        var i1 = c1();
+        var total = 0;
+        for (var i = 0; i < 3; i = i + 1) { total = total + i1.m1() + i1.m2(); }
+        exit total;
+    ";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 33);
+}
+
+#[test]
+fn vm_class_getproperty_cache_hit() {
+    // A field set after the method was cached still shadows it
+    let code = "
+        class c1 { m1() { return 1; } }
+        fun f1() { return 5; }
+        var i1 = c1();
+        var total = 0;
+        for (var i = 0; i < 3; i = i + 1) { if (i == 2) i1.m1 = f1; var m = i1.m1; total = total + m(); }
+        exit total;
+    ";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn vm_class_getproperty_cache_per_class() {
+    // The same property read sees receivers of different classes
+    let code = "
+        class c1 { m1() { return 1; } }
+        class c2 { m1() { return 10; } }
+        var items = [c1(), c2(), c1(), c2()];
+        var total = 0;
+        for (var i = 0; i < 4; i = i + 1) { var m = items[i].m1; total = total + m(); }
+        exit total;
+    ";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 22);
+}
+
+#[test]
+fn vm_class_invoke_missing_method() {
+    let code = "class c1 {} var i1=c1(); exit i1.m1();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
@@ -52,3 +52,34 @@ fn vm_closure_setupvalue_3() {
     assert_eq!(res.unwrap(), 468);
 }
 
+#[test]
+fn vm_closure_block_scope() {
+    let code = "var f; { var a = 1; fun g() { return a; } f = g; } { var b = 2; exit b + f(); }";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn vm_closure_loop_scope() {
+    let code = "var n = 0; var i = 0; while (i < 3) { var a = i; fun g() { return a; } n = n + g(); i = i + 1; } exit n;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn vm_closure_nested_block_slots() {
+    let code = "{ var a = 1; { var b = 2; fun g() { return b; } } var c = 3; exit a * 10 + c; }";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 13);
+}
+
+#[test]
+fn vm_closure_loop_break() {
+    let code = "var f; var i = 0; while (true) { var a = i; fun g() { return a; } f = g; i = i + 1; if (i == 3) break; } var b = 10; exit f() + b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 12);
+}
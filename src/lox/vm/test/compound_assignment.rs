@@ -0,0 +1,158 @@
+
+
+use super::compile_and_execute;
+
+
+// Compound assignment
+#[test]
+fn vm_compound_assignment_global() {
+    let code = "var a = 10; a += 5; a -= 3; a *= 2; a /= 4; exit a;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 6);
+}
+
+#[test]
+fn vm_compound_assignment_modulo() {
+    let code = "var a = 17; a %= 5; exit a;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_compound_assignment_local() {
+    let code = "fun f() { var a = 1; a += 41; return a; } exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 42);
+}
+
+#[test]
+fn vm_compound_assignment_upvalue() {
+    let code = "fun f() { var a = 1; fun g() { a += 2; } g(); g(); return a; } exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn vm_compound_assignment_value() {
+    let code = "var a = 1; var b = a += 2; exit a * 10 + b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 33);
+}
+
+#[test]
+fn vm_compound_assignment_property() {
+    let code = "class A {} var a = A(); a.x = 1; a.x += 2; exit a.x;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn vm_compound_assignment_this_property() {
+    let code = "class A { init() { this.n = 1; } add(v) { this.n += v; return this.n; } } exit A().add(4);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn vm_compound_assignment_subscript_evaluated_once() {
+    let code = "var calls = 0; fun i() { calls += 1; return 1; } var a = [1, 2, 3]; a[i()] += 10; exit a[1] * 10 + calls;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 121);
+}
+
+#[test]
+fn vm_compound_assignment_string() {
+    let code = "var s = 'ab'; s += 'cd'; exit s == 'abcd' ? 1 : 0;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+// Increment and decrement
+#[test]
+fn vm_increment_prefix_value() {
+    let code = "var a = 1; var b = ++a; exit a * 10 + b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 22);
+}
+
+#[test]
+fn vm_increment_postfix_value() {
+    let code = "var a = 1; var b = a++; exit a * 10 + b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 21);
+}
+
+#[test]
+fn vm_decrement_prefix_and_postfix() {
+    let code = "var a = 5; var b = a--; var c = --a; exit a * 100 + b * 10 + c;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 353);
+}
+
+#[test]
+fn vm_increment_in_for_loop() {
+    let code = "var n = 0; for (var i = 0; i < 5; i++) { n += i; } exit n;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 10);
+}
+
+#[test]
+fn vm_increment_upvalue() {
+    let code = "fun counter() { var n = 0; return fun () { return ++n; }; } var c = counter(); c(); exit c();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_increment_property() {
+    let code = "class A {} var a = A(); a.x = 1; var b = a.x++; var c = ++a.x; exit a.x * 100 + b * 10 + c;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 313);
+}
+
+#[test]
+fn vm_increment_subscript() {
+    let code = "var a = [[1, 2], [3, 4]]; var b = a[1][0]++; --a[0][1]; exit a[1][0] * 100 + b * 10 + a[0][1];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 431);
+}
+
+#[test]
+fn vm_increment_property_keeps_value() {
+    let code = "class A {} var a = A(); a.x = 0.1; var b = a.x++; exit b == 0.1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn vm_decrement_subscript_keeps_value() {
+    let code = "var a = [0.1]; var b = a[0]--; exit b == 0.1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn vm_increment_in_expression() {
+    let code = "var a = 1; var b = 10 + a++ * 2; exit a * 100 + b;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 212);
+}
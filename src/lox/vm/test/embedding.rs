@@ -0,0 +1,117 @@
+
+
+use super::RuntimeError;
+use crate::lox::Compiler;
+use crate::lox::common::Value;
+use super::VM;
+
+
+fn load(vm: &mut VM, code: &str) {
+    let builder = Compiler::new();
+    let reader = std::io::Cursor::new(code);
+    let bytecode = match builder.compile("test", reader) {
+        Ok(bytecode) => bytecode,
+        Err(errors) => panic!("Compile failed: {:?}", errors),
+    };
+    if let Err(error) = vm.execute(&bytecode) {
+        panic!("Execute failed: {}", error);
+    }
+}
+
+
+fn call_global(vm: &mut VM, name: &str, args: &[Value]) -> Result<Value, RuntimeError> {
+    let callee = vm.get_global(name).expect("global not defined");
+    return vm.call(&callee, args);
+}
+
+
+#[test]
+fn vm_embed_call_function() {
+    let mut vm = VM::new();
+    load(&mut vm, "fun add(a, b) { return a + b; }");
+    let res = call_global(&mut vm, "add", &[Value::number(2.0), Value::number(3.0)]);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), Value::number(5.0));
+}
+
+#[test]
+fn vm_embed_call_closure() {
+    let code = "fun counter() { var n = 0; fun inc() { n = n + 1; return n; } return inc; } var c = counter();";
+    let mut vm = VM::new();
+    load(&mut vm, code);
+    assert_eq!(call_global(&mut vm, "c", &[]).unwrap(), Value::number(1.0));
+    assert_eq!(call_global(&mut vm, "c", &[]).unwrap(), Value::number(2.0));
+}
+
+#[test]
+fn vm_embed_call_wrong_arity() {
+    let mut vm = VM::new();
+    load(&mut vm, "fun f(a) { return a; }");
+    let res = call_global(&mut vm, "f", &[]);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn vm_embed_call_error_recovers() {
+    let mut vm = VM::new();
+    load(&mut vm, "fun bad() { return 0.invalid; } fun good() { return 1; }");
+    let res = call_global(&mut vm, "bad", &[]);
+    assert_eq!(res.is_err(), true);
+    let res = call_global(&mut vm, "good", &[]);
+    assert_eq!(res.unwrap(), Value::number(1.0));
+}
+
+#[test]
+fn vm_embed_call_class() {
+    let mut vm = VM::new();
+    load(&mut vm, "class C { init(x) { this.x = x; } }");
+    let instance = call_global(&mut vm, "C", &[Value::number(7.0)]).unwrap();
+    assert_eq!(instance.is_instance(), true);
+    let res = vm.invoke(&instance, "x", &[]);
+    assert_eq!(res.is_err(), true); // A number is not callable
+}
+
+#[test]
+fn vm_embed_invoke_method() {
+    let code = "class C { init() { this.n = 10; } add(x) { this.n = this.n + x; return this.n; } } var i = C();";
+    let mut vm = VM::new();
+    load(&mut vm, code);
+    let instance = vm.get_global("i").unwrap();
+    let res = vm.invoke(&instance, "add", &[Value::number(5.0)]);
+    assert_eq!(res.unwrap(), Value::number(15.0));
+}
+
+#[test]
+fn vm_embed_invoke_missing_method() {
+    let mut vm = VM::new();
+    load(&mut vm, "class C {} var i = C();");
+    let instance = vm.get_global("i").unwrap();
+    let res = vm.invoke(&instance, "nothing", &[]);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn vm_embed_get_global() {
+    let mut vm = VM::new();
+    load(&mut vm, "var a = 42;");
+    assert_eq!(vm.get_global("a"), Some(Value::number(42.0)));
+    assert_eq!(vm.get_global("c"), None);
+}
+
+#[test]
+fn vm_embed_set_global() {
+    let mut vm = VM::new();
+    load(&mut vm, "var a = 1; fun get() { return a; }");
+    assert_eq!(vm.set_global("a", Value::number(2.0)).is_ok(), true);
+    assert_eq!(call_global(&mut vm, "get", &[]).unwrap(), Value::number(2.0));
+    assert_eq!(vm.set_global("new", Value::Bool(true)).is_ok(), true);
+    assert_eq!(vm.get_global("new"), Some(Value::Bool(true)));
+}
+
+#[test]
+fn vm_embed_set_global_const() {
+    let mut vm = VM::new();
+    load(&mut vm, "const a = 1;");
+    assert_eq!(vm.set_global("a", Value::number(2.0)).is_err(), true);
+    assert_eq!(vm.get_global("a"), Some(Value::number(1.0)));
+}
@@ -0,0 +1,188 @@
+
+
+use super::compile_and_execute;
+
+
+#[test]
+fn catch_thrown_number() {
+    let code = "var r = 0; try { throw 5; } catch (e) { r = e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn catch_without_variable() {
+    let code = "var r = 0; try { throw 5; } catch { r = 1; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn catch_nothing_thrown() {
+    let code = "var r = 1; try { r = r + 1; } catch { r = 0; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn catch_runtime_error() {
+    let code = "var r = false; try { var a = null; a[0]; } catch (e) { r = e.message != null && e.at != null && e.stack[0] != null; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn catch_across_calls() {
+    let code = "fun f(n) { if (n == 0) throw 7; return f(n - 1); } var r = 0; try { f(5); } catch (e) { r = e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn catch_restores_stack() {
+    let code = "fun f() { var a = 1; var b = 2; throw a + b; } fun g() { var x = 1; try { var y = 2; f(); } catch (e) { x = x + e; } var z = 10; return x + z; } exit g();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 14);
+}
+
+#[test]
+fn catch_closes_upvalues() {
+    let code = "var f; try { var a = 5; fun g() { return a; } f = g; throw 1; } catch { } exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn finally_without_error() {
+    let code = "var r = 0; try { r = 1; } finally { r = r + 10; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 11);
+}
+
+#[test]
+fn finally_after_catch() {
+    let code = "var r = 0; try { throw 1; } catch (e) { r = e; } finally { r = r + 10; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 11);
+}
+
+#[test]
+fn finally_rethrows() {
+    let code = "var r = 0; try { try { throw 2; } finally { r = 10; } } catch (e) { r = r + e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 12);
+}
+
+#[test]
+fn finally_after_throw_in_catch() {
+    let code = "var r = 0; try { try { throw 1; } catch (e) { throw e + 1; } finally { r = 10; } } catch (e) { r = r + e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 12);
+}
+
+#[test]
+fn break_inside_try() {
+    let code = "var r = 0; while (true) { try { r = r + 1; if (r == 3) break; } catch { } } try { throw 1; } catch (e) { r = r + e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 4);
+}
+
+#[test]
+fn continue_inside_catch() {
+    let code = "var r = 0; for (var i = 0; i < 3; i = i + 1) { try { throw i; } catch (e) { r = r + e; continue; } } try { throw 10; } catch (e) { r = r + e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 13);
+}
+
+#[test]
+fn return_runs_finally() {
+    let code = "var r = 0; fun f() { try { return 1; } finally { r = 10; } } var v = f(); exit r + v;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 11);
+}
+
+#[test]
+fn return_from_catch_runs_finally() {
+    let code = "var r = 0; fun f() { var a = 5; try { throw 1; } catch (e) { var b = 2; return a + b + e; } finally { a = 100; r = a; } } exit f() + r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 108);
+}
+
+#[test]
+fn return_runs_nested_finally() {
+    let code = "var r = 0; fun f() { try { try { return 1; } finally { r = r + 10; } } finally { r = r + 100; } } exit f() + r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 111);
+}
+
+#[test]
+fn break_runs_finally() {
+    let code = "var r = 0; while (true) { try { break; } finally { r = r + 1; } } try { throw 10; } catch (e) { r = r + e; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 11);
+}
+
+#[test]
+fn continue_runs_finally() {
+    let code = "var r = 0; for (var i = 0; i < 3; i = i + 1) { try { if (i == 1) continue; r = r + 10; } finally { r = r + 1; } } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 23);
+}
+
+#[test]
+fn break_inside_finally_loop() {
+    let code = "var r = 0; try { while (true) { break; } r = 1; } finally { r = r + 10; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 11);
+}
+
+#[test]
+fn uncaught_throw() {
+    let code = "throw 42;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "Uncaught exception: 42");
+}
+
+#[test]
+fn uncaught_instance() {
+    let code = "class Oops {} var o = Oops(); o.message = 'oops'; throw o;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "oops");
+}
+
+#[test]
+fn error_inside_catch() {
+    let code = "try { throw 1; } catch (e) { null[0]; }";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "Can't subscript into value 'null'");
+}
+
+#[test]
+fn rethrow_runtime_error() {
+    let code = "try { null[0]; } catch (e) { throw e; }";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "Can't subscript into value 'null'");
+}
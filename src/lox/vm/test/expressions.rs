@@ -399,3 +399,77 @@ fn vm_greater_or_equal_4() {
     assert_eq!(res.is_ok(), true);
     assert_eq!(res.unwrap(), 1);
 }
+
+
+// ternary ?:
+#[test]
+fn vm_ternary_true() {
+    let code = "exit true ? 1 : 2;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn vm_ternary_false() {
+    let code = "exit false ? 1 : 2;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_ternary_null_is_falsey() {
+    let code = "exit null ? 1 : 2;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_ternary_right_associative() {
+    let code = "var a = 2; exit a == 1 ? 10 : a == 2 ? 20 : 30;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 20);
+}
+
+#[test]
+fn vm_ternary_nested_in_then() {
+    let code = "exit true ? false ? 1 : 2 : 3;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_ternary_lower_than_or() {
+    let code = "exit false || true ? 1 : 2;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn vm_ternary_higher_than_assignment() {
+    let code = "var a; a = false ? 1 : 2; exit a;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_ternary_short_circuit() {
+    let code = "var a = 0; fun f() { a = a + 1; return a; } true ? f() : f(); false ? f() : f(); exit a;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_ternary_stack_balanced() {
+    let code = "var s = 0; for var i in (1, 2, 3, 4) { s = s + (i % 2 == 0 ? i : 0); } exit s;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 6);
+}
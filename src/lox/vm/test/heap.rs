@@ -0,0 +1,65 @@
+
+
+use super::RuntimeError;
+use crate::lox::Compiler;
+use super::VM;
+
+
+// Keep the VM around so the heap can be inspected afterwards
+fn execute_using(vm: &mut VM, code: &str) -> Result<i32, RuntimeError> {
+    let builder = Compiler::new();
+    let reader = std::io::Cursor::new(code);
+    match builder.compile("test", reader) {
+        Ok(bytecode) => return vm.execute(&bytecode),
+        Err(errors) => panic!("Compile failed: {:?}", errors),
+    }
+}
+
+
+#[test]
+fn vm_heap_collect_nothing() {
+    let mut vm = VM::new();
+    let res = execute_using(&mut vm, "var a = [1, 2, 3];");
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(vm.collect(), 0);
+}
+
+#[test]
+fn vm_heap_instance_cycle() {
+    let code = "class C {} var c = C(); c.me = c; c = null;";
+    let mut vm = VM::new();
+    let res = execute_using(&mut vm, code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(vm.collect(), 1);
+    assert_eq!(vm.heap_stats().freed, 1);
+}
+
+#[test]
+fn vm_heap_reachable_cycle() {
+    let code = "class C {} var c = C(); c.me = c;";
+    let mut vm = VM::new();
+    let res = execute_using(&mut vm, code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(vm.collect(), 0);
+}
+
+#[test]
+fn vm_heap_array_cycle() {
+    let code = "var a = [null]; var b = [a]; a[0] = b; a = null; b = null;";
+    let mut vm = VM::new();
+    let res = execute_using(&mut vm, code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(vm.collect(), 2);
+}
+
+#[test]
+fn vm_heap_stats() {
+    let mut vm = VM::new();
+    let res = execute_using(&mut vm, "var a = [[1], [2]];");
+    assert_eq!(res.is_ok(), true);
+    vm.collect();
+    let stats = vm.heap_stats();
+    assert_eq!(stats.collections, 1);
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.objects > 0, true);
+}
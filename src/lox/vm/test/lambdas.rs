@@ -0,0 +1,77 @@
+
+
+use super::compile_and_execute;
+
+
+// Anonymous functions
+#[test]
+fn vm_lambda_call() {
+    let code = "var add = fun (a, b) { return a + b; }; exit add(2, 3);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn vm_lambda_call_immediately() {
+    let code = "exit fun (a) { return a * 3; }(4);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 12);
+}
+
+#[test]
+fn vm_lambda_as_argument() {
+    let code = "fun apply(f, v) { return f(v); } exit apply(fun (x) { return x + 1; }, 41);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 42);
+}
+
+#[test]
+fn vm_lambda_captures_upvalue() {
+    let code = "fun counter() { var n = 0; return fun () { n = n + 1; return n; }; } var c = counter(); c(); exit c();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn vm_arrow_expression_body() {
+    let code = "var double = (x) => x * 2; exit double(21);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 42);
+}
+
+#[test]
+fn vm_arrow_block_body() {
+    let code = "var f = (a, b) => { var c = a - b; return c; }; exit f(10, 3);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn vm_arrow_no_params() {
+    let code = "var f = () => 9; exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 9);
+}
+
+#[test]
+fn vm_arrow_captures_upvalue() {
+    let code = "fun adder(n) { return (x) => x + n; } var add5 = adder(5); exit add5(2);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn vm_arrow_nested() {
+    let code = "var add = (a) => (b) => a + b; exit add(1)(2);";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+
+use crate::lox::{Compiler, RuntimeError};
+use crate::lox::common::Value;
+use crate::lox::vm::{Limits, RuntimeErrorKind, VM};
+
+
+fn execute_with_limits(vm: &mut VM, code: &str, limits: Limits) -> Result<i32, RuntimeError> {
+    let bytecode = Compiler::new().compile("test", std::io::Cursor::new(code)).expect("Compile failed");
+    vm.set_limits(limits);
+    return vm.execute(&bytecode);
+}
+
+
+fn expect_limit(code: &str, limits: Limits) -> RuntimeErrorKind {
+    match execute_with_limits(&mut VM::new(), code, limits) {
+        Ok(rc) => panic!("Unexpectedly returned rc={}", rc),
+        Err(error) => {
+            assert!(error.is_limit());
+            return error.get_kind();
+        }
+    }
+}
+
+
+#[test]
+fn instruction_limit() {
+    let code = "while (true) {}";
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::InstructionLimit);
+}
+
+#[test]
+fn within_instruction_limit() {
+    let code = "var a = 0; while (a < 10) a = a + 1; exit a;";
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    assert_eq!(execute_with_limits(&mut VM::new(), code, limits).unwrap(), 10);
+}
+
+#[test]
+fn timeout() {
+    let code = "while (true) {}";
+    let limits = Limits { timeout: Some(Duration::from_millis(50)), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::Timeout);
+}
+
+#[test]
+fn memory_limit() {
+    let code = "var a = []; while (true) a = [a, a];";
+    let limits = Limits { max_bytes: Some(1000000), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::MemoryLimit);
+}
+
+#[test]
+fn memory_limit_doubling_string() {
+    // Only two strings are live at a time, but their size doubles every iteration
+    let code = "var s = \"x\"; while (true) s = s + s;";
+    let limits = Limits { max_bytes: Some(1000000), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::MemoryLimit);
+}
+
+#[test]
+fn memory_limit_growing_map() {
+    let code = "var m = {}; var i = 0; while (true) { m[i] = i; i = i + 1; }";
+    let limits = Limits { max_bytes: Some(1000000), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::MemoryLimit);
+}
+
+#[test]
+fn garbage_within_memory_limit() {
+    let code = "for (var i = 0; i < 10000; i = i + 1) { var a = [i]; } exit 1;";
+    let limits = Limits { max_bytes: Some(100000), ..Limits::default() };
+    assert_eq!(execute_with_limits(&mut VM::new(), code, limits).unwrap(), 1);
+}
+
+#[test]
+fn limit_not_catchable() {
+    let code = "try { while (true) {} } catch { exit 1; } exit 2;";
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    assert_eq!(expect_limit(code, limits), RuntimeErrorKind::InstructionLimit);
+}
+
+#[test]
+fn other_errors_not_limits() {
+    let code = "0.invalid;";
+    let error = execute_with_limits(&mut VM::new(), code, Limits::default()).expect_err("Expected an error");
+    assert!(!error.is_limit());
+    assert_eq!(error.get_kind(), RuntimeErrorKind::Error);
+}
+
+#[test]
+fn reuse_after_limit() {
+    let mut vm = VM::new();
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    let code = "var a = 1; fun f() { var b = a; while (true) { fun g() { return b; } } } f();";
+    assert!(execute_with_limits(&mut vm, code, limits).is_err());
+    let code = "var a = 0; while (a < 10) a = a + 1; exit a;";
+    assert_eq!(execute_with_limits(&mut vm, code, limits).unwrap(), 10);
+}
+
+#[test]
+fn call_after_limit() {
+    let mut vm = VM::new();
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    let code = "fun spin() { while (true) {} } fun one() { return 1; } exit 0;";
+    assert_eq!(execute_with_limits(&mut vm, code, limits).unwrap(), 0);
+    let spin = vm.get_global("spin").unwrap();
+    let error = vm.call(&spin, &[]).expect_err("Expected a limit error");
+    assert_eq!(error.get_kind(), RuntimeErrorKind::InstructionLimit);
+    let one = vm.get_global("one").unwrap();
+    assert_eq!(vm.call(&one, &[]).unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn call_after_execute() {
+    let mut vm = VM::new();
+    let limits = Limits { max_instructions: Some(10000), ..Limits::default() };
+    let code = "fun count(n) { var i = 0; while (i < n) i = i + 1; return i; } exit 0;";
+    assert_eq!(execute_with_limits(&mut vm, code, limits).unwrap(), 0);
+    let count = vm.get_global("count").unwrap();
+    for _ in 0..10 {
+        assert_eq!(vm.call(&count, &[Value::Number(1000.0)]).unwrap(), Value::Number(1000.0));
+    }
+}
+
+#[test]
+fn call_closure_after_exit() {
+    let mut vm = VM::new();
+    let code = "var get; { var a = 5; fun g() { return a; } get = g; exit 0; }";
+    assert_eq!(execute_with_limits(&mut vm, code, Limits::default()).unwrap(), 0);
+    let get = vm.get_global("get").unwrap();
+    assert_eq!(vm.call(&get, &[]).unwrap(), Value::Number(5.0));
+}
+
+#[test]
+fn memory_counted_per_vm() {
+    // The first VM keeps well over 100000 bytes alive, which must not count against the second
+    let mut big = VM::new();
+    let code = "var keep = null; for (var i = 0; i < 10000; i = i + 1) keep = [keep, i]; exit 1;";
+    assert_eq!(execute_with_limits(&mut big, code, Limits::default()).unwrap(), 1);
+    let mut small = VM::new();
+    let limits = Limits { max_bytes: Some(100000), ..Limits::default() };
+    let code = "var a = [1, 2, 3]; exit 2;";
+    assert_eq!(execute_with_limits(&mut small, code, limits).unwrap(), 2);
+    let code = "var keep = null; for (var i = 0; i < 10000; i = i + 1) keep = [keep, i]; exit 3;";
+    assert_eq!(execute_with_limits(&mut big, code, Limits::default()).unwrap(), 3);
+}
@@ -0,0 +1,134 @@
+use super::compile_and_execute;
+
+
+#[test]
+fn empty_equal() {
+    let code = "exit {} == {};";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn literal_equal() {
+    let code = "exit {'a': 1, 2: 'b'} == {2: 'b', 'a': 1};"; // Order does not matter
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn trailing_comma() {
+    let code = "exit {'a': 1, 'b': 2,} == {'a': 1, 'b': 2};";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn literal_is_not_same() {
+    let code = "exit {'a': 1} is not {'a': 1};";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn get_subscript() {
+    let code = "var m = {'a': 1, 2: 'b', true: 3, null: 4}; exit m['a'] + m[true] + m[null];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 8);
+}
+
+#[test]
+fn get_subscript_expression_keys() {
+    let code = "var k = 'a'; var m = {k + 'b': 1 + 2}; exit m['ab'];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn get_subscript_missing() {
+    let code = "var m = {'a': 1}; exit m['b'];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn set_subscript() {
+    let code = "var m = {}; m['a'] = 1; m[2] = 2; m['a'] = 3; exit m['a'] + m[2];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 5);
+}
+
+#[test]
+fn set_subscript_local() {
+    let code = "fun f() { var m = {}; m['a'] = 4; return m['a']; } exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 4);
+}
+
+#[test]
+fn bad_key_in_literal() {
+    let code = "var m = {[]: 1};";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn bad_key_in_subscript() {
+    let code = "var m = {}; m[nan] = 1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn nested() {
+    let code = "var m = {'a': {'b': [1, 2, 3]}}; exit m['a']['b'][2];";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn for_in_map_yields_keys() {
+    let code = "var m = {1: 'a', 2: 'b', 4: 'c'}; var sum = 0; for var k in m { sum = sum + k; } exit sum;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[test]
+fn for_in_map_literal() {
+    let code = "var s = ''; for var k in {'a': 1, 'b': 2} { s = s + k; } exit s == 'ab';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn map_as_own_key() {
+    let code = "var m = {}; m[m] = 1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn set_map_subscript_keeps_locals() {
+    let code = "fun f() { var x = 3; var m = {}; m['a'] = 1; m['b'] = 2; return x; } exit f();";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn for_in_map_key_is_copy() {
+    let code = "var m = {'ab': 1}; for var k in m { k[0] = 'x'; } var s = ''; for var k in m { s = s + k; } exit s == 'ab';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
@@ -0,0 +1,116 @@
+
+
+use super::compile_and_execute;
+
+
+#[test]
+fn import_call_function() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; exit g.greet('world') == 'Hello, world';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_constant() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; exit g.greeting == 'Hello';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_class() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; var x = g.Greeter(); exit x.hello() == 'Hello, class';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_same_global_name() {
+    let code = "var greeting = 'Hi'; var counter = 10; import 'src/lox/vm/test/testlib/greeter.lox' as g; exit greeting == 'Hi' && g.greeting == 'Hello' && counter == 10;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_module_globals() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; g.bump(); g.bump(); exit g.counter;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
+
+#[test]
+fn import_runs_once() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; g.bump(); import 'src/lox/vm/test/testlib/greeter.lox' as h; exit h.counter;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_same_module() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; import 'src/lox/vm/test/testlib/greeter.lox' as h; exit g is h;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_nested() {
+    let code = "import 'src/lox/vm/test/testlib/nested.lox' as n; exit n.hello() == 'Hello, nested';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_in_function() {
+    let code = "fun f() { import 'src/lox/vm/test/testlib/greeter.lox' as g; return g.greet('f'); } exit f() == 'Hello, f';";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_not_exported() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; exit g.secret;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "Module 'src/lox/vm/test/testlib/greeter.lox' does not export 'secret'");
+}
+
+#[test]
+fn import_failing_module() {
+    let code = "import 'src/lox/vm/test/testlib/failing.lox' as f;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_err(), true);
+    assert_eq!(res.unwrap_err().get_message(), "Can't subscript into value 'null'");
+}
+
+#[test]
+fn import_catch_failing_module() {
+    let code = "var r = 0; try { import 'src/lox/vm/test/testlib/failing.lox' as f; } catch { r = 1; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_same_module_other_path() {
+    let code = "import 'src/lox/vm/test/testlib/greeter.lox' as g; import './src/lox/vm/test/../test/testlib/greeter.lox' as h; g.bump(); exit g is h && h.counter == 1;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[test]
+fn import_failing_module_again() {
+    let code = "var r = 0; try { import 'src/lox/vm/test/testlib/failing.lox' as f; } catch { r = r + 1; } try { import 'src/lox/vm/test/testlib/failing.lox' as f; } catch { r = r + 1; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 2);
+}
@@ -0,0 +1,74 @@
+
+
+use crate::lox::Compiler;
+use crate::lox::common::Value;
+use super::VM;
+
+
+// Compile and run one entry the way the REPL does
+fn enter(compiler: &mut Compiler<std::io::Cursor<String>>, vm: &mut VM, code: &str) -> Result<i32, String> {
+    let reader = std::io::Cursor::new(code.to_string());
+    match compiler.compile_incremental("INPUT", reader) {
+        Ok(bytecode) => vm.execute_incremental(&bytecode).map_err(|error| error.get_message().to_string()),
+        Err(errors) => Err(format!("{:?}", errors)),
+    }
+}
+
+
+#[test]
+fn vm_repl_keeps_globals() {
+    let mut compiler = Compiler::new();
+    let mut vm = VM::new();
+    assert_eq!(enter(&mut compiler, &mut vm, "var a = 1;"), Ok(0));
+    assert_eq!(enter(&mut compiler, &mut vm, "var b = a + 1;"), Ok(0));
+    assert_eq!(enter(&mut compiler, &mut vm, "a = a + b;"), Ok(0));
+    assert_eq!(vm.get_global("a"), Some(Value::number(3.0)));
+    assert_eq!(enter(&mut compiler, &mut vm, "exit a;"), Ok(3));
+}
+
+#[test]
+fn vm_repl_keeps_functions() {
+    let mut compiler = Compiler::new();
+    let mut vm = VM::new();
+    assert_eq!(enter(&mut compiler, &mut vm, "fun twice(n) {\n  return n * 2;\n}"), Ok(0));
+    assert_eq!(enter(&mut compiler, &mut vm, "exit twice(21);"), Ok(42));
+}
+
+#[test]
+fn vm_repl_echo_expression() {
+    let mut compiler = Compiler::new();
+    let mut vm = VM::new();
+    assert_eq!(enter(&mut compiler, &mut vm, "1 + 2"), Ok(0));
+    assert_eq!(enter(&mut compiler, &mut vm, "var a = 1; a;"), Ok(0));
+}
+
+#[test]
+fn vm_repl_compile_error_keeps_nothing() {
+    let mut compiler = Compiler::new();
+    let mut vm = VM::new();
+    assert_eq!(enter(&mut compiler, &mut vm, "var a = 1; var b = ;").is_err(), true);
+    assert_eq!(enter(&mut compiler, &mut vm, "var a = 2;"), Ok(0));
+    assert_eq!(vm.get_global("a"), Some(Value::number(2.0)));
+}
+
+#[test]
+fn vm_repl_runtime_error_keeps_declared() {
+    let mut compiler = Compiler::new();
+    let mut vm = VM::new();
+    assert_eq!(enter(&mut compiler, &mut vm, "var a = 1; var b = a.invalid;").is_err(), true);
+    assert_eq!(enter(&mut compiler, &mut vm, "exit a;"), Ok(1));
+    assert_eq!(enter(&mut compiler, &mut vm, "b;"), Ok(0));
+}
+
+#[test]
+fn vm_repl_is_complete() {
+    let compiler: Compiler<std::io::Cursor<String>> = Compiler::new();
+    assert_eq!(compiler.is_complete("var a = 1;\n"), true);
+    assert_eq!(compiler.is_complete("fun f() {\n"), false);
+    assert_eq!(compiler.is_complete("fun f() {\n  return [1,\n"), false);
+    assert_eq!(compiler.is_complete("fun f() {\n  return [1,\n2];\n}\n"), true);
+    assert_eq!(compiler.is_complete("var s = \"{\";\n"), true);
+    assert_eq!(compiler.is_complete("// {\n"), true);
+    assert_eq!(compiler.is_complete("var s = \"abc\n"), false);
+    assert_eq!(compiler.is_complete("}\n"), true); // Let the compiler complain
+}
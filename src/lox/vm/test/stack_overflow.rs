@@ -0,0 +1,67 @@
+use crate::lox::{Compiler, RuntimeError, VM};
+
+
+use super::compile_and_execute;
+
+
+fn execute_with_limits(code: &str, max_call_depth: usize, max_stack_size: usize) -> Result<i32, RuntimeError> {
+    let bytecode = Compiler::new().compile("test", std::io::Cursor::new(code)).expect("Compile failed");
+    let mut vm = VM::new();
+    vm.set_max_call_depth(max_call_depth);
+    vm.set_max_stack_size(max_stack_size);
+    return vm.execute(&bytecode);
+}
+
+
+#[test]
+fn runaway_recursion() {
+    let code = "fun f() { f(); } f();";
+    let error = compile_and_execute(code).expect_err("Expected a stack overflow");
+    assert_eq!(error.get_message(), "Stack overflow");
+}
+
+#[test]
+fn truncated_stack_trace() {
+    let code = "fun f() { f(); } f();";
+    let error = compile_and_execute(code).expect_err("Expected a stack overflow");
+    let trace = error.get_stack_trace();
+    assert_eq!(trace.len(), 21);
+    assert!(trace[0].starts_with("__main__:"));
+    assert!(trace[9].starts_with("f:"));
+    assert_eq!(trace[10], "... 1004 frame(s) omitted ...");
+    assert!(trace[20].starts_with("f:"));
+}
+
+#[test]
+fn recursion_within_limit() {
+    let code = "fun f(n) { if (n == 0) return 0; return f(n - 1) + 1; } exit f(500);";
+    assert_eq!(compile_and_execute(code).unwrap(), 500);
+}
+
+#[test]
+fn max_call_depth() {
+    let code = "fun f(n) { if (n == 0) return 0; return f(n - 1) + 1; } exit f(8);";
+    assert_eq!(execute_with_limits(code, 10, 1000).unwrap(), 8);
+    let code = "fun f(n) { if (n == 0) return 0; return f(n - 1) + 1; } exit f(9);";
+    let error = execute_with_limits(code, 10, 1000).expect_err("Expected a stack overflow");
+    assert_eq!(error.get_message(), "Stack overflow");
+}
+
+#[test]
+fn max_stack_size() {
+    let code = "fun f(n) { var a = n; var b = n; var c = n; if (n == 0) return 0; return f(n - 1) + 1; } exit f(100);";
+    let error = execute_with_limits(code, 1000, 100).expect_err("Expected a stack overflow");
+    assert_eq!(error.get_message(), "Stack overflow");
+}
+
+#[test]
+fn catch_stack_overflow() {
+    let code = "fun f() { f(); } var r = 0; try { f(); } catch (e) { if (e.message == \"Stack overflow\") r = 1; } exit r;";
+    assert_eq!(compile_and_execute(code).unwrap(), 1);
+}
+
+#[test]
+fn recover_after_stack_overflow() {
+    let code = "fun f() { f(); } fun g(n) { if (n == 0) return 0; return g(n - 1) + 1; } try { f(); } catch {} exit g(100);";
+    assert_eq!(compile_and_execute(code).unwrap(), 100);
+}
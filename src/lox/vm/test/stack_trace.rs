@@ -20,7 +20,7 @@ fn main() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000004 at line 1 char 1 of test",
+        "__main__:0x00000004 at line 1 char 2 of test",
     ]);
 }
 
@@ -30,7 +30,7 @@ fn anon_scope() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000004 at line 1 char 1 of test",
+        "__main__:0x00000004 at line 1 char 4 of test",
     ]);
 }
 
@@ -40,7 +40,7 @@ fn nested_anon_scopes() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000004 at line 1 char 1 of test",
+        "__main__:0x00000004 at line 1 char 6 of test",
     ]);
 }
 
@@ -50,8 +50,8 @@ fn function() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000008 at line 1 char 1 of test",
-        "f1:0x00000004 at line 1 char 5 of test",
+        "__main__:0x00000008 at line 1 char 27 of test",
+        "f1:0x00000004 at line 1 char 13 of test",
     ]);
 }
 
@@ -61,9 +61,9 @@ fn nested_functions() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000008 at line 1 char 1 of test",
-        "f1:0x00000006 at line 1 char 5 of test",
-        "f2:0x00000004 at line 1 char 16 of test",
+        "__main__:0x00000008 at line 1 char 46 of test",
+        "f1:0x00000006 at line 1 char 38 of test",
+        "f2:0x00000004 at line 1 char 24 of test",
     ]);
 }
 
@@ -73,8 +73,8 @@ fn returned_function() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x0000000e at line 1 char 1 of test",
-        "f2:0x00000004 at line 1 char 16 of test",
+        "__main__:0x0000000e at line 1 char 64 of test",
+        "f2:0x00000004 at line 1 char 24 of test",
     ]);
 }
 
@@ -84,8 +84,8 @@ fn class_initializer() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x0000000f at line 1 char 1 of test",
-        "init:0x00000004 at line 1 char 12 of test",
+        "__main__:0x0000000f at line 1 char 45 of test",
+        "init:0x00000004 at line 1 char 22 of test",
     ]);
 }
 
@@ -95,8 +95,28 @@ fn class_method() {
     let error = generate_error(code);
     let trace = error.get_stack_trace();
     assert_eq!(trace.as_ref(), vec![
-        "__main__:0x00000017 at line 1 char 1 of test",
-        "m1:0x00000004 at line 1 char 12 of test",
+        "__main__:0x00000017 at line 1 char 52 of test",
+        "m1:0x00000004 at line 1 char 20 of test",
     ]);
 }
 
+
+#[test]
+fn multiple_lines() {
+    let code = "fun f1() {\n  var a = 1;\n  a.invalid;\n}\nf1();";
+    let error = generate_error(code);
+    let trace = error.get_stack_trace();
+    assert_eq!(trace.as_ref(), vec![
+        "__main__:0x00000008 at line 5 char 3 of test",
+        "f1:0x00000006 at line 3 char 4 of test",
+    ]);
+}
+
+#[test]
+fn error_at() {
+    let code = "var a = 1;\n\na.invalid;";
+    let error = generate_error(code);
+    let at = error.get_at().expect("RuntimeError has no location");
+    assert_eq!(at.lineno(), 3);
+    assert_eq!(at.charno(), 2);
+}
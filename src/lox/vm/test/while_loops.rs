@@ -52,6 +52,46 @@ fn vm_while_nested_break() {
     assert_eq!(res.unwrap(), 1);
 }
 
+#[test]
+fn vm_while_break_with_locals() {
+    let code = "var n=0; while (n<5) { var a=n; n=n+1; if (a==2) break; } exit n;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn vm_while_continue_with_locals() {
+    let code = "var n=0; var s=0; while (n<5) { var a=n; n=n+1; if (a==2) continue; s=s+a; } exit s;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 8);
+}
+
+#[test]
+fn vm_while_locals_after_break() {
+    let code = "var r=0; var i=0; while (i<3) { var a=10; i=i+1; if (i==3) break; var b=5; r=r+a+b; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 30);
+}
+
+#[test]
+fn vm_while_locals_after_continue() {
+    let code = "var r=0; var i=0; while (i<3) { var a=10; i=i+1; if (i==2) continue; var b=i; r=r+a+b; } exit r;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 24);
+}
+
+#[test]
+fn vm_while_continue_nested_scopes() {
+    let code = "var s=0; var i=0; while (i<4) { var a=i; i=i+1; { var b=a*2; { var c=b; if (a==1) continue; s=s+c; } } var d=1; s=s+d; } exit s;";
+    let res = compile_and_execute(code);
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(res.unwrap(), 13);
+}
+
 #[test]
 fn vm_while_if_break() {
     let code = "var i=0; while (i<5) { i=i+1; if (i==3) break; } exit i;";
@@ -1,9 +1,11 @@
 
 
 use std::borrow::BorrowMut;
+use std::collections::HashMap;
+use std::time::Instant;
 
 
-use log::{trace, debug, warn};
+use log::{debug, warn};
 
 
 use super::CallFrame;
@@ -11,13 +13,36 @@ use super::runtime::NativeMethod;
 use crate::lox::common::keyword::*;
 use crate::lox::common::ByteCode;
 use super::Stack;
-use crate::lox::common::{Array, Value, ValueIterator, NativeCallables};
-use crate::lox::common::Globals;
+use crate::lox::common::{Array, Map, Value, ValueIterator, NativeCallable, NativeCallables, NativeFunction};
+use crate::lox::common::{allocations, Globals, IdentifierKind, Symbol};
 use crate::lox::common::Closure;
-use super::{RuntimeError, r_error};
+use super::{Limits, RuntimeError, RuntimeErrorKind, memory_limit_exceeded, r_error};
 use crate::lox::common::OpCode;
 use super::Upvalue;
 use super::{Class, Instance, Method};
+use super::{Handler, Heap, HeapStats};
+
+
+// Tracing is only compiled in with the "trace" feature; even a log level
+// check for every instruction is a noticeable cost in the dispatch loop
+#[cfg(feature = "trace")]
+use log::trace;
+#[cfg(not(feature = "trace"))]
+macro_rules! trace {
+    ( $($arg:tt)* ) => { if false { log::trace!($($arg)*) } };
+}
+
+
+// Default limits, see set_max_call_depth() and set_max_stack_size()
+const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
+const DEFAULT_MAX_STACK_SIZE: usize = 256 * 1024;
+
+// Runaway recursion would otherwise produce one stack trace line per callframe
+const STACK_TRACE_HEAD: usize = 10;
+const STACK_TRACE_TAIL: usize = 10;
+
+// Instructions between checks of the timeout and memory limits
+const LIMIT_CHECK_INTERVAL: u64 = 1024;
 
 
 pub struct VM {
@@ -25,7 +50,21 @@ pub struct VM {
     stack: Stack<Value>,
     globals: Globals<Value>,
     open_upvalues: Vec<Upvalue<Value>>, // Note: Runtime representation
+    handlers: Vec<Handler>,
+    caught: Option<RuntimeError>, // Most recently caught error, in case it gets rethrown
+    error_class: Value, // Class of values passed to CATCH for errors not raised by THROW
+    modules: HashMap<String, Value>, // Modules whose top level code has been run
     native_callables: NativeCallables,
+    heap: Heap,
+    init_symbol: Symbol, // Names of methods the VM calls implicitly
+    next_symbol: Symbol,
+    max_call_depth: usize,
+    max_stack_size: usize,
+    limits: Limits,
+    instructions: u64, // Executed since the limits were last reset
+    max_instructions: u64,
+    max_bytes: usize,
+    deadline: Option<Instant>,
 }
 
 
@@ -36,34 +75,256 @@ impl VM {
             stack: 		Stack::new(), 
             globals:		Globals::new(),
             open_upvalues:	vec![],
+            handlers:		vec![],
+            caught:		None,
+            error_class:	Value::class(Class::new("Error")),
+            modules:		HashMap::new(),
             native_callables: NativeCallables::new(),
+            heap:		Heap::new(),
+            init_symbol:	Symbol::intern(KEYWORD_INIT),
+            next_symbol:	Symbol::intern(KEYWORD_NEXT),
+            max_call_depth:	DEFAULT_MAX_CALL_DEPTH,
+            max_stack_size:	DEFAULT_MAX_STACK_SIZE,
+            limits:		Limits::default(),
+            instructions:	0,
+            max_instructions:	u64::MAX,
+            max_bytes:		usize::MAX,
+            deadline:		None,
         }
     }
 
     pub fn native_callables(&mut self) -> &mut NativeCallables {
         return &mut self.native_callables;
     }
+
+
+    // Free unreachable reference cycles, return the number of objects freed
+    pub fn collect(&mut self) -> usize {
+        let mut roots: Vec<&Value> = self.stack.as_slice().iter().collect();
+        roots.extend(self.callframes.iter().map(|callframe| callframe.closure_value()));
+        roots.extend(self.globals.values());
+        roots.extend(self.native_callables.values());
+        roots.push(&self.error_class);
+        roots.extend(self.modules.values());
+        if let Some(value) = self.caught.as_ref().and_then(|error| error.get_value()) { roots.push(value); }
+        let upvalue_roots: Vec<&Upvalue<Value>> = self.open_upvalues.iter().collect();
+        return self.heap.collect(&roots, &upvalue_roots);
+    }
+
+
+    pub fn heap_stats(&mut self) -> HeapStats {
+        return self.heap.stats();
+    }
+
+
+    // Calls nested deeper than this fail with "Stack overflow"
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        self.max_call_depth = max_call_depth;
+    }
+
+
+    // Calls made while the value stack holds more than this fail with "Stack overflow"
+    pub fn set_max_stack_size(&mut self, max_stack_size: usize) {
+        self.max_stack_size = max_stack_size;
+    }
+
+
+    // Limit the instructions, time and memory used by each execute(),
+    // or by each call() made while no script is running
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
     
 }
 
 
+// Embedding API, used by host applications after execute() has loaded a script
+impl VM {
+
+    // Call a closure, bound method, class or native method with the given arguments
+    pub fn call(&mut self, callee: &Value, args: &[Value]) -> Result<Value, RuntimeError> {
+        return self.counted(|vm| vm.call_counted(callee, args));
+    }
+
+
+    fn call_counted(&mut self, callee: &Value, args: &[Value]) -> Result<Value, RuntimeError> {
+        if args.len() > u8::MAX as usize {
+            r_error!(format!("Can not call {} with more than {} arguments", callee, u8::MAX))
+        }
+        let depth = self.callframes.len();
+        let stack_base = self.stack.size();
+        if depth == 0 { self.reset_limits(); }
+        self.push(callee.clone());
+        for arg in args { self.push(arg.clone()); }
+
+        let mut result = self.call_value(callee.clone(), args.len() as u8);
+        if result.is_ok() && self.callframes.len() > depth {
+            result = match self.run(depth) {
+                Ok(None) => Ok(()),
+                Ok(Some(rc)) => Err(RuntimeError::new(format!("Script exited with rc={} during call to {}", rc, callee))),
+                Err(error) => Err(error),
+            };
+        }
+        if let Err(error) = result {
+            self.unwind(depth, stack_base);
+            return Err(error);
+        }
+
+        // The callee left its result on top of the stack
+        let value = self.pop();
+        self.stack.truncate(stack_base);
+        return Ok(value);
+    }
+
+
+    // Call a method on an instance, or a built-in method on any value
+    pub fn invoke(&mut self, receiver: &Value, method_name: &str, args: &[Value]) -> Result<Value, RuntimeError> {
+        let callee = self.get_method(receiver, method_name)?;
+        return self.call(&callee, args);
+    }
+
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        let id = self.globals.id_by_name(name)?;
+        return self.globals.value_by_id(id).cloned();
+    }
+
+
+    // Assign a global variable, declaring it if necessary
+    pub fn set_global(&mut self, name: &str, value: Value) -> Result<(), RuntimeError> {
+        let id = match self.globals.id_by_name(name) {
+            Some(id) => {
+                if !self.globals.global_ref_by_id(id).is_mutable() && self.globals.value_by_id(id).is_some() {
+                    r_error!(format!("Can not assign to constant '{}'", name))
+                }
+                id
+            }
+            None => {
+                match self.globals.declare(name, IdentifierKind::Variable) {
+                    Ok(id) => id,
+                    Err(error) => r_error!(error.get_message().to_string()),
+                }
+            }
+        };
+        self.globals.define_by_id(id, value);
+        Ok(())
+    }
+
+
+    // Look up a method or field the same way the GetProperty opcode does
+    fn get_method(&self, receiver: &Value, method_name: &str) -> Result<Value, RuntimeError> {
+        if receiver.is_instance() {
+            if let Some(value) = receiver.as_instance().get(method_name) {
+                return Ok(value.clone());
+            }
+            let class = receiver.as_instance().class().clone();
+            if let Some(method) = class.as_class().get(method_name) {
+                return Ok(Value::method(Method::new(receiver.clone(), method.clone())));
+            }
+        }
+        if let Some(callable) = self.native_callables.get_method(method_name) {
+            return Ok(Value::native_method(NativeMethod::new(receiver.clone(), callable.clone())));
+        }
+        r_error!(format!("{} does not have a method or field '{}'", receiver, method_name))
+    }
+
+
+    // Discard callframes and stack entries left behind by a failed call
+    fn unwind(&mut self, depth: usize, stack_base: usize) {
+        self.callframes.truncate(depth);
+        self.handlers.retain(|handler| handler.depth() <= depth);
+        self.close_upvalues(stack_base);
+        self.stack.truncate(stack_base);
+    }
+
+}
+
+
 impl VM {
     pub fn execute(&mut self, bytecode: &ByteCode) -> Result<i32, RuntimeError> {
         trace!("initialize");
-        self.initialize(&bytecode)?;
-        
+        let result = self.counted(|vm| {
+            vm.initialize(&bytecode, false)?;
+            return vm.run_checked();
+        });
+        match result? {
+            Some(rc) => return Ok(rc),
+            None => r_error!(format!("Main function returned without 'exit'")), // The compiler should make this impossible
+        }
+    }
+
+
+    // Execute ByteCode from Compiler::compile_incremental(), keeping the values
+    // of globals and the imported modules left by the previous entry
+    pub fn execute_incremental(&mut self, bytecode: &ByteCode) -> Result<i32, RuntimeError> {
+        trace!("initialize incremental");
+        let result = self.counted(|vm| {
+            vm.initialize(&bytecode, true)?;
+            return vm.run_checked();
+        });
+        match result? {
+            Some(rc) => return Ok(rc),
+            None => r_error!(format!("Main function returned without 'exit'")), // The compiler should make this impossible
+        }
+    }
+
+
+    // Count the allocations and memory used by f against this VM's heap, not against
+    // other VMs on the same thread. Nested calls, e.g. from a native, are counted by the outer one
+    fn counted<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let activated = self.heap.activate();
+        let result = f(self);
+        if activated { self.heap.deactivate(); }
+        return result;
+    }
+
+
+    // Run the main function, then discard its callframe and stack however it ended,
+    // so the next call() from the host application starts with fresh limits
+    fn run_checked(&mut self) -> Result<Option<i32>, RuntimeError> {
+        let result = self.run(0);
+        self.unwind(0, 0);
+        self.handlers.clear();
+        self.caught = None;
+        return result;
+    }
+
+
+    // Execute until the script exits, returning Some(rc),
+    // or until the number of callframes drops back to depth, returning None
+    fn run(&mut self, depth: usize) -> Result<Option<i32>, RuntimeError> {
         loop {
-            let ip = self.callframe().ip();
-            let fn_name = self.callframe().closure_ref().function_ref().name().to_string();
+            // Note: Memory can grow fast, e.g. by doubling a string, so it is checked after every allocation
+            let mut over_budget = false;
+            if allocations::take_changed() {
+                if self.heap.should_collect() { self.collect(); }
+                over_budget = self.max_bytes != usize::MAX && allocations::bytes() > self.max_bytes;
+            }
+
+            self.instructions = self.instructions + 1;
+            if self.instructions > self.max_instructions
+                || self.instructions % LIMIT_CHECK_INTERVAL == 0
+                || over_budget {
+                if let Err(mut runtime_error) = self.check_limits() {
+                    if let Some(at) = self.callframe().at() { runtime_error.set_at(&at); }
+                    runtime_error.set_stack_trace(self.stack_trace());
+                    return Err(runtime_error);
+                }
+            }
 
+            let ip = self.callframe().ip();
             let opcode = self.callframe_mut().read_op();
 
             // Trace VM state
-            debug!("IP={}:0x{:04x} SP=0x{:04x} CF=0x{:04x} Next={} stack=", fn_name, ip, self.stack.size(), self.callframe().stack_bottom(), opcode.mnemonic());
-            debug!("{:?}", self.stack);
+            #[cfg(feature = "trace")]
+            {
+                let fn_name = self.callframe().closure_ref().function_ref().name().to_string();
+                debug!("IP={}:0x{:04x} SP=0x{:04x} CF=0x{:04x} Next={} stack=", fn_name, ip, self.stack.size(), self.callframe().stack_bottom(), opcode.mnemonic());
+                debug!("{:?}", self.stack);
+            }
             
             let result = match opcode {
-                OpCode::Exit		    => return self.opcode_exit(),
+                OpCode::Exit		    => return self.opcode_exit().map(Some),
                 OpCode::Return 		    => self.opcode_return(),
                 OpCode::Debug		    => self.opcode_debug(),
                 OpCode::Print		    => self.opcode_print(),
@@ -71,6 +332,8 @@ impl VM {
                 OpCode::MakeIter        => self.opcode_makeiter(),
                 OpCode::ReadIter        => self.opcode_readiter(),
                 OpCode::NextIter        => self.opcode_nextiter(),
+                OpCode::Dup2            => self.opcode_dup2(),
+                OpCode::Tuck            => self.opcode_tuck(),
 
                 OpCode::GetConst8 	    |
                 OpCode::GetConst16 	    |
@@ -103,6 +366,9 @@ impl VM {
                 OpCode::DefArray8	    |
                 OpCode::DefArray16 	    |
                 OpCode::DefArray32 	    => self.opcode_defarray(opcode.len()),
+                OpCode::DefMap8	        |
+                OpCode::DefMap16 	    |
+                OpCode::DefMap32 	    => self.opcode_defmap(opcode.len()),
 
                 OpCode::SetLocal8 	    |
                 OpCode::SetLocal16 	    |
@@ -127,9 +393,13 @@ impl VM {
                 OpCode::Method8 	    |
                 OpCode::Method16 	    |
                 OpCode::Method32 	    => self.opcode_method(opcode.len()),
+                OpCode::Import8 	    |
+                OpCode::Import16 	    |
+                OpCode::Import32 	    => self.opcode_import(opcode.len()),
 
                 OpCode::Not 		    => self.opcode_not(),
                 OpCode::Negate 		    => self.opcode_negate(),
+                OpCode::BitNot 		    => self.opcode_bitnot(),
 
                 OpCode::Add 		    => self.opcode_add(),
                 OpCode::Sub 		    => self.opcode_sub(),
@@ -143,11 +413,19 @@ impl VM {
                 OpCode::LessEqual	    => self.opcode_lessequal(),
                 OpCode::GreaterEqual	=> self.opcode_greaterequal(),
                 OpCode::Same		    => self.opcode_same(),
+                OpCode::BitAnd		    => self.opcode_bitand(),
+                OpCode::BitOr		    => self.opcode_bitor(),
+                OpCode::BitXor		    => self.opcode_bitxor(),
+                OpCode::ShiftLeft	    => self.opcode_shiftleft(),
+                OpCode::ShiftRight	    => self.opcode_shiftright(),
 
                 OpCode::Jmp 		    => self.opcode_jmp(opcode.len()),
                 OpCode::JmpFalseP	    => self.opcode_jmpfalsep(opcode.len()),
                 OpCode::JmpFalseQ	    => self.opcode_jmpfalseq(opcode.len()),
                 OpCode::Call 		    => self.opcode_call(opcode.len()),
+                OpCode::Try 		    => self.opcode_try(opcode.len()),
+                OpCode::EndTry 		    => self.opcode_endtry(),
+                OpCode::Throw 		    => self.opcode_throw(),
 
                 OpCode::Pop 		    => self.opcode_pop(),
                 OpCode::PopN 		    => self.opcode_popn(),
@@ -156,6 +434,10 @@ impl VM {
                 OpCode::GetSubscript    => self.opcode_getsubscript(),
                 OpCode::SetSubscript    => self.opcode_setsubscript(),
 
+                OpCode::Invoke8         |
+                OpCode::Invoke16        |
+                OpCode::Invoke32        => self.opcode_invoke(opcode.len()),
+
                 OpCode::BAD 		    => self.opcode_bad(),
             };
             
@@ -167,15 +449,102 @@ impl VM {
                     ip, 
                     self.callframe().closure_ref().function_ref()
                 );
-                runtime_error.set_stack_trace(self.stack_trace());
+                if runtime_error.get_at().is_none() {
+                    if let Some(at) = self.callframe().at() { runtime_error.set_at(&at); }
+                }
+                // Note: Errors from a re-entrant call already have a more complete trace
+                if runtime_error.get_stack_trace().is_empty() {
+                    runtime_error.set_stack_trace(self.stack_trace());
+                }
+                // Jump to the nearest exception handler, unless it belongs to an outer run()
+                // Note: Exceeding the limits must stop the script, even inside 'try'
+                if !runtime_error.is_limit() && self.handlers.last().map_or(false, |handler| handler.depth() > depth) {
+                    self.catch(runtime_error);
+                    continue;
+                }
                 return Err(runtime_error);
             }
+
+            if self.callframes.len() == depth { return Ok(None); }
+        }
+    }
+
+
+    fn reset_limits(&mut self) {
+        self.instructions = 0;
+        self.max_instructions = self.limits.max_instructions.unwrap_or(u64::MAX);
+        self.deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        // Note: Natives check the memory limit through allocations, see reserve()
+        self.max_bytes = self.limits.max_bytes.unwrap_or(usize::MAX);
+        self.heap.set_max_bytes(self.max_bytes);
+    }
+
+
+    fn check_limits(&mut self) -> Result<(), RuntimeError> {
+        if self.instructions > self.max_instructions {
+            let message = format!("Instruction limit of {} exceeded", self.max_instructions);
+            return Err(RuntimeError::limit(RuntimeErrorKind::InstructionLimit, message));
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                let message = format!("Timeout after {:?}", self.limits.timeout.unwrap());
+                return Err(RuntimeError::limit(RuntimeErrorKind::Timeout, message));
+            }
+        }
+        // The estimate includes garbage, so collect it before giving up
+        if allocations::bytes() > self.max_bytes {
+            self.collect();
+            if allocations::bytes() > self.max_bytes {
+                return Err(memory_limit_exceeded());
+            }
         }
+        return Ok(());
+    }
+
+
+    // Discard callframes and stack entries above the nearest handler,
+    // then resume execution there with the error value on the stack
+    fn catch(&mut self, mut runtime_error: RuntimeError) {
+        let handler = self.handlers.pop().unwrap();
+        let value = match runtime_error.get_value() {
+            Some(value) => value.clone(),
+            None => self.error_value(&runtime_error),
+        };
+        trace!("caught {} at depth={}", value, handler.depth());
+        self.unwind(handler.depth(), handler.stack_size());
+        self.push(value.clone());
+        runtime_error.set_value(value);
+        self.caught = Some(runtime_error);
+        self.callframe_mut().jmp(handler.ip());
     }
 
 
+    // Wrap an error raised by the VM itself in an Error instance
+    fn error_value(&self, runtime_error: &RuntimeError) -> Value {
+        let mut instance = Instance::new(self.error_class.clone());
+        instance.set("message", Value::string(runtime_error.get_message()));
+        match runtime_error.get_at() {
+            Some(at) => instance.set("at", Value::string(&at.to_string())),
+            None => instance.set("at", Value::Null),
+        }
+        let mut stack = Array::new();
+        for line in runtime_error.get_stack_trace() { stack.push(Value::string(line)); }
+        instance.set("stack", Value::array(stack));
+        return Value::instance(instance);
+    }
+
+
+    // Only the outermost and innermost callframes are listed if there are many
     fn stack_trace(&self) -> Vec<String> {
-        self.callframes.iter().map(|callframe| format!("{:?}", callframe)).collect()
+        let count = self.callframes.len();
+        if count <= STACK_TRACE_HEAD + STACK_TRACE_TAIL {
+            return self.callframes.iter().map(|callframe| format!("{:?}", callframe)).collect();
+        }
+        let omitted = count - STACK_TRACE_HEAD - STACK_TRACE_TAIL;
+        let mut trace: Vec<String> = self.callframes[..STACK_TRACE_HEAD].iter().map(|callframe| format!("{:?}", callframe)).collect();
+        trace.push(format!("... {} frame(s) omitted ...", omitted));
+        trace.extend(self.callframes[count - STACK_TRACE_TAIL..].iter().map(|callframe| format!("{:?}", callframe)));
+        return trace;
     }
 
     
@@ -208,6 +577,8 @@ impl VM {
         trace!("return statement: stack_bottom={}", bottom);
         self.close_upvalues(self.callframe().stack_bottom());
         self.callframes.pop();
+        let depth = self.callframes.len();
+        self.handlers.retain(|handler| handler.depth() <= depth); // 'return' ends its handlers, this only guards against stale ones
         trace!("  stack height is now {}", self.stack.len());
         while self.stack.len() > bottom { self.pop(); } // Workaround hack to solve problem with receiver being left on the stack
         if self.callframes.len() == 0 { 
@@ -226,6 +597,28 @@ impl VM {
         return self.call_value(callee, arg_count as u8);        
     }
 
+    fn opcode_try(&mut self, len: usize) -> Result<(), RuntimeError> {
+        let ip = self.callframe_mut().read_bytes(len);
+        self.handlers.push(Handler::new(self.callframes.len(), self.stack.size(), ip));
+        Ok(())
+    }
+
+    fn opcode_endtry(&mut self) -> Result<(), RuntimeError> {
+        self.handlers.pop();
+        Ok(())
+    }
+
+    fn opcode_throw(&mut self) -> Result<(), RuntimeError> {
+        let value = self.pop();
+        // Rethrowing a caught object keeps the original location and stack trace
+        if let Some(caught) = &self.caught {
+            if value.is_obj() && caught.get_value().map_or(false, |c| c.is(&value)) {
+                return Err(caught.clone());
+            }
+        }
+        return Err(RuntimeError::thrown(value));
+    }
+
     fn opcode_debug(&mut self) -> Result<(), RuntimeError> {
         let value = self.pop();
         println!("DEBUG> {:?}", value);
@@ -237,6 +630,22 @@ impl VM {
         Ok(())
     }
 
+    fn opcode_dup2(&mut self) -> Result<(), RuntimeError> {
+        self.push(self.peek(1).clone());
+        self.push(self.peek(1).clone());
+        Ok(())
+    }
+
+    fn opcode_tuck(&mut self) -> Result<(), RuntimeError> {
+        let count = self.callframe_mut().read_bytes(1) as usize;
+        let top = self.peek(0).clone();
+        self.push(top);
+        // Rotate the copy down below the count values, the last of which it was copied from
+        let len = self.stack.len();
+        self.stack.as_mut_slice()[len - count - 1..].rotate_right(1);
+        Ok(())
+    }
+
     fn opcode_makeiter(&mut self) -> Result<(), RuntimeError> {
         match ValueIterator::new(self.pop()) {
             Err(msg) => r_error!(format!("{}", msg)),
@@ -262,7 +671,7 @@ impl VM {
         if value.is_instance() {
             self.push(value.clone()); // receiver (=instance)
             trace!("load method '{}' of {} onto stack", KEYWORD_NEXT, value);
-            self.bind_method(&value.as_instance().class(), KEYWORD_NEXT)?; // pops receiver, pushes bound method
+            self.bind_method(&value.as_instance().class(), self.next_symbol)?; // pops receiver, pushes bound method
             let method = self.pop();
             self.push(value.clone()); // receiver again so opcode_nextiter() can see it
             self.push(Value::Null); // Empty slot where call_value() will put the receiver
@@ -298,7 +707,7 @@ impl VM {
     fn opcode_getconst(&mut self, len: usize) -> Result<(), RuntimeError> {
         let id = self.callframe_mut().read_bytes(len) as usize;
         // When reading from the constant table, we must copy, not clone; constants are immutable
-        let value = Value::from(self.callframe().closure_ref().function_ref().read_constants().value_by_id(id));
+        let value = Value::from(self.callframe().constant(id));
         trace!("loaded constant id=0x{:08x} onto stack: {}", id, value);
         self.push(value);
         Ok(())
@@ -307,8 +716,9 @@ impl VM {
 
     fn opcode_getlocal(&mut self, len: usize) -> Result<(), RuntimeError> {
         let id = self.callframe_mut().read_bytes(len) as usize;
-        let depth = self.slot_depth(id); // Stack index from bottom
-        self.push(self.peek(depth).clone());
+        let addr = self.slot_addr(id);
+        let value = self.stack.peek_addr(addr).clone();
+        self.push(value);
         trace!("loaded local variable id=0x{:08x} onto stack: {}", id, self.peek(0));
         Ok(())
     }
@@ -349,37 +759,59 @@ impl VM {
 
     fn opcode_getglobal(&mut self, len: usize) -> Result<(), RuntimeError> {
         let id = self.callframe_mut().read_bytes(len) as usize;
-        // Compiler guarantees the variable is defined
-        self.push(self.globals.value_by_id(id).unwrap().clone());
-        trace!("loaded global id=0x{:08x} onto stack: {}", id, self.globals.value_by_id(id).unwrap());
+        // The compiler only reads globals it has seen defined, but verified bytecode from a file may not
+        let value = match self.globals.value_by_id(id) {
+            Some(value) => value.clone(),
+            None => r_error!(format!("Global '{}' has not been defined yet", self.globals.name_by_id(id))),
+        };
+        trace!("loaded global id=0x{:08x} onto stack: {}", id, value);
+        self.push(value);
         Ok(())
     }
 
 
     fn opcode_getproperty(&mut self, len: usize) -> Result<(), RuntimeError> {
-        let id = self.callframe_mut().read_bytes(len) as usize;
-        // Read field name from the constants table
-        let constant = self.callframe().closure_ref().function_ref().read_constants().value_by_id(id).clone();
-        let name = constant.as_string();
+        let name = self.read_symbol(len)?; // Field name from the constants table
+        let slot = self.callframe_mut().read_bytes(2) as usize;
 
         let receiver = self.peek(0).clone();	// Receiver Value
 
         // Check the user-defined fields and methods (note that these may shadow any built-in ones)
         if receiver.is_instance() {
-            if let Some(value) = receiver.as_instance().get(name.as_str()) {
+            let field = receiver.as_instance().get_by_symbol(name).cloned();
+            if let Some(value) = field {
                 self.pop();
-                self.push(value.clone());
+                self.push(value);
                 trace!("loaded field '{}' of {} onto stack", name, receiver);
                 return Ok(())
             }
-            if let Some(_) = receiver.as_instance().class().get(&constant) {
+            // Fields belong to the instance, so only a method found on its class can be cached
+            let class = receiver.as_instance().class().clone();
+            let mut method = self.callframe().inline_caches().lookup(slot, &class, name);
+            if method.is_none() {
+                method = class.as_class().get_by_symbol(name).cloned();
+                if let Some(method) = &method {
+                    self.callframe().inline_caches().update(slot, &class, name, method);
+                }
+            }
+            if let Some(method) = method {
                 trace!("loaded method '{}' of {} onto stack", name, receiver);
-                return self.bind_method(&receiver.as_instance().class(), name.as_str());
+                self.pop();
+                self.push(Value::method(Method::new(receiver, method)));
+                return Ok(());
             }
         }
 
+        // Modules only have the globals they export
+        if receiver.is_module() {
+            let value = self.module_export(&receiver, name)?;
+            self.pop();
+            self.push(value);
+            return Ok(())
+        }
+
         // If the name matches a built-in method, bind it and push it onto the stack
-        if let Some(callable) = self.native_callables().get_method(name.as_str()).cloned() {
+        if let Some(callable) = self.native_callables.get_method_by_symbol(name).cloned() {
             return self.bind_native_method(callable); // The receiver is still on the stack
         }
 
@@ -388,14 +820,69 @@ impl VM {
     }
 
 
+    fn module_export(&self, module: &Value, name: Symbol) -> Result<Value, RuntimeError> {
+        let module = module.as_module();
+        let value = match module.export(&name.name()) {
+            Some(id) => self.globals.value_by_id(id).cloned(),
+            None => r_error!(format!("Module '{}' does not export '{}'", module.name(), name)),
+        };
+        match value {
+            Some(value) => return Ok(value),
+            None => r_error!(format!("Module '{}' has not defined '{}' yet", module.name(), name)),
+        }
+    }
+
+
+    // Same as GetProperty followed by Call, but a method is called directly
+    // with the receiver in its place instead of creating a bound method first
+    fn opcode_invoke(&mut self, len: usize) -> Result<(), RuntimeError> {
+        let name = self.read_symbol(len)?;
+        let arg_count = self.callframe_mut().read_bytes(1) as u8;
+        let slot = self.callframe_mut().read_bytes(2) as usize;
+        let receiver = self.peek(arg_count as usize).clone();
+
+        if receiver.is_instance() {
+            // A field holding a callable value shadows any method of the same name
+            let field = receiver.as_instance().get_by_symbol(name).cloned();
+            if let Some(value) = field {
+                self.poke(value.clone(), arg_count as usize);
+                return self.call_value(value, arg_count);
+            }
+            let class = receiver.as_instance().class().clone();
+            let mut method = self.callframe().inline_caches().lookup(slot, &class, name);
+            if method.is_none() {
+                method = class.as_class().get_by_symbol(name).cloned();
+                if let Some(method) = &method {
+                    self.callframe().inline_caches().update(slot, &class, name, method);
+                }
+            }
+            if let Some(method) = method {
+                trace!("invoke method '{}' of {} with {} argument(s)", name, receiver, arg_count);
+                return self.call_closure(method, arg_count);
+            }
+        }
+
+        if receiver.is_module() {
+            let value = self.module_export(&receiver, name)?;
+            self.poke(value.clone(), arg_count as usize);
+            return self.call_value(value, arg_count);
+        }
+
+        // Built-in methods get the receiver as their first argument
+        if let Some(callable) = self.native_callables.get_method_by_symbol(name) {
+            let callable = callable.as_native().clone();
+            return self.call_native(callable, arg_count, true);
+        }
+
+        r_error!(format!("{} does not have a method or field '{}'", receiver, name))
+    }
+
+
     fn opcode_getsuper(&mut self, len: usize) -> Result<(), RuntimeError> {
-        let id = self.callframe_mut().read_bytes(len) as usize;
-        // Read field name from the constants table
-        let constant = self.callframe().closure_ref().function_ref().read_constants().value_by_id(id).clone();
-        let method_name = constant.as_string();
+        let method_name = self.read_symbol(len)?; // Method name from the constants table
 
         let superclass = self.pop();
-        if self.bind_method(&superclass, method_name.as_str()).is_err() {
+        if self.bind_method(&superclass, method_name).is_err() {
             r_error!(format!("Could not bind method '{}' to superclass {}", method_name, superclass))
         }
         trace!("bound method '{}' to superclass {}", method_name, superclass);
@@ -426,6 +913,20 @@ impl VM {
         Ok(())
     }
 
+    fn opcode_defmap(&mut self, len: usize) -> Result<(), RuntimeError> {
+        let pairs = self.callframe_mut().read_bytes(len) as usize;
+        let mut map = Map::new();
+        for pair in self.stack.as_slice()[self.stack.len()-pairs*2..].chunks(2) {
+            if let Err(msg) = map.set(&pair[0], pair[1].clone()) {
+                r_error!(msg)
+            }
+        }
+        self.stack.truncate(self.stack.len() - pairs*2); // Drop keys and values from stack
+        self.push(Value::map(map));
+        trace!("popped {} key/value pair(s) off stack, defined map", pairs);
+        Ok(())
+    }
+
     fn opcode_getsubscript(&mut self) -> Result<(), RuntimeError> {
         let keys = self.pop();
         let value = self.pop();
@@ -472,14 +973,16 @@ impl VM {
                 r_error!(format!("{}", msg));
             }
         }
+        self.push(source); // Assignment is an expression, the compiler uses or pops the value
         Ok(())
     }
 
 
     fn opcode_setlocal(&mut self, len: usize) -> Result<(), RuntimeError> {
         let id = self.callframe_mut().read_bytes(len) as usize;
-        let depth = self.slot_depth(id); // Stack index from bottom
-        self.poke(self.peek(0).clone(), depth);
+        let addr = self.slot_addr(id);
+        let value = self.peek(0).clone();
+        self.stack.poke_addr(value, addr);
         trace!("value copied to local variable id=0x{:08x}: {}", id, self.peek(0));
         Ok(())
     }
@@ -528,17 +1031,17 @@ impl VM {
 
 
     fn opcode_setproperty(&mut self, len: usize) -> Result<(), RuntimeError> {
-        let id = self.callframe_mut().read_bytes(len) as usize;
-        // Read field name from the constants table
-        let constant = self.callframe().closure_ref().function_ref().read_constants().value_by_id(id).clone();
-        let field = constant.as_string();
+        let field = self.read_symbol(len)?; // Field name from the constants table
 
         let value = self.pop(); // Value to assign
         let mut instance = self.pop();// Value with field to be written
 
         if instance.is_instance() {
             let mut instance = instance.as_instance_mut();
-            instance.set(field.as_str(), value.clone());
+            if instance.is_read_only() {
+                r_error!(format!("Can not set field '{}' of read-only {}", field, instance))
+            }
+            instance.set_by_symbol(field, value.clone());
             trace!("set field '{}' of {} to {}", field, instance, value);
             self.push(value);
         } else {
@@ -548,10 +1051,28 @@ impl VM {
     }
 
 
+    fn opcode_import(&mut self, len: usize) -> Result<(), RuntimeError> {
+        let id = self.callframe_mut().read_bytes(len) as usize;
+        let module = self.callframe().constant(id).clone();
+        let name = module.as_module().name().to_string();
+
+        // Run the top level code of a module the first time it is imported.
+        // A module whose top level code failed is not recorded, so importing it again retries
+        if !self.modules.contains_key(&name) {
+            trace!("running top level code of module {}", name);
+            let main = Value::closure(Closure::new(module.as_module().main().clone()));
+            self.call(&main, &[])?;
+            self.modules.insert(name.clone(), module.clone());
+        }
+        self.push(self.modules[&name].clone());
+        Ok(())
+    }
+
+
     fn opcode_capture(&mut self, len: usize) -> Result<(), RuntimeError> {
         let constant = self.callframe_mut().read_bytes(len) as usize;
         // Get the function from constants table
-        let value = self.callframe().closure_ref().function_ref().read_constants().value_by_id(constant).clone();
+        let value = self.callframe().constant(constant).clone();
         let upvalue_count = value.as_function().upvalue_count();
         // Wrap it in a closure
         let mut closure = Closure::new(value);
@@ -585,7 +1106,7 @@ impl VM {
 
     fn opcode_class(&mut self, len: usize) -> Result<(), RuntimeError> {
         let id = self.callframe_mut().read_bytes(len) as usize;
-        let name = self.callframe().closure_ref().function_ref().read_constants().value_by_id(id).clone();
+        let name = self.callframe().constant(id).clone();
         let class = Class::new(name.as_string().as_str());
         trace!("class {} defined using constant id=0x{:08x}: {}", class, id, name);
         self.push(Value::class(class));
@@ -594,12 +1115,11 @@ impl VM {
 
 
     fn opcode_method(&mut self, len: usize) -> Result<(), RuntimeError> {
-        let id = self.callframe_mut().read_bytes(len) as usize;
-        let method_name = self.callframe().closure_ref().function_ref().read_constants().value_by_id(id).as_string().clone();
+        let method_name = self.read_symbol(len)?;
         let method_value = self.pop();
         let mut class_value = self.peek(0).clone();
         trace!("popped {} off stack, added as method '{}' of {}", method_value, method_name, class_value);
-        class_value.as_class_mut().set(&method_name, method_value);
+        class_value.as_class_mut().set_by_symbol(method_name, method_value);
         Ok(())
     }
 
@@ -622,6 +1142,15 @@ impl VM {
         Ok(())
     }
     
+    fn opcode_bitnot(&mut self) -> Result<(), RuntimeError> {
+        let value = self.pop();
+        match value.bitwise_not() {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
     fn opcode_add(&mut self) -> Result<(), RuntimeError> {
         let b = self.pop();
         let a = self.pop();
@@ -677,6 +1206,61 @@ impl VM {
         Ok(())
     }
     
+    fn opcode_bitand(&mut self) -> Result<(), RuntimeError> {
+        let b = self.pop();
+        let a = self.pop();
+        let res = a.bitwise_and(&b);
+        match res {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
+    fn opcode_bitor(&mut self) -> Result<(), RuntimeError> {
+        let b = self.pop();
+        let a = self.pop();
+        let res = a.bitwise_or(&b);
+        match res {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
+    fn opcode_bitxor(&mut self) -> Result<(), RuntimeError> {
+        let b = self.pop();
+        let a = self.pop();
+        let res = a.bitwise_xor(&b);
+        match res {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
+    fn opcode_shiftleft(&mut self) -> Result<(), RuntimeError> {
+        let b = self.pop();
+        let a = self.pop();
+        let res = a.shift_left(&b);
+        match res {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
+    fn opcode_shiftright(&mut self) -> Result<(), RuntimeError> {
+        let b = self.pop();
+        let a = self.pop();
+        let res = a.shift_right(&b);
+        match res {
+            Ok(value) => { self.push(value); }
+            Err(msg) => { r_error!(msg) }
+        }
+        Ok(())
+    }
+    
     fn opcode_equal(&mut self) -> Result<(), RuntimeError> {
         let b = self.pop();
         let a = self.pop();
@@ -762,6 +1346,7 @@ impl VM {
     
     fn opcode_closeupvalue(&mut self) -> Result<(), RuntimeError> {
         self.close_upvalues(self.stack.top());
+        self.pop(); // Emitted instead of Pop for a captured local, which goes out of scope the same way
         Ok(())
     }
     
@@ -801,34 +1386,62 @@ impl VM {
         self.stack.poke(value, depth);
     }
 
-    fn slot_depth(&self, slot: usize) -> usize {
-        trace!("current stack size is {}", self.stack.size());
-        trace!("callframe stack bottom is at {}", self.callframe().stack_bottom());
-        let depth = self.stack.size()
-            - self.callframe().stack_bottom()
-            - 1
-            - slot;
-        trace!("requested slot is {}; {} - {} - 1 - {} = {}", slot, self.stack.size(), self.callframe().stack_bottom(), slot, depth);
-        return depth;
+    // Stack index from bottom of a local variable slot in the current callframe
+    fn slot_addr(&self, slot: usize) -> usize {
+        return self.callframe().stack_bottom() + slot;
+    }
+
+
+    // Read the operand of an instruction that names a field or method
+    fn read_symbol(&mut self, len: usize) -> Result<Symbol, RuntimeError> {
+        let id = self.callframe_mut().read_bytes(len) as usize;
+        match self.callframe().symbol(id) {
+            Some(symbol) => Ok(symbol),
+            None => r_error!(format!("Constant 0x{:08x} is not a valid name", id)),
+        }
     }
 
 
-    fn initialize(&mut self, bytecode: &ByteCode) -> Result<(), RuntimeError> {
+    fn initialize(&mut self, bytecode: &ByteCode, incremental: bool) -> Result<(), RuntimeError> {
         self.callframes.clear();
         self.stack.clear();
         self.open_upvalues.clear();
-        self.globals = bytecode.globals().clone();
+        self.handlers.clear();
+        self.caught = None;
+        self.reset_limits();
+        let previous = std::mem::replace(&mut self.globals, bytecode.globals().clone());
+        if incremental {
+            // Ids are stable between entries, new globals are appended at the end
+            for id in 0..previous.count().min(self.globals.count()) {
+                match previous.value_by_id(id) {
+                    Some(value) => self.globals.define_by_id(id, value.clone()),
+                    // A failed entry may leave a declared global without a value
+                    None => if self.globals.global_ref_by_id(id).is_defined() { self.globals.define_by_id(id, Value::Null) },
+                }
+            }
+        } else {
+            self.modules.clear();
+        }
+        // Bind native functions and values declared by the compiler to the ones registered with this VM
+        for (name, value) in self.native_callables.globals() {
+            if let Some(id) = self.globals.id_by_name(name) {
+                self.globals.define_by_id(id, value.clone());
+            }
+        }
         let closure = Closure::new(Value::function(bytecode.main().clone()));
         let value = Value::closure(closure);
         self.push(value.clone());
         return self.call_value(value, 0); // Main function takes zero arguments
     }
 
-    fn call(&mut self, callee: Value, argc: u8) -> Result<(), RuntimeError> {
+    fn call_closure(&mut self, callee: Value, argc: u8) -> Result<(), RuntimeError> {
         let want_argc = callee.as_closure().function_ref().arity();
         if argc != want_argc {
             r_error!(format!("Expected {} argument(s) but got {}", want_argc, argc)) 
         }
+        if self.callframes.len() >= self.max_call_depth || self.stack.size() > self.max_stack_size {
+            r_error!(format!("Stack overflow"))
+        }
 
         let stack_bottom = self.stack.size() - (argc as usize) - 1;
         let callframe = CallFrame::new(callee, stack_bottom);
@@ -839,13 +1452,13 @@ impl VM {
     fn call_value(&mut self, value: Value, argc: u8) -> Result<(), RuntimeError> {
         trace!("calling {} with {} argument(s)", value, argc);
         if value.is_closure() {
-            self.call(value, argc)?;
+            self.call_closure(value, argc)?;
         } else if value.is_method() {
             let bound = value.as_method();
             self.stack.poke(bound.receiver().clone(), argc as usize);       
-            self.call(bound.method().clone(), argc)?;
+            self.call_closure(bound.method().clone(), argc)?;
         } else if value.is_class() {
-            let initializer = match value.as_class().get(KEYWORD_INIT) {
+            let initializer = match value.as_class().get_by_symbol(self.init_symbol) {
                 None => None,
                 Some(function) => Some(function.clone()),
             };
@@ -856,28 +1469,51 @@ impl VM {
             self.poke(instance, argc as usize);
             // handle constructor arguments, if any
             if let Some(function) = initializer {
-                self.call(function, argc)?;
+                self.call_closure(function, argc)?;
             } else if argc != 0 {
                 r_error!(format!("Expected 0 arguments but got {}", argc))
             }
         } else if value.is_native_method() {
             let bound = value.as_native_method();
-            if bound.method().as_native().arity() != argc as usize{
-                r_error!(format!("Expected {} argument(s) but got {}", bound.method().as_native().arity(), argc))
-            }
             self.stack.poke(bound.receiver().clone(), argc as usize);
-            let depth = self.stack.len() - argc as usize - 1;
-            let result = bound.method().as_native().callable()(&mut self.stack.as_mut_slice()[depth..])?;
-            self.stack.truncate(depth); // Discard the receiver and the arguments, if any
-            self.push(result);
+            let callable = bound.method().as_native().clone();
+            drop(bound); // The native may call back into the VM
+            self.call_native(callable, argc, true)?;
+        } else if value.is_native() {
+            let callable = value.as_native().clone();
+            self.call_native(callable, argc, false)?;
         } else {
             r_error!(format!("VM.call_value({}, {}) not implemented.", value, argc))
         }
         Ok(())
     }
 
+
+    // The callee or receiver and the arguments are on the stack
+    // Methods get the receiver as args[0], functions get only the arguments
+    fn call_native(&mut self, native: NativeCallable, argc: u8, is_method: bool) -> Result<(), RuntimeError> {
+        if native.arity() != argc as usize {
+            r_error!(format!("Expected {} argument(s) but got {}", native.arity(), argc))
+        }
+        let depth = self.stack.len() - argc as usize - 1;
+        let first = if is_method { depth } else { depth + 1 };
+        let result = match native.callable() {
+            NativeFunction::Plain(function) => {
+                function(&mut self.stack.as_mut_slice()[first..])?
+            }
+            NativeFunction::Context(function) => {
+                // Copy the arguments so the VM can be lent to the native
+                let mut args = self.stack.as_slice()[first..].to_vec();
+                function(self, &mut args)?
+            }
+        };
+        self.stack.truncate(depth); // Discard the callee or receiver and the arguments, if any
+        self.push(result);
+        Ok(())
+    }
+
         
-    fn bind_method(&mut self, class: &Value, method_name: &str) -> Result<(), RuntimeError> {
+    fn bind_method(&mut self, class: &Value, method_name: Symbol) -> Result<(), RuntimeError> {
         let receiver = self.stack.pop();
         trace!("class={} method={} receiver={}", class, method_name, receiver);
         if !class.is_class() {
@@ -888,7 +1524,7 @@ impl VM {
         }
         // clox looks up the class by name, 
         // but the receiver already has a reference to its class.
-        match class.as_class().get(method_name) {
+        match class.as_class().get_by_symbol(method_name) {
             Some(method_value) => {
                 let bound_method = Method::new(receiver, method_value.clone());        
                 self.push(Value::method(bound_method));
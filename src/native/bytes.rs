@@ -0,0 +1,22 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::VALUE_SIZE;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Return an array with the UTF-8 encoding of a string
+pub fn bytes(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        reserve(receiver.as_string().len().saturating_mul(VALUE_SIZE))?;
+        let mut result = Array::new();
+        for byte in receiver.as_string().bytes() {
+            result.push(Value::number(byte as f64));
+        }
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'bytes'", receiver));
+}
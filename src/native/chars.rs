@@ -0,0 +1,22 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::{OBJ_SIZE, VALUE_SIZE};
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Return an array of single character strings
+pub fn chars(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        reserve(receiver.as_string().len().saturating_mul(VALUE_SIZE + OBJ_SIZE))?;
+        let mut result = Array::new();
+        for ch in receiver.as_string().chars() {
+            result.push(Value::string(String::from(ch).as_str()));
+        }
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'chars'", receiver));
+}
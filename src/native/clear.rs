@@ -0,0 +1,26 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn clear(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        receiver.as_string_mut().clear();
+        return Ok(Value::Null);
+    }
+
+    if receiver.is_array() {
+        receiver.as_array_mut().clear();
+        return Ok(Value::Null);
+    }
+
+    if receiver.is_map() {
+        receiver.as_map_mut().clear();
+        return Ok(Value::Null);
+    }
+
+    r_error!(format!("{} does not have a method 'clear'", receiver));
+}
@@ -0,0 +1,13 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Seconds since the UNIX epoch
+pub fn clock(_args: &mut [Value]) -> Result<Value, RuntimeError> {
+    match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(duration) => return Ok(Value::number(duration.as_secs_f64())),
+        Err(error) => r_error!(format!("System clock error: {}", error)),
+    }
+}
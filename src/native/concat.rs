@@ -0,0 +1,24 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::VALUE_SIZE;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Return a new array with the elements of both arrays
+pub fn concat(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let other = &args[1];
+
+    if receiver.is_array() {
+        if !other.is_array() {
+            r_error!(format!("concat() expects an array, got {}", other))
+        }
+        reserve((receiver.as_array().len() + other.as_array().len()) * VALUE_SIZE)?;
+        let mut result = Array::from(receiver.as_array().as_slice());
+        result.extend_from_slice(other.as_array().as_slice());
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'concat'", receiver));
+}
@@ -0,0 +1,23 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn contains(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let needle = &args[1];
+
+    if receiver.is_string() {
+        if !needle.is_string() {
+            r_error!(format!("contains() expects a string, got {}", needle))
+        }
+        return Ok(Value::boolean(receiver.as_string().contains(needle.as_string().as_str())));
+    }
+
+    if receiver.is_array() {
+        return Ok(Value::boolean(receiver.as_array().as_slice().contains(needle)));
+    }
+
+    r_error!(format!("{} does not have a method 'contains'", receiver));
+}
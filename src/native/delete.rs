@@ -0,0 +1,17 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Remove a key from a map and return its value, or null if the key was not found
+pub fn delete(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let key = &args[1];
+
+    if receiver.is_map() {
+        return Ok(receiver.as_map_mut().delete(key).unwrap_or(Value::Null));
+    }
+
+    r_error!(format!("{} does not have a method 'delete'", receiver));
+}
@@ -0,0 +1,20 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Set every element to the same value
+pub fn fill(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let element = args[1].clone();
+    let receiver = &args[0];
+
+    if receiver.is_array() {
+        for slot in receiver.as_array_mut().as_mut_slice() {
+            *slot = element.clone();
+        }
+        return Ok(Value::Null);
+    }
+
+    r_error!(format!("{} does not have a method 'fill'", receiver));
+}
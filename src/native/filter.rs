@@ -0,0 +1,24 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::vm::{RuntimeError, r_error, VM};
+
+
+pub fn filter(vm: &mut VM, args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let callback = args[1].clone();
+    let receiver = &args[0];
+
+    if receiver.is_array() {
+        // Note: Copy the elements, the callback may modify the array
+        let elements = receiver.as_array().as_slice().to_vec();
+        let mut result = Array::new();
+        for element in elements {
+            if vm.call(&callback, &[element.clone()])?.is_truthy() {
+                result.push(element);
+            }
+        }
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'filter'", receiver));
+}
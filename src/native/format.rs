@@ -0,0 +1,21 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Format a number with a fixed number of decimals
+pub fn format(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_number() {
+        // Note: NaN and infinity have no integer part, so fract() rules them out too
+        if !args[1].is_number() || args[1].as_number().fract() != 0.0 || !(0.0..=100.0).contains(&args[1].as_number()) {
+            r_error!(format!("format() expects an integer precision between 0 and 100, got {}", args[1]))
+        }
+        let precision = args[1].as_number() as usize;
+        return Ok(Value::string(format!("{:.*}", precision, receiver.as_number()).as_str()));
+    }
+
+    r_error!(format!("{} does not have a method 'format'", receiver));
+}
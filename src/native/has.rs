@@ -0,0 +1,16 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn has(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let key = &args[1];
+
+    if receiver.is_map() {
+        return Ok(Value::boolean(receiver.as_map().has(key)));
+    }
+
+    r_error!(format!("{} does not have a method 'has'", receiver));
+}
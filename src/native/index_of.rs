@@ -0,0 +1,32 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Return the index of the first occurrence, or -1 if not found
+// Strings are indexed by character
+pub fn index_of(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let needle = &args[1];
+
+    if receiver.is_string() {
+        if !needle.is_string() {
+            r_error!(format!("index_of() expects a string, got {}", needle))
+        }
+        let haystack = receiver.as_string();
+        match haystack.find(needle.as_string().as_str()) {
+            Some(byte_index) => return Ok(Value::number(haystack[..byte_index].chars().count() as f64)),
+            None => return Ok(Value::number(-1.0)),
+        }
+    }
+
+    if receiver.is_array() {
+        match receiver.as_array().as_slice().iter().position(|element| element == needle) {
+            Some(index) => return Ok(Value::number(index as f64)),
+            None => return Ok(Value::number(-1.0)),
+        }
+    }
+
+    r_error!(format!("{} does not have a method 'index_of'", receiver));
+}
@@ -0,0 +1,24 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn insert(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let element = args[2].clone();
+    let receiver = &args[0];
+
+    if receiver.is_array() {
+        // Note: NaN and infinity have no integer part, so fract() rules them out too
+        if !args[1].is_number() || args[1].as_number().fract() != 0.0 || args[1].as_number() < 0.0 {
+            r_error!(format!("insert() expects a non-negative integer index, got {}", args[1]))
+        }
+        let index = args[1].as_number() as usize;
+        match receiver.as_array_mut().insert(index, element) {
+            Ok(()) => return Ok(Value::Null),
+            Err(msg) => r_error!(msg),
+        }
+    }
+
+    r_error!(format!("{} does not have a method 'insert'", receiver));
+}
@@ -0,0 +1,27 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Join the elements of an array into a string;
+// either array.join(separator) or separator.join(array)
+pub fn join(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let (elements, separator) = if args[0].is_array() {
+        (&args[0], &args[1])
+    } else if args[0].is_string() {
+        (&args[1], &args[0])
+    } else {
+        r_error!(format!("{} does not have a method 'join'", args[0]))
+    };
+
+    if !elements.is_array() || !separator.is_string() {
+        r_error!(format!("join() expects an array and a string, got {} and {}", elements, separator))
+    }
+
+    let parts: Vec<String> = elements.as_array().as_slice().iter().map(|element| element.to_string()).collect();
+    let separator = separator.as_string();
+    let length = parts.iter().map(|part| part.len()).sum::<usize>();
+    reserve(length.saturating_add(separator.len().saturating_mul(parts.len())))?;
+    return Ok(Value::string(parts.join(separator.as_str()).as_str()));
+}
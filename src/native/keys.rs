@@ -0,0 +1,16 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn keys(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_map() {
+        let keys = receiver.as_map().keys();
+        return Ok(Value::array(Array::from(keys.as_slice())));
+    }
+
+    r_error!(format!("{} does not have a method 'keys'", receiver));
+}
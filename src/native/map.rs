@@ -0,0 +1,24 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::VALUE_SIZE;
+use crate::lox::vm::{RuntimeError, r_error, reserve, VM};
+
+
+pub fn map(vm: &mut VM, args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let callback = args[1].clone();
+    let receiver = &args[0];
+
+    if receiver.is_array() {
+        // Note: Copy the elements, the callback may modify the array
+        let elements = receiver.as_array().as_slice().to_vec();
+        reserve(elements.len() * VALUE_SIZE)?;
+        let mut result = Array::new();
+        for element in elements {
+            result.push(vm.call(&callback, &[element])?);
+        }
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'map'", receiver));
+}
@@ -0,0 +1,197 @@
+
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error, VM};
+use super::Namespace;
+
+
+// Wrap an f64 method taking no arguments
+macro_rules! unary {
+    ( $name:expr, $method:ident ) => {
+        |args: &mut [Value]| -> Result<Value, RuntimeError> {
+            Ok(Value::number(number($name, &args[0])?.$method()))
+        }
+    };
+}
+
+// Wrap an f64 method taking one f64 argument
+macro_rules! binary {
+    ( $name:expr, $method:ident ) => {
+        |args: &mut [Value]| -> Result<Value, RuntimeError> {
+            Ok(Value::number(number($name, &args[0])?.$method(number($name, &args[1])?)))
+        }
+    };
+}
+
+// Wrap an f64 predicate
+macro_rules! predicate {
+    ( $name:expr, $method:ident ) => {
+        |args: &mut [Value]| -> Result<Value, RuntimeError> {
+            Ok(Value::boolean(number($name, &args[0])?.$method()))
+        }
+    };
+}
+
+
+// The math namespace
+pub fn math() -> Value {
+    let mut math = Namespace::new("math");
+
+    math.constant("E", Value::number(std::f64::consts::E));
+    math.constant("PI", Value::number(std::f64::consts::PI));
+    math.constant("TAU", Value::number(std::f64::consts::TAU));
+    math.constant("SQRT_2", Value::number(std::f64::consts::SQRT_2));
+    math.constant("LN_2", Value::number(std::f64::consts::LN_2));
+    math.constant("LN_10", Value::number(std::f64::consts::LN_10));
+    math.constant("LOG2_E", Value::number(std::f64::consts::LOG2_E));
+    math.constant("LOG10_E", Value::number(std::f64::consts::LOG10_E));
+    math.constant("EPSILON", Value::number(f64::EPSILON));
+    math.constant("MAX", Value::number(f64::MAX));
+    math.constant("MIN", Value::number(f64::MIN));
+    math.constant("MIN_POSITIVE", Value::number(f64::MIN_POSITIVE));
+
+    math.function("abs", unary!("abs", abs), 1);
+    math.function("acos", unary!("acos", acos), 1);
+    math.function("acosh", unary!("acosh", acosh), 1);
+    math.function("asin", unary!("asin", asin), 1);
+    math.function("asinh", unary!("asinh", asinh), 1);
+    math.function("atan", unary!("atan", atan), 1);
+    math.function("atan2", binary!("atan2", atan2), 2);
+    math.function("atanh", unary!("atanh", atanh), 1);
+    math.function("cbrt", unary!("cbrt", cbrt), 1);
+    math.function("ceil", unary!("ceil", ceil), 1);
+    math.function("copysign", binary!("copysign", copysign), 2);
+    math.function("cos", unary!("cos", cos), 1);
+    math.function("cosh", unary!("cosh", cosh), 1);
+    math.function("div_euclid", binary!("div_euclid", div_euclid), 2);
+    math.function("exp", unary!("exp", exp), 1);
+    math.function("exp2", unary!("exp2", exp2), 1);
+    math.function("expm1", unary!("expm1", exp_m1), 1);
+    math.function("floor", unary!("floor", floor), 1);
+    math.function("fract", unary!("fract", fract), 1);
+    math.function("hypot", binary!("hypot", hypot), 2);
+    math.function("ln", unary!("ln", ln), 1);
+    math.function("ln1p", unary!("ln1p", ln_1p), 1);
+    math.function("log", binary!("log", log), 2);
+    math.function("log10", unary!("log10", log10), 1);
+    math.function("log2", unary!("log2", log2), 1);
+    math.function("max", binary!("max", max), 2);
+    math.function("min", binary!("min", min), 2);
+    math.function("pow", binary!("pow", powf), 2);
+    math.function("recip", unary!("recip", recip), 1);
+    math.function("rem_euclid", binary!("rem_euclid", rem_euclid), 2);
+    math.function("round", unary!("round", round), 1);
+    math.function("signum", unary!("signum", signum), 1);
+    math.function("sin", unary!("sin", sin), 1);
+    math.function("sinh", unary!("sinh", sinh), 1);
+    math.function("sqrt", unary!("sqrt", sqrt), 1);
+    math.function("tan", unary!("tan", tan), 1);
+    math.function("tanh", unary!("tanh", tanh), 1);
+    math.function("to_degrees", unary!("to_degrees", to_degrees), 1);
+    math.function("to_radians", unary!("to_radians", to_radians), 1);
+    math.function("trunc", unary!("trunc", trunc), 1);
+    math.function("is_finite", predicate!("is_finite", is_finite), 1);
+    math.function("is_infinite", predicate!("is_infinite", is_infinite), 1);
+    math.function("is_nan", predicate!("is_nan", is_nan), 1);
+    math.function("clamp", clamp, 3);
+    math.function("mul_add", mul_add, 3);
+
+    // Each namespace gets its own generator, seeded from the clock.
+    // Call math.seed(n) to get a reproducible sequence.
+    let prng = Rc::new(Prng::new(clock_seed()));
+    let state = prng.clone();
+    math.context_function("seed", Box::new(move |_vm: &mut VM, args: &mut [Value]| -> Result<Value, RuntimeError> {
+        state.seed(number("seed", &args[0])? as i64 as u64);
+        Ok(Value::Null)
+    }), 1);
+    let state = prng.clone();
+    math.context_function("random", Box::new(move |_vm: &mut VM, _args: &mut [Value]| -> Result<Value, RuntimeError> {
+        Ok(Value::number(state.next_f64()))
+    }), 0);
+    let state = prng.clone();
+    math.context_function("random_int", Box::new(move |_vm: &mut VM, args: &mut [Value]| -> Result<Value, RuntimeError> {
+        let low = number("random_int", &args[0])?.ceil();
+        let high = number("random_int", &args[1])?.floor();
+        if !(low <= high) {
+            r_error!(format!("math.random_int() expects low <= high, got {} and {}", args[0], args[1]))
+        }
+        Ok(Value::number(low + (state.next_f64() * (high - low + 1.0)).floor()))
+    }), 2);
+
+    return math.into_value();
+}
+
+
+fn number(name: &str, value: &Value) -> Result<f64, RuntimeError> {
+    if !value.is_number() {
+        r_error!(format!("math.{}() expects a number, got {}", name, value))
+    }
+    return Ok(value.as_number());
+}
+
+
+fn clamp(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let value = number("clamp", &args[0])?;
+    let min = number("clamp", &args[1])?;
+    let max = number("clamp", &args[2])?;
+    if !(min <= max) {
+        r_error!(format!("math.clamp() expects min <= max, got {} and {}", args[1], args[2]))
+    }
+    return Ok(Value::number(value.clamp(min, max)));
+}
+
+
+fn mul_add(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let value = number("mul_add", &args[0])?;
+    let a = number("mul_add", &args[1])?;
+    let b = number("mul_add", &args[2])?;
+    return Ok(Value::number(value.mul_add(a, b)));
+}
+
+
+fn clock_seed() -> u64 {
+    match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos() as u64,
+        Err(_) => 0,
+    }
+}
+
+
+// SplitMix64; small, fast and any seed is fine. Not for cryptographic use.
+struct Prng {
+    state: Cell<u64>,
+}
+
+
+impl Prng {
+
+    fn new(seed: u64) -> Self {
+        Prng { state: Cell::new(seed) }
+    }
+
+
+    fn seed(&self, seed: u64) {
+        self.state.set(seed);
+    }
+
+
+    fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9e3779b97f4a7c15);
+        self.state.set(state);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+
+    // Uniformly distributed in [0, 1)
+    fn next_f64(&self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+}
@@ -1,18 +1,82 @@
-
-
 #[cfg(test)]
 mod test;
 
 
-mod shift;
+mod bytes;
+mod chars;
+mod clear;
+mod clock;
+mod concat;
+mod contains;
+mod delete;
+mod ends_with;
+mod fill;
+mod filter;
+mod format;
+mod has;
+mod index_of;
+mod insert;
+mod join;
+mod keys;
 mod len;
+mod map;
+mod math;
+mod namespace;
 mod pop;
 mod push;
+mod remove;
+mod repeat;
+mod replace;
+mod reverse;
+mod shift;
+mod slice;
+mod sort;
+mod split;
+mod starts_with;
+mod substring;
+mod to_lower;
+mod to_number;
+mod to_upper;
+mod trim;
 mod unshift;
+mod values;
 
 
-pub use shift::*;
+pub use bytes::*;
+pub use chars::*;
+pub use clear::*;
+pub use clock::*;
+pub use concat::*;
+pub use contains::*;
+pub use delete::*;
+pub use ends_with::*;
+pub use fill::*;
+pub use filter::*;
+pub use format::*;
+pub use has::*;
+pub use index_of::*;
+pub use insert::*;
+pub use join::*;
+pub use keys::*;
 pub use len::*;
+pub use map::*;
+pub use math::*;
+pub use namespace::*;
 pub use pop::*;
 pub use push::*;
+pub use remove::*;
+pub use repeat::*;
+pub use replace::*;
+pub use reverse::*;
+pub use shift::*;
+pub use slice::*;
+pub use sort::*;
+pub use split::*;
+pub use starts_with::*;
+pub use substring::*;
+pub use to_lower::*;
+pub use to_number::*;
+pub use to_upper::*;
+pub use trim::*;
 pub use unshift::*;
+pub use values::*;
@@ -0,0 +1,52 @@
+
+
+use crate::lox::common::{NativeCallable, NativeContextFn, NativeFn, Symbol, Value};
+use crate::lox::vm::{Class, Instance};
+
+
+// Built-in libraries are exposed to scripts as a read-only instance of an empty class,
+// with native functions and constants as fields, e.g. math.sqrt(2)
+pub struct Namespace {
+    name: String,
+    instance: Instance,
+}
+
+
+impl Namespace {
+
+    pub fn new(name: &str) -> Self {
+        Namespace {
+            name: name.to_string(),
+            instance: Instance::new(Value::class(Class::new(name))),
+        }
+    }
+
+
+    pub fn function(&mut self, name: &str, function: NativeFn, arity: usize) {
+        let callable = NativeCallable::new(self.qualified(name), function, arity);
+        self.instance.set_by_symbol(Symbol::intern(name), Value::native(callable));
+    }
+
+
+    pub fn context_function(&mut self, name: &str, function: Box<NativeContextFn>, arity: usize) {
+        let callable = NativeCallable::with_context(self.qualified(name), function, arity);
+        self.instance.set_by_symbol(Symbol::intern(name), Value::native(callable));
+    }
+
+
+    pub fn constant(&mut self, name: &str, value: Value) {
+        self.instance.set_by_symbol(Symbol::intern(name), value);
+    }
+
+
+    pub fn into_value(mut self) -> Value {
+        self.instance.make_read_only();
+        return Value::instance(self.instance);
+    }
+
+
+    fn qualified(&self, name: &str) -> String {
+        return format!("{}.{}", self.name, name);
+    }
+
+}
@@ -1,4 +1,4 @@
-use crate::lox::common::Value;
+use crate::lox::common::{allocations, Value};
 use crate::lox::vm::{RuntimeError, r_error};
 
 
@@ -9,6 +9,7 @@ pub fn push(args: &mut [Value]) -> Result<Value, RuntimeError> {
     if receiver.is_string() && element.is_number() {
         match char::from_u32(element.as_number() as u32) {
             Some(ch) => {
+                allocations::grow(ch.len_utf8());
                 receiver.as_string_mut().push(ch);
                 return Ok(Value::Null);
             }
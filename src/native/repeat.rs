@@ -0,0 +1,29 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+const MAX_LENGTH: usize = 256 * 1024 * 1024; // Bytes in the resulting string
+
+
+pub fn repeat(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        // Note: NaN and infinity have no integer part, so fract() rules them out too
+        if !args[1].is_number() || args[1].as_number().fract() != 0.0 || args[1].as_number() < 0.0 {
+            r_error!(format!("repeat() expects a non-negative integer, got {}", args[1]))
+        }
+        let input = receiver.as_string();
+        // Note: Counts beyond usize::MAX saturate, and are rejected below
+        let count = args[1].as_number() as usize;
+        match input.len().checked_mul(count) {
+            Some(length) if length <= MAX_LENGTH => reserve(length)?,
+            _ => r_error!(format!("repeat() would make a string longer than {} bytes", MAX_LENGTH)),
+        }
+        return Ok(Value::string(input.repeat(count).as_str()));
+    }
+
+    r_error!(format!("{} does not have a method 'repeat'", receiver));
+}
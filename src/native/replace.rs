@@ -0,0 +1,27 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Return a copy with all occurrences of a substring replaced
+pub fn replace(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        if !args[1].is_string() || !args[2].is_string() {
+            r_error!(format!("replace() expects two strings, got {} and {}", args[1], args[2]))
+        }
+        let from = args[1].as_string();
+        if from.is_empty() {
+            r_error!(format!("replace() can not replace an empty string"))
+        }
+        let input = receiver.as_string();
+        let to = args[2].as_string();
+        let count = input.matches(from.as_str()).count();
+        reserve(input.len().saturating_add(count.saturating_mul(to.len())))?;
+        return Ok(Value::string(input.replace(from.as_str(), to.as_str()).as_str()));
+    }
+
+    r_error!(format!("{} does not have a method 'replace'", receiver));
+}
@@ -0,0 +1,22 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Return a reversed copy of a string, like the other string methods, or reverse an array in place
+pub fn reverse(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        let result: String = receiver.as_string().chars().rev().collect();
+        return Ok(Value::string(result.as_str()));
+    }
+
+    if receiver.is_array() {
+        receiver.as_array_mut().reverse();
+        return Ok(Value::Null);
+    }
+
+    r_error!(format!("{} does not have a method 'reverse'", receiver));
+}
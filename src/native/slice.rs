@@ -0,0 +1,43 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::VALUE_SIZE;
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Return the elements or characters from start up to but not including end
+pub fn slice(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if !args[1].is_number() || !args[2].is_number() {
+        r_error!(format!("slice() expects two numbers, got {} and {}", args[1], args[2]))
+    }
+    let start = args[1].as_number().floor();
+    let end = args[2].as_number().floor();
+    if !start.is_finite() || !end.is_finite() {
+        r_error!(format!("slice() expects two finite numbers, got {} and {}", start, end))
+    }
+
+    if receiver.is_string() {
+        let input = receiver.as_string();
+        let length = input.chars().count() as f64;
+        if start < 0.0 || start > end || end > length {
+            r_error!(format!("Bad slice range {}..{} for string of length {}", start, end, length))
+        }
+        reserve(input.len())?;
+        let result: String = input.chars().skip(start as usize).take((end - start) as usize).collect();
+        return Ok(Value::string(result.as_str()));
+    }
+
+    if receiver.is_array() {
+        let input = receiver.as_array();
+        let length = input.len() as f64;
+        if start < 0.0 || start > end || end > length {
+            r_error!(format!("Bad slice range {}..{} for array of length {}", start, end, length))
+        }
+        reserve((end - start) as usize * VALUE_SIZE)?;
+        return Ok(Value::array(Array::from(&input.as_slice()[start as usize..end as usize])));
+    }
+
+    r_error!(format!("{} does not have a method 'slice'", receiver));
+}
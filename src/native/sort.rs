@@ -0,0 +1,29 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Sort in place, elements must be comparable with each other
+pub fn sort(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_array() {
+        // Note: Sort a copy, comparing elements may need to borrow the array itself
+        let mut elements = receiver.as_array().as_slice().to_vec();
+        // The comparator must be a total order, so check first. Only numbers other than NaN
+        // can be ordered, and those that compare with themselves and the first compare with each other
+        if let Some(first) = elements.first() {
+            for element in elements.iter() {
+                if element.partial_cmp(element).is_none() || element.partial_cmp(first).is_none() {
+                    r_error!(format!("Can not compare {} with {}", first, element))
+                }
+            }
+        }
+        elements.sort_by(|a, b| a.partial_cmp(b).expect("Internal error: elements were checked"));
+        receiver.as_array_mut().as_mut_slice().clone_from_slice(&elements);
+        return Ok(Value::Null);
+    }
+
+    r_error!(format!("{} does not have a method 'sort'", receiver));
+}
@@ -0,0 +1,31 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::common::allocations::{OBJ_SIZE, VALUE_SIZE};
+use crate::lox::vm::{RuntimeError, r_error, reserve};
+
+
+// Split a string into an array of strings, or into characters if the separator is empty
+pub fn split(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+    let separator = &args[1];
+
+    if receiver.is_string() {
+        if !separator.is_string() {
+            r_error!(format!("split() expects a string separator, got {}", separator))
+        }
+        let input = receiver.as_string();
+        let separator = separator.as_string();
+        let count = if separator.is_empty() { input.len() } else { input.matches(separator.as_str()).count() + 1 };
+        reserve(count.saturating_mul(VALUE_SIZE + OBJ_SIZE).saturating_add(input.len()))?;
+        let mut result = Array::new();
+        if separator.is_empty() {
+            for ch in input.chars() { result.push(Value::string(String::from(ch).as_str())); }
+        } else {
+            for part in input.split(separator.as_str()) { result.push(Value::string(part)); }
+        }
+        return Ok(Value::array(result));
+    }
+
+    r_error!(format!("{} does not have a method 'split'", receiver));
+}
@@ -0,0 +1,18 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn starts_with(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        if !args[1].is_string() {
+            r_error!(format!("starts_with() expects a string, got {}", args[1]))
+        }
+        return Ok(Value::boolean(receiver.as_string().starts_with(args[1].as_string().as_str())));
+    }
+
+    r_error!(format!("{} does not have a method 'starts_with'", receiver));
+}
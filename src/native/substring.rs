@@ -0,0 +1,30 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Return the characters from start up to but not including end
+pub fn substring(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        if !args[1].is_number() || !args[2].is_number() {
+            r_error!(format!("substring() expects two numbers, got {} and {}", args[1], args[2]))
+        }
+        let input = receiver.as_string();
+        let length = input.chars().count() as f64;
+        let start = args[1].as_number().floor();
+        let end = args[2].as_number().floor();
+        if !start.is_finite() || !end.is_finite() {
+            r_error!(format!("substring() expects two finite numbers, got {} and {}", start, end))
+        }
+        if start < 0.0 || start > end || end > length {
+            r_error!(format!("Bad substring range {}..{} for string of length {}", start, end, length))
+        }
+        let result: String = input.chars().skip(start as usize).take((end - start) as usize).collect();
+        return Ok(Value::string(result.as_str()));
+    }
+
+    r_error!(format!("{} does not have a method 'substring'", receiver));
+}
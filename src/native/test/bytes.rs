@@ -0,0 +1,24 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn bytes_ascii() {
+    let code = "var a = 'AB'.bytes(); exit a[0] + a[1];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("bytes", bytes, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 131);
+}
+
+#[test]
+fn bytes_unicode() {
+    let code = "exit 'ø'.bytes().len();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("bytes", bytes, 0);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 2);
+}
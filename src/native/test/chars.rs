@@ -0,0 +1,24 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn chars_unicode() {
+    let code = "var a = 'aøc'.chars(); exit a.len() == 3 && a[1] == 'ø';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("chars", chars, 0);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn chars_empty() {
+    let code = "exit ''.chars() == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("chars", chars, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
@@ -0,0 +1,33 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn clear_array() {
+    let code = "var a = [1, 2]; a.clear(); exit a == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("clear", clear, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn clear_string() {
+    let code = "var s = 'abc'; s.clear(); exit s == '';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("clear", clear, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn clear_map() {
+    let code = "var m = {'a': 1}; m.clear(); exit m == {};";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("clear", clear, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
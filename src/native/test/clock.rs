@@ -0,0 +1,82 @@
+use crate::lox::VM;
+use crate::lox::common::Value;
+use super::*;
+
+
+#[test]
+fn clock_function() {
+    let code = "var t = clock(); exit t > 0;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_function("clock", clock, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn clock_wrong_arity() {
+    let code = "clock(1);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_function("clock", clock, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn clock_in_function() {
+    let code = "fun f() { return clock(); } exit f() > 0;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_function("clock", clock, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn native_function_args() {
+    let code = "exit add(2, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_function("add", |args: &mut [Value]| {
+        Ok(Value::number(args[0].as_number() + args[1].as_number()))
+    }, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 5);
+}
+
+#[test]
+fn native_function_is_constant() {
+    let code = "clock = 1;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_function("clock", clock, 0);
+    let compiler = crate::lox::Compiler::with_natives(vm.native_callables());
+    let result = compiler.compile("test", std::io::Cursor::new(code));
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn native_global_duplicate() {
+    let mut vm = VM::new();
+    assert_eq!(vm.native_callables().insert_function("clock", clock, 0), true);
+    assert_eq!(vm.native_callables().insert_value("clock", Value::number(1.0)), false);
+    assert_eq!(vm.native_callables().insert_function("clock", clock, 1), false);
+    let result = compile_and_execute_using(vm, "exit clock() > 0;");
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn native_globals_in_order() {
+    let mut vm = VM::new();
+    let names = ["one", "two", "three", "four", "five", "six", "seven", "eight"];
+    for name in names.iter() {
+        vm.native_callables().insert_value(name, Value::number(1.0));
+        vm.native_callables().insert_function(&name.to_uppercase(), clock, 0);
+    }
+    let globals: Vec<&String> = vm.native_callables().globals().map(|(name, _)| name).collect();
+    assert_eq!(globals.len(), 16);
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(globals[2 * i], name);
+        assert_eq!(*globals[2 * i + 1], name.to_uppercase());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn concat_arrays() {
+    let code = "var a = [1, 2]; var b = a.concat([3]); exit b == [1, 2, 3] && a == [1, 2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("concat", concat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn concat_not_array() {
+    let code = "[1].concat(2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("concat", concat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
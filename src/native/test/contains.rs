@@ -0,0 +1,33 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn contains_array_true() {
+    let code = "exit [1, 'a', null].contains('a');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("contains", contains, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn contains_array_false() {
+    let code = "exit [1, 2].contains(3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("contains", contains, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 0);
+}
+
+#[test]
+fn contains_string() {
+    let code = "exit 'hello'.contains('ell');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("contains", contains, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
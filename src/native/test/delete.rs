@@ -0,0 +1,34 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn delete_existing() {
+    let code = "var m = {'a': 1, 'b': 2}; var v = m.delete('a'); exit v == 1 && m == {'b': 2};";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("delete", delete, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn delete_missing() {
+    let code = "var m = {'a': 1}; exit m.delete('b') == null && m == {'a': 1};";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("delete", delete, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn delete_while_iterating() {
+    let code = "var m = {1: 1, 2: 2, 3: 3, 4: 4}; var n = 0; for var k in m { m.delete(k); n = n + 1; } exit n * 10 + m.len();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("delete", delete, 1);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 40);
+}
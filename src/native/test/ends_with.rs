@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn ends_with_true() {
+    let code = "exit 'hello'.ends_with('lo');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("ends_with", ends_with, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn ends_with_false() {
+    let code = "exit 'hello'.ends_with('he');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("ends_with", ends_with, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 0);
+}
@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn fill_array() {
+    let code = "var a = [1, 2, 3]; a.fill(0); exit a == [0, 0, 0];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("fill", fill, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn fill_empty() {
+    let code = "var a = []; a.fill(0); exit a == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("fill", fill, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
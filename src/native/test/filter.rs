@@ -0,0 +1,34 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn filter_array() {
+    let code = "fun big(x) { return x > 1; } var a = [1, 2, 3]; var b = a.filter(big); exit b[0] + b[1];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("filter", Box::new(filter), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 5);
+}
+
+#[test]
+fn filter_array_none() {
+    let code = "fun none(x) { return false; } var a = [1, 2, 3]; exit a.filter(none) == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("filter", Box::new(filter), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn filter_keeps_original() {
+    let code = "fun big(x) { return x > 1; } var a = [1, 2, 3]; a.filter(big); exit a.len();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("filter", Box::new(filter), 1);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 3);
+}
@@ -0,0 +1,69 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn format_precision() {
+    let code = "var n = 3.14159; exit n.format(2) == '3.14';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn format_zero() {
+    let code = "var n = 2.5; exit n.format(0) == '2';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn format_pad() {
+    let code = "var n = 1; exit n.format(3) == '1.000';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn format_string() {
+    let code = "'abc'.format(2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn format_nan() {
+    let code = "var n = 1; n.format(0/0);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn format_fraction() {
+    let code = "var n = 1; n.format(1.5);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn format_out_of_range() {
+    let code = "var n = 1; n.format(101);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("format", format, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
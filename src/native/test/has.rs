@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn has_true() {
+    let code = "var m = {'a': 1, 2: 'b'}; exit m.has('a') && m.has(2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("has", has, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn has_false() {
+    let code = "var m = {'a': 1}; exit !m.has('b') && !m.has(1) && !m.has([]);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("has", has, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
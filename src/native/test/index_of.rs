@@ -0,0 +1,73 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn index_of_found() {
+    let code = "exit 'hello'.index_of('l');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[test]
+fn index_of_unicode() {
+    let code = "exit 'æøå'.index_of('å');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[test]
+fn index_of_not_found() {
+    let code = "exit 'hello'.index_of('x') == -1;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn find_alias() {
+    let code = "exit 'hello'.find('lo');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("find", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn index_of_array() {
+    let code = "exit [5, 6, 7].index_of(7);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[test]
+fn index_of_array_string() {
+    let code = "exit ['a', 'b'].index_of('b');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn index_of_array_not_found() {
+    let code = "exit [5, 6, 7].index_of(8) == -1;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("index_of", index_of, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
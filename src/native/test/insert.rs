@@ -0,0 +1,70 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn insert_middle() {
+    let code = "var a = [1, 3]; a.insert(1, 2); exit a == [1, 2, 3];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn insert_end() {
+    let code = "var a = [1, 2]; a.insert(2, 3); exit a == [1, 2, 3];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn insert_out_of_range() {
+    let code = "var a = [1, 2]; a.insert(3, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn insert_negative() {
+    let code = "var a = [1, 2]; a.insert(-1, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn insert_nan() {
+    let code = "var a = [1, 2]; a.insert(0/0, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn insert_zero() {
+    let code = "var a = [1, 2]; a.insert(0, 0); exit a == [0, 1, 2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn insert_fraction() {
+    let code = "var a = [1, 2]; a.insert(0.5, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("insert", insert, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "insert() expects a non-negative integer index, got 0.5");
+}
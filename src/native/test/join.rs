@@ -0,0 +1,42 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn join_array() {
+    let code = "exit ['a', 'b', 'c'].join(', ') == 'a, b, c';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("join", join, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn join_string() {
+    let code = "exit '-'.join([1, 2, 3]) == '1-2-3';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("join", join, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn join_empty() {
+    let code = "exit [].join(',') == '';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("join", join, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn join_bad_separator() {
+    let code = "[1, 2].join(3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("join", join, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
@@ -0,0 +1,32 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn keys_map() {
+    let code = "var m = {'a': 1, 2: 'b'}; exit m.keys() == ['a', 2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("keys", keys, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn keys_empty() {
+    let code = "exit {}.keys() == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("keys", keys, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn keys_array() {
+    let code = "[1, 2].keys();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("keys", keys, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
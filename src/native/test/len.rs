@@ -84,3 +84,13 @@ fn len_array_3() {
     assert_eq!(result.unwrap(), 1);
 }
 
+
+#[test]
+fn len_map_2() {
+    let code = "var m={'foo': 1, 'bar': 2}; exit m.len() == 2;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
@@ -0,0 +1,86 @@
+use crate::lox::{RuntimeError, VM};
+use crate::lox::vm::{Limits, RuntimeErrorKind};
+use crate::lox::common::Value;
+use super::*;
+
+
+#[test]
+fn map_array() {
+    let code = "fun double(x) { return x * 2; } var a = [1, 2, 3]; var b = a.map(double); exit b[0] + b[1] + b[2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 12);
+}
+
+#[test]
+fn map_array_closure() {
+    let code = "var n = 10; fun add(x) { return x + n; } var b = [1, 2].map(add); exit b[0] + b[1];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 23);
+}
+
+#[test]
+fn map_empty() {
+    let code = "fun double(x) { return x * 2; } var a = []; exit a.map(double) == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn map_callback_error() {
+    let code = "fun bad(x) { return x.invalid; } var a = [1]; a.map(bad);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn map_not_callable() {
+    let code = "var a = [1]; a.map(1);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn map_string() {
+    let code = "fun double(x) { return x * 2; } 'abc'.map(double);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn boxed_closure_with_state() {
+    let code = "var a = []; a.count(); a.count(); exit a.count();";
+    let mut vm = VM::new();
+    let counter = std::cell::Cell::new(0.0);
+    vm.native_callables().insert_context_method("count", Box::new(move |_vm: &mut VM, _args: &mut [Value]| -> Result<Value, RuntimeError> {
+        counter.set(counter.get() + 1.0);
+        Ok(Value::number(counter.get()))
+    }), 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn map_limit_not_catchable() {
+    let code = "fun spin(x) { while (true) {} } try { [1, 2].map(spin); } catch { exit 1; } exit 2;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_context_method("map", Box::new(map), 1);
+    vm.set_limits(Limits { max_instructions: Some(10000), ..Limits::default() });
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.unwrap_err().get_kind(), RuntimeErrorKind::InstructionLimit);
+}
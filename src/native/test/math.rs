@@ -0,0 +1,158 @@
+use crate::lox::VM;
+use super::*;
+
+
+fn math_vm() -> VM {
+    let mut vm = VM::new();
+    vm.native_callables().insert_value("math", math());
+    return vm;
+}
+
+
+#[test]
+fn math_sqrt() {
+    let code = "exit math.sqrt(16);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 4);
+}
+
+#[test]
+fn math_floor_ceil_round() {
+    let code = "exit math.floor(2.5) == 2 && math.ceil(2.5) == 3 && math.round(2.5) == 3;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_abs() {
+    let code = "exit math.abs(-7);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 7);
+}
+
+#[test]
+fn math_pow() {
+    let code = "exit math.pow(2, 10);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1024);
+}
+
+#[test]
+fn math_min_max() {
+    let code = "exit math.min(3, 9) * 10 + math.max(3, 9);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 39);
+}
+
+#[test]
+fn math_pi() {
+    let code = "exit math.PI > 3.14 && math.PI < 3.15;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_constant_read_only() {
+    let code = "math.PI = 3; exit math.PI;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "Can not set field 'PI' of read-only Instance(class=math)");
+}
+
+#[test]
+fn math_constant_read_only_subscript() {
+    let code = "math['PI'] = 3; exit math.PI;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "Can not set field 'PI' of read-only Instance(class=math)");
+}
+
+#[test]
+fn math_function_read_only() {
+    let code = "math.sqrt = null; exit 0;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn math_not_a_function() {
+    let mut vm = math_vm();
+    assert_eq!(vm.native_callables().get_function("math").is_none(), true);
+    assert_eq!(vm.native_callables().get_value("math").is_some(), true);
+}
+
+#[test]
+fn math_sin() {
+    let code = "exit math.abs(math.sin(math.PI)) < math.EPSILON * 10;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_clamp() {
+    let code = "exit math.clamp(15, 0, 10);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 10);
+}
+
+#[test]
+fn math_is_nan() {
+    let code = "exit math.is_nan(nan) && !math.is_nan(1);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_not_a_number() {
+    let code = "math.sqrt('four');";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn math_wrong_arity() {
+    let code = "math.sqrt(1, 2);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn math_random_range() {
+    let code = "for (var i = 0; i < 100; i = i + 1) { var r = math.random(); if (r < 0 || r >= 1) exit 0; } exit 1;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_random_seed() {
+    let code = "math.seed(42); var a = math.random(); math.seed(42); exit a == math.random();";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_random_int() {
+    let code = "math.seed(1); for (var i = 0; i < 100; i = i + 1) { var r = math.random_int(1, 6); if (r < 1 || r > 6 || r != math.floor(r)) exit 0; } exit 1;";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn math_include() {
+    let code = "#include <math>\nexit sqrt(81) + floor(PI);";
+    let result = compile_and_execute_using(math_vm(), code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 12);
+}
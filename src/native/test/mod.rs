@@ -1,11 +1,41 @@
 
-
+mod bytes;
+mod chars;
+mod clear;
+mod clock;
+mod concat;
+mod contains;
+mod delete;
+mod ends_with;
+mod fill;
+mod filter;
+mod format;
+mod has;
+mod index_of;
+mod insert;
+mod join;
+mod keys;
 mod len;
+mod map;
+mod math;
 mod pop;
-mod push; 
+mod push;
+mod remove;
+mod repeat;
+mod replace;
+mod reverse;
 mod shift;
+mod slice;
+mod sort;
+mod split;
+mod starts_with;
+mod substring;
+mod to_lower;
+mod to_number;
+mod to_upper;
+mod trim;
 mod unshift;
-
+mod values;
 
 use crate::lox::{Compiler, VM, RuntimeError};
 
@@ -15,10 +45,10 @@ pub use super::*;
 
 // Convenience function used for testing native methods and functions
 fn compile_and_execute_using(mut vm: VM, code: &str) -> Result<i32, RuntimeError> {
-    let compiler = Compiler::new();
+    let compiler = Compiler::with_natives(vm.native_callables());
     let reader = std::io::Cursor::new(code);
     match compiler.compile("test", reader) {
-        Err(compile_error) => panic!("Compile failed unexpectedly: {}", compile_error),
+        Err(compile_errors) => panic!("Compile failed unexpectedly: {:?}", compile_errors),
         Ok(bytecode) => {
             match vm.execute(&bytecode) {
                 Err(runtime_error) => {
@@ -0,0 +1,60 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn remove_middle() {
+    let code = "var a = [1, 2, 3]; var e = a.remove(1); exit e == 2 && a == [1, 3];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn remove_out_of_range() {
+    let code = "var a = [1, 2]; a.remove(2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn remove_empty() {
+    let code = "var a = []; a.remove(0);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn remove_nan() {
+    let code = "var a = [1, 2]; a.remove(0/0);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn remove_zero() {
+    let code = "var a = [1, 2]; var e = a.remove(0); exit e == 1 && a == [2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn remove_fraction() {
+    let code = "var a = [1, 2]; a.remove(0.5);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("remove", remove, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "remove() expects a non-negative integer index, got 0.5");
+}
@@ -0,0 +1,71 @@
+use crate::lox::VM;
+use crate::lox::vm::{Limits, RuntimeErrorKind};
+use super::*;
+
+
+#[test]
+fn repeat_3() {
+    let code = "exit 'ab'.repeat(3) == 'ababab';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn repeat_0() {
+    let code = "exit 'ab'.repeat(0) == '';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn repeat_negative() {
+    let code = "'ab'.repeat(-1);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "repeat() expects a non-negative integer, got -1");
+}
+
+#[test]
+fn repeat_fraction() {
+    let code = "'ab'.repeat(1.5);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn repeat_capacity_overflow() {
+    let code = "'x'.repeat(1000000000000000000);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn repeat_too_long() {
+    let code = "'abc'.repeat(100000000000);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn repeat_memory_limit() {
+    let code = "'x'.repeat(10000000);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("repeat", repeat, 1);
+    vm.set_limits(Limits { max_bytes: Some(1000000), ..Limits::default() });
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.unwrap_err().get_kind(), RuntimeErrorKind::MemoryLimit);
+}
@@ -0,0 +1,32 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn replace_all() {
+    let code = "exit 'a-b-c'.replace('-', '+') == 'a+b+c';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("replace", replace, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn replace_not_found() {
+    let code = "exit 'abc'.replace('x', 'y') == 'abc';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("replace", replace, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn replace_empty() {
+    let code = "'abc'.replace('', 'y');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("replace", replace, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn reverse_array() {
+    let code = "var a = [1, 2, 3]; a.reverse(); exit a == [3, 2, 1];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("reverse", reverse, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn reverse_string() {
+    let code = "var s = 'æøå'; var r = s.reverse(); exit r == 'åøæ' && s == 'æøå';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("reverse", reverse, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
@@ -0,0 +1,71 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn slice_array() {
+    let code = "var a = [1, 2, 3, 4].slice(1, 3); exit a == [2, 3];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn slice_array_empty() {
+    let code = "exit [1, 2].slice(1, 1) == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn slice_array_out_of_range() {
+    let code = "[1, 2].slice(0, 3);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn slice_string() {
+    let code = "exit 'æøåabc'.slice(2, 4) == 'åa';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn slice_keeps_original() {
+    let code = "var a = [1, 2, 3]; a.slice(0, 1); exit a.len();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn slice_array_nan() {
+    let code = "[1, 2, 3].slice(2, 0/0);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn slice_string_nan() {
+    let code = "'abc'.slice(0/0, 2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("slice", slice, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
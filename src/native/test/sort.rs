@@ -0,0 +1,72 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn sort_numbers() {
+    let code = "var a = [3, 1, 2]; a.sort(); exit a == [1, 2, 3];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn sort_negative() {
+    let code = "var a = [0, -1.5, 2, -3]; a.sort(); exit a == [-3, -1.5, 0, 2];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn sort_not_comparable() {
+    let code = "var a = ['b', 'a']; a.sort();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn sort_empty() {
+    let code = "var a = []; a.sort(); exit a == [];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn sort_mixed() {
+    let code = "var a = [1, 'a']; a.sort();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "Can not compare 1 with a");
+}
+
+#[test]
+fn sort_nan() {
+    let code = "var a = [1, 0/0, 2]; a.sort();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+    assert_eq!(result.unwrap_err().get_message(), "Can not compare 1 with nan");
+}
+
+#[test]
+fn sort_mixed_unchanged() {
+    let code = "var a = [2, 1, \"a\"]; try { a.sort(); } catch { } exit a[0] == 2 && a[1] == 1;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("sort", sort, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
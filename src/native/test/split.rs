@@ -0,0 +1,45 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn split_words() {
+    let code = "var a = 'a,b,c'.split(','); exit a.len() == 3 && a[0] == 'a' && a[2] == 'c';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("split", split, 1);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn split_empty_separator() {
+    let code = "var a = 'æøå'.split(''); exit a.len() == 3 && a[1] == 'ø';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("split", split, 1);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn split_not_found() {
+    let code = "var a = 'abc'.split(','); exit a.len() == 1 && a[0] == 'abc';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("split", split, 1);
+    vm.native_callables().insert_method("len", len, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn split_bad_separator() {
+    let code = "'abc'.split(1);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("split", split, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
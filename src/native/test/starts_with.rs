@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn starts_with_true() {
+    let code = "exit 'hello'.starts_with('he');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("starts_with", starts_with, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn starts_with_false() {
+    let code = "exit 'hello'.starts_with('lo');";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("starts_with", starts_with, 1);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 0);
+}
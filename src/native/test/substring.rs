@@ -0,0 +1,60 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn substring_middle() {
+    let code = "exit 'hello'.substring(1, 3) == 'el';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn substring_unicode() {
+    let code = "exit 'æøåæøå'.substring(2, 4) == 'åæ';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn substring_empty() {
+    let code = "exit 'hello'.substring(2, 2) == '';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn substring_out_of_range() {
+    let code = "'hello'.substring(2, 6);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn substring_reversed() {
+    let code = "'hello'.substring(3, 2);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
+
+#[test]
+fn substring_nan() {
+    let code = "'hello'.substring(2, 0/0);";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("substring", substring, 2);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
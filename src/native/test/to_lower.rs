@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn to_lower_ascii() {
+    let code = "exit 'ABC'.to_lower() == 'abc';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_lower", to_lower, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn to_lower_unicode() {
+    let code = "exit 'ÆØÅ'.to_lower() == 'æøå';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_lower", to_lower, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
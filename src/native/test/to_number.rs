@@ -0,0 +1,33 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn to_number_decimal() {
+    let code = "exit '12.5'.to_number() * 2;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_number", to_number, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 25);
+}
+
+#[test]
+fn to_number_whitespace() {
+    let code = "exit ' 42 '.to_number();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_number", to_number, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[test]
+fn to_number_invalid() {
+    let code = "exit 'abc'.to_number() == null;";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_number", to_number, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
@@ -0,0 +1,23 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn to_upper_ascii() {
+    let code = "exit 'abc'.to_upper() == 'ABC';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_upper", to_upper, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn to_upper_unicode() {
+    let code = "exit 'æøå'.to_upper() == 'ÆØÅ';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("to_upper", to_upper, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
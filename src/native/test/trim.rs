@@ -0,0 +1,32 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn trim_whitespace() {
+    let code = "exit '  abc \t\n'.trim() == 'abc';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("trim", trim, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn trim_keeps_original() {
+    let code = "var s = ' a '; s.trim(); exit s == ' a ';";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("trim", trim, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn trim_number() {
+    let code = "1.trim();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("trim", trim, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
@@ -0,0 +1,22 @@
+use crate::lox::VM;
+use super::*;
+
+
+#[test]
+fn values_map() {
+    let code = "var m = {'a': 1, 2: 'b'}; exit m.values() == [1, 'b'];";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("values", values, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn values_string() {
+    let code = "'abc'.values();";
+    let mut vm = VM::new();
+    vm.native_callables().insert_method("values", values, 0);
+    let result = compile_and_execute_using(vm, code);
+    assert_eq!(result.is_err(), true);
+}
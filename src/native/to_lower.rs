@@ -0,0 +1,15 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn to_lower(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        return Ok(Value::string(receiver.as_string().to_lowercase().as_str()));
+    }
+
+    r_error!(format!("{} does not have a method 'to_lower'", receiver));
+}
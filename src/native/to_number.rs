@@ -0,0 +1,23 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Parse a string as a number, return null if it is not a valid number
+pub fn to_number(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_number() {
+        return Ok(receiver.clone());
+    }
+
+    if receiver.is_string() {
+        match receiver.as_string().trim().parse::<f64>() {
+            Ok(number) => return Ok(Value::number(number)),
+            Err(_) => return Ok(Value::Null),
+        }
+    }
+
+    r_error!(format!("{} does not have a method 'to_number'", receiver));
+}
@@ -0,0 +1,16 @@
+
+
+use crate::lox::common::Value;
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+// Return a copy without leading and trailing whitespace
+pub fn trim(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_string() {
+        return Ok(Value::string(receiver.as_string().trim()));
+    }
+
+    r_error!(format!("{} does not have a method 'trim'", receiver));
+}
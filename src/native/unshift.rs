@@ -1,6 +1,6 @@
 
 
-use crate::lox::common::{Value, Array};
+use crate::lox::common::{allocations, Value, Array};
 use crate::lox::vm::{RuntimeError, r_error};
 
 
@@ -11,6 +11,7 @@ pub fn unshift(args: &mut [Value]) -> Result<Value, RuntimeError> {
     if receiver.is_string() && element.is_number() {
         match char::from_u32(element.as_number() as u32) {
             Some(ch) => {
+                allocations::grow(ch.len_utf8());
                 let mut input = receiver.as_string_mut();
                 *input =  format!("{}{}", ch, input);
                 return Ok(Value::Null);
@@ -0,0 +1,16 @@
+
+
+use crate::lox::common::{Array, Value};
+use crate::lox::vm::{RuntimeError, r_error};
+
+
+pub fn values(args: &mut [Value]) -> Result<Value, RuntimeError> {
+    let receiver = &args[0];
+
+    if receiver.is_map() {
+        let values = receiver.as_map().values();
+        return Ok(Value::array(Array::from(values.as_slice())));
+    }
+
+    r_error!(format!("{} does not have a method 'values'", receiver));
+}